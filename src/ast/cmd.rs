@@ -1,4 +1,4 @@
-use super::commons::{Barcode1DKind, Justification, YesNo};
+use super::commons::{Barcode1DKind, Justification, PostalKind, YesNo};
 
 /// Represents the supported ZPL commands in the AST.
 #[derive(Debug, Clone)]
@@ -46,6 +46,13 @@ pub enum Command {
         y: Option<u32>,
     },
 
+    /// ^FM - Multiple Field Origin Locations
+    /// Sets the origins of the symbols a Macro PDF417 or MicroPDF417 field is split across.
+    FieldMultipleOrigin {
+        /// Symbol origins (in dots); `None` excludes that symbol (`e`)
+        origins: Vec<Option<(u32, u32)>>,
+    },
+
     /// ^FS - Field Separator
     /// Indicates the end of a field definition.
     FieldSeparator,
@@ -110,6 +117,17 @@ pub enum Command {
         indent: Option<u32>,
     },
 
+    /// ^TB - Text Block
+    /// Wraps text in a block of fixed width and height, without justification.
+    TextBlock {
+        /// Field orientation (N, R, I, B)
+        orientation: Option<char>,
+        /// Width of the text block
+        width: Option<u32>,
+        /// Height of the text block; lines beyond it are dropped
+        height: Option<u32>,
+    },
+
     /// ^CI - Change International Font/Encoding
     /// Changes the character set or international encoding.
     ChangeIntFont {
@@ -117,6 +135,13 @@ pub enum Command {
         charset: Option<u32>,
     },
 
+    /// ^FH - Field Hexadecimal Indicator
+    /// Enables hexadecimal escapes (e.g. `_1D`) in the next field data.
+    FieldHex {
+        /// Escape character (default `_`)
+        indicator: Option<char>,
+    },
+
     /// ^FR - Field Reverse Print
     /// Prints the field as white on black (inverted).
     FieldReverse,
@@ -209,6 +234,38 @@ pub enum Command {
         value: String,
     },
 
+    /// ^TFC - Text Fit Custom (Extension)
+    /// Custom command shrinking the current text field to the largest font
+    /// height, within a range, whose wrapped lines fit a box.
+    TextFit {
+        /// Box width
+        width: u32,
+        /// Box height
+        height: u32,
+        /// Minimum font height
+        min_height: u32,
+        /// Maximum font height
+        max_height: u32,
+    },
+
+    /// ^TDC - Text Decoration Custom (Extension)
+    /// Custom command to set the letter spacing, underline and strike-through
+    /// of the next text field.
+    TextDecoration {
+        /// Extra space between characters in dots (negative tightens)
+        letter_spacing: Option<i32>,
+        /// Underline (Y/N)
+        underline: Option<YesNo>,
+        /// Strike-through (Y/N)
+        strike_through: Option<YesNo>,
+    },
+
+    /// ^GSC - GS1 Data Custom
+    /// Custom command marking the current barcode field's data as GS1
+    /// Application Identifier data in bracketed notation, validated before
+    /// rendering.
+    Gs1Data,
+
     /// ^BC - Code 128 Barcode
     /// Code 128 Barcode (Subsets A, B, and C).
     Code128 {
@@ -220,7 +277,7 @@ pub enum Command {
         interpretation_line: Option<char>,
         /// Interpretation line above (Y/N)
         interpretation_line_above: Option<char>,
-        /// Verify check digit (Y/N)
+        /// UCC check digit (Y/N)
         check_digit: Option<char>,
         /// Mode (N=Not selected, U=UCC Case Mode, A=Automatic, D=UCC/EAN Display)
         mode: Option<char>,
@@ -256,8 +313,10 @@ pub enum Command {
         interpretation_line_above: Option<char>,
     },
 
-    /// Generic 1-D barcode commands sharing the `o,h,f,g,e` parameter shape:
-    /// `^BE` (EAN-13), `^BU` (UPC-A), `^B2` (Interleaved 2 of 5), `^BA` (Code 93).
+    /// Generic 1-D barcode commands: `^BE` (EAN-13), `^BU` (UPC-A),
+    /// `^B2` (Interleaved 2 of 5), `^BA` (Code 93), `^B8` (EAN-8), `^B9` (UPC-E),
+    /// `^BS` (UPC/EAN extensions), `^BK` (Codabar), `^BM` (MSI), `^BP` (Plessey),
+    /// `^B1` (Code 11), `^BJ`/`^BI` (Standard/Industrial 2 of 5) and `^BL` (LOGMARS).
     Barcode1D {
         /// Which symbology this command selects.
         kind: Barcode1DKind,
@@ -269,8 +328,73 @@ pub enum Command {
         interpretation_line: Option<char>,
         /// Interpretation line above (Y/N)
         interpretation_line_above: Option<char>,
-        /// Check digit (Y/N), where applicable
+        /// Check digit selection, where applicable (Y/N, or the `^BM` A-D scheme)
         check_digit: Option<char>,
+        /// Print the check digit in the interpretation line (Y/N): `^BM`, `^BP`
+        print_check_digit: Option<char>,
+        /// Codabar start character (A, B, C, D)
+        start_character: Option<char>,
+        /// Codabar stop character (A, B, C, D)
+        stop_character: Option<char>,
+    },
+
+    /// ^B0 / ^BO - Aztec Barcode
+    /// Aztec two-dimensional Barcode.
+    Aztec {
+        /// Orientation
+        orientation: Option<char>,
+        /// Magnification factor (1-10)
+        magnification: Option<u32>,
+        /// Data contains Extended Channel Interpretation codes (Y/N)
+        extended_channel: Option<char>,
+        /// Error control and symbol size: 0 (default), 1-99 (error correction
+        /// percentage), 101-104 (compact layers), 201-232 (full-range layers),
+        /// 300 (Aztec rune)
+        error_control: Option<u32>,
+    },
+
+    /// ^BD - MaxiCode Barcode
+    /// Fixed-size two-dimensional Barcode used by UPS.
+    MaxiCode {
+        /// Symbol mode (2-6)
+        mode: Option<u32>,
+        /// Symbol number for structured append (1-8)
+        symbol_number: Option<u32>,
+        /// Total number of symbols for structured append (1-8)
+        symbol_count: Option<u32>,
+    },
+
+    /// ^BR - GS1 DataBar
+    /// GS1 DataBar (RSS) family of linear and stacked Barcodes.
+    DataBar {
+        /// Orientation
+        orientation: Option<char>,
+        /// Symbology type: 1 (Omnidirectional), 2 (Truncated), 3 (Stacked),
+        /// 4 (Stacked Omnidirectional), 5 (Limited), 6 (Expanded), 7-12 (composites)
+        symbology: Option<u32>,
+        /// Magnification factor (module width in dots, 1-10)
+        magnification: Option<u32>,
+        /// Separator height in modules (1-2)
+        separator_height: Option<u32>,
+        /// Bar height in dots
+        height: Option<u32>,
+        /// Segment width for Expanded Stacked (even, 2-22)
+        segment_width: Option<u32>,
+    },
+
+    /// ^BZ / ^B5 - Postal Barcodes
+    /// POSTNET, PLANET and USPS Intelligent Mail barcodes.
+    PostalBarcode {
+        /// Which symbology this command selects.
+        kind: PostalKind,
+        /// Orientation (N, R, I, B)
+        orientation: Option<char>,
+        /// Full bar height in dots
+        height: Option<u32>,
+        /// Print interpretation line (Y/N)
+        interpretation_line: Option<char>,
+        /// Interpretation line above (Y/N)
+        interpretation_line_above: Option<char>,
     },
 
     /// ^GD - Graphic Diagonal Line
@@ -312,6 +436,12 @@ pub enum Command {
         columns: Option<u32>,
         /// Rows to encode
         rows: Option<u32>,
+        /// Format ID (ECC 000-140 only)
+        format: Option<u32>,
+        /// Escape sequence control character
+        escape: Option<char>,
+        /// Aspect ratio (1 = square, 2 = rectangular)
+        aspect_ratio: Option<u32>,
     },
 
     /// ^B7 - PDF417 Barcode
@@ -331,6 +461,51 @@ pub enum Command {
         truncate: Option<YesNo>,
     },
 
+    /// ^BF - MicroPDF417 Barcode
+    /// Multi-dimensional MicroPDF417 Barcode.
+    MicroPdf417 {
+        /// Orientation
+        orientation: Option<char>,
+        /// Bar height
+        height: Option<u32>,
+        /// Mode (0-33), selecting columns, rows and error correction
+        mode: Option<u32>,
+    },
+
+    /// ^BB - CODABLOCK Barcode
+    /// Multi-row CODABLOCK Barcode.
+    Codablock {
+        /// Orientation
+        orientation: Option<char>,
+        /// Bar height of each row
+        height: Option<u32>,
+        /// Security level (Y/N)
+        security: Option<YesNo>,
+        /// Number of characters per row
+        columns: Option<u32>,
+        /// Number of rows
+        rows: Option<u32>,
+        /// Mode (A, E or F)
+        mode: Option<char>,
+    },
+
+    /// ^BT - TLC39 Barcode
+    /// Code 39 and MicroPDF417 composite for telecommunications equipment.
+    Tlc39 {
+        /// Orientation
+        orientation: Option<char>,
+        /// Code 39 narrow bar width (1-10)
+        code39_width: Option<u32>,
+        /// Code 39 wide-to-narrow ratio (2.0-3.0)
+        ratio: Option<f32>,
+        /// Code 39 bar height
+        code39_height: Option<u32>,
+        /// MicroPDF417 narrow bar width (1-10)
+        micro_width: Option<u32>,
+        /// MicroPDF417 row height (1-255)
+        micro_height: Option<u32>,
+    },
+
     /// Unsupported or unknown command
     UnsupportedCommand {
         /// Command code (e.g., ^XY)
@@ -9,6 +9,47 @@ pub enum Barcode1DKind {
     Interleaved2of5,
     /// `^BA` — Code 93.
     Code93,
+    /// `^B8` — EAN-8 (small retail items).
+    Ean8,
+    /// `^B9` — UPC-E (zero-suppressed UPC).
+    UpcE,
+    /// `^BS` — UPC/EAN two- or five-digit add-on.
+    UpcEanExtension,
+    /// `^BK` — Codabar (libraries, blood banks).
+    Codabar,
+    /// `^BM` — MSI (shelf labels).
+    Msi,
+    /// `^BP` — Plessey.
+    Plessey,
+    /// `^B1` — Code 11 (telecom equipment).
+    Code11,
+    /// `^BJ` — Standard 2 of 5.
+    Standard2of5,
+    /// `^BI` — Industrial 2 of 5.
+    Industrial2of5,
+    /// `^BL` — LOGMARS (Code 39 with a mandatory Mod 43 check digit).
+    Logmars,
+}
+
+impl Barcode1DKind {
+    /// Whether this is a UPC/EAN retail symbol that add-ons can follow.
+    pub fn is_upc_ean(self) -> bool {
+        matches!(
+            self,
+            Barcode1DKind::Ean13 | Barcode1DKind::UpcA | Barcode1DKind::Ean8 | Barcode1DKind::UpcE
+        )
+    }
+}
+
+/// Height-modulated postal symbologies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PostalKind {
+    /// `^BZ` type 0 — USPS POSTNET.
+    Postnet,
+    /// `^B5`, `^BZ` type 1 — USPS PLANET.
+    Planet,
+    /// `^BZ` type 3 — USPS Intelligent Mail (4-state).
+    IntelligentMail,
 }
 
 /// Represents text justification options in ZPL.
@@ -4,8 +4,8 @@ use nom::{
     combinator::{cut, map_res},
 };
 
-use super::{Res, Span, param, parse_u32};
-use crate::ast::cmd;
+use super::{Res, Span, opt_param, param, parse_char, parse_i32, parse_u32};
+use crate::ast::{cmd, commons::YesNo};
 
 /// ^GIC - Custom Image Color
 /// Format: ^GIC<width>,<height>,<base64>
@@ -56,6 +56,25 @@ pub fn cmd_gtc(input: Span) -> Res<cmd::Command> {
     ))
 }
 
+/// ^TDC - Text Decoration Custom
+/// Format: ^TDC<letter_spacing>,<underline>,<strike_through>
+/// Omitted parameters reset to no spacing and no lines.
+pub fn cmd_tdc(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^TDC").parse(input)?;
+    let (input, letter_spacing) = opt_param(parse_i32).parse(input)?;
+    let (input, underline) = param(parse_char).parse(input).unwrap_or((input, None));
+    let (input, strike_through) = param(parse_char).parse(input).unwrap_or((input, None));
+
+    Ok((
+        input,
+        cmd::Command::TextDecoration {
+            letter_spacing,
+            underline: underline.map(YesNo::from),
+            strike_through: strike_through.map(YesNo::from),
+        },
+    ))
+}
+
 /// ^GLC - Custom Line Color
 /// Format: ^GLC<hex_color>
 pub fn cmd_glc(input: Span) -> Res<cmd::Command> {
@@ -70,6 +89,44 @@ pub fn cmd_glc(input: Span) -> Res<cmd::Command> {
     ))
 }
 
+/// ^GSC - GS1 Data Custom
+/// Format: ^GSC
+pub fn cmd_gsc(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^GSC").parse(input)?;
+    let (input, _) = take_till(|c| c == '^').parse(input)?;
+
+    Ok((input, cmd::Command::Gs1Data))
+}
+
+/// ^TFC - Text Fit Custom
+/// Format: ^TFC<width>,<height>,<min_height>,<max_height>
+/// All parameters are mandatory.
+pub fn cmd_tfc(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^TFC").parse(input)?;
+
+    let (input, width) = cut(parse_u32).parse(input)?;
+    let mut mandatory = cut(map_res(param(parse_u32), |opt| {
+        opt.ok_or("parameter is mandatory")
+    }));
+    let (input, height) = mandatory.parse(input)?;
+    let (input, min_height) = mandatory.parse(input)?;
+    let (input, max_height) = cut(map_res(param(parse_u32), |opt| match opt {
+        Some(max) if max >= min_height && min_height > 0 => Ok(max),
+        _ => Err("heights must satisfy 0 < min <= max"),
+    }))
+    .parse(input)?;
+
+    Ok((
+        input,
+        cmd::Command::TextFit {
+            width,
+            height,
+            min_height,
+            max_height,
+        },
+    ))
+}
+
 /// ^IFC - If Condition
 /// Format: ^IFC<variable>,<value>
 pub fn cmd_ifc(input: Span) -> Res<cmd::Command> {
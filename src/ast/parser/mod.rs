@@ -4,7 +4,7 @@ use nom::{
     bytes::complete::{tag, take, take_till},
     character::complete::{digit1, multispace0, none_of},
     combinator::{all_consuming, map_res, opt, recognize},
-    error::Error,
+    error::{Error, ErrorKind},
     multi::many0,
     sequence::delimited,
 };
@@ -47,7 +47,10 @@ pub fn parse_zpl(input: &str) -> ZplResult<Vec<cmd::Command>> {
                 standard::cmd_cf,
                 standard::cmd_fd,
                 standard::cmd_fb,
+                standard::cmd_tb,
                 standard::cmd_fr,
+                standard::cmd_fh,
+                standard::cmd_fm,
             )),
             alt((
                 standard::cmd_gb,
@@ -59,16 +62,42 @@ pub fn parse_zpl(input: &str) -> ZplResult<Vec<cmd::Command>> {
                 standard::cmd_b2,
                 standard::cmd_b3,
                 standard::cmd_b7,
+                standard::cmd_b8,
+                standard::cmd_b9,
                 standard::cmd_ba,
                 standard::cmd_be,
+                standard::cmd_bs,
                 standard::cmd_bu,
                 standard::cmd_by,
                 standard::cmd_bx,
                 standard::cmd_bc,
+            )),
+            alt((
+                standard::cmd_b1,
+                standard::cmd_bi,
+                standard::cmd_bj,
+                standard::cmd_bk,
+                standard::cmd_bl,
+                standard::cmd_bm,
+                standard::cmd_bp,
+                standard::cmd_bz,
+                standard::cmd_b5,
+                standard::cmd_b0,
+                standard::cmd_bo,
+                standard::cmd_bd,
+                standard::cmd_br,
+                standard::cmd_bf,
+                standard::cmd_bb,
+                standard::cmd_bt,
+            )),
+            alt((
                 custom::cmd_gic,
                 custom::cmd_gtc,
                 custom::cmd_glc,
                 custom::cmd_ifc,
+                custom::cmd_gsc,
+                custom::cmd_tfc,
+                custom::cmd_tdc,
                 cmd_unsupported,
             )),
         )),
@@ -81,13 +110,16 @@ pub fn parse_zpl(input: &str) -> ZplResult<Vec<cmd::Command>> {
             let offset = input.len() - e.input.len();
             let line = input[..offset].chars().filter(|&c| c == '\n').count() + 1;
 
-            Err(ZplError::ParseError {
-                line,
-                message: format!(
-                    "Invalid or malformed ZPL command (Error code: {:?})",
-                    e.code
-                ),
-            })
+            // Commands fail with `Verify` on parameters they parse but cannot
+            // render; name the command so the caller knows which.
+            let message = match e.code {
+                ErrorKind::Verify => {
+                    let end = e.input[1..].find('^').map_or(e.input.len(), |i| i + 1);
+                    format!("Unsupported parameters in {}", e.input[..end].trim())
+                }
+                code => format!("Invalid or malformed ZPL command (Error code: {:?})", code),
+            };
+            Err(ZplError::ParseError { line, message })
         }
         Err(nom::Err::Incomplete(_)) => Err(ZplError::EmptyInput),
     }
@@ -122,6 +154,14 @@ pub fn parse_u32(input: Span) -> Res<u32> {
     map_res(digit1, |s: Span| s.parse::<u32>()).parse(input)
 }
 
+/// Parses a signed 32-bit integer, with an optional leading `-`.
+pub fn parse_i32(input: Span) -> Res<i32> {
+    map_res(recognize((opt(tag("-")), digit1)), |s: Span| {
+        s.parse::<i32>()
+    })
+    .parse(input)
+}
+
 /// Parses a 32-bit floating-point number, accepting both integer and decimal forms (e.g., `3` or `2.5`).
 pub fn parse_f32(input: Span) -> Res<f32> {
     map_res(recognize((digit1, opt((tag("."), digit1)))), |s: Span| {
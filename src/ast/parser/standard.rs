@@ -2,11 +2,12 @@ use nom::{
     Parser,
     bytes::complete::{tag, take_till},
     combinator::{cut, map, opt},
+    error::{Error, ErrorKind},
 };
 
 use super::{Res, Span, opt_param, param, parse_char, parse_f32, parse_u32, parse_xy};
 use crate::ast::cmd;
-use crate::ast::commons::{Barcode1DKind, Justification, YesNo};
+use crate::ast::commons::{Barcode1DKind, Justification, PostalKind, YesNo};
 
 /// ^XA - Start Format
 pub fn cmd_xa(input: Span) -> Res<cmd::Command> {
@@ -46,6 +47,27 @@ pub fn cmd_ft(input: Span) -> Res<cmd::Command> {
     Ok((input, cmd::Command::FieldTypeset { x, y }))
 }
 
+/// ^FM - Multiple Field Origin Locations
+pub fn cmd_fm(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^FM").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let values: Vec<&str> = args.split(',').map(str::trim).collect();
+    // Any pair that is not two coordinates (e.g. `e,e`) excludes its symbol.
+    let origins = if args.trim().is_empty() {
+        Vec::new()
+    } else {
+        values
+            .chunks(2)
+            .take(60)
+            .map(|pair| match pair {
+                [x, y] => x.parse().ok().zip(y.parse().ok()),
+                _ => None,
+            })
+            .collect()
+    };
+    Ok((rest, cmd::Command::FieldMultipleOrigin { origins }))
+}
+
 /// ^FS - Field Separator
 pub fn cmd_fs(input: Span) -> Res<cmd::Command> {
     map(tag("^FS"), |_| cmd::Command::FieldSeparator).parse(input)
@@ -144,6 +166,30 @@ pub fn cmd_fb(input: Span) -> Res<cmd::Command> {
     ))
 }
 
+/// ^TB - Text Block
+pub fn cmd_tb(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^TB").parse(input)?;
+    let (input, orientation) = opt_param(parse_char).parse(input)?;
+    let (input, width) = param(parse_u32).parse(input).unwrap_or((input, None));
+    let (input, height) = param(parse_u32).parse(input).unwrap_or((input, None));
+
+    Ok((
+        input,
+        cmd::Command::TextBlock {
+            orientation,
+            width,
+            height,
+        },
+    ))
+}
+
+/// ^FH - Field Hexadecimal Indicator
+pub fn cmd_fh(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^FH").parse(input)?;
+    let (input, indicator) = opt_param(parse_char).parse(input)?;
+    Ok((input, cmd::Command::FieldHex { indicator }))
+}
+
 /// ^FR - Field Reverse Print
 pub fn cmd_fr(input: Span) -> Res<cmd::Command> {
     map(tag("^FR"), |_| cmd::Command::FieldReverse).parse(input)
@@ -359,7 +405,16 @@ pub fn cmd_bx(input: Span) -> Res<cmd::Command> {
     let (args_input, columns) = param(parse_u32)
         .parse(args_input)
         .unwrap_or((args_input, None));
-    let (_, rows) = param(parse_u32)
+    let (args_input, rows) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, format) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, escape) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, aspect_ratio) = param(parse_u32)
         .parse(args_input)
         .unwrap_or((args_input, None));
 
@@ -371,6 +426,9 @@ pub fn cmd_bx(input: Span) -> Res<cmd::Command> {
             quality,
             columns,
             rows,
+            format,
+            escape,
+            aspect_ratio,
         },
     ))
 }
@@ -401,10 +459,59 @@ fn parse_barcode_1d(input: Span, kind: Barcode1DKind) -> Res<cmd::Command> {
             interpretation_line,
             interpretation_line_above,
             check_digit,
+            print_check_digit: None,
+            start_character: None,
+            stop_character: None,
         },
     ))
 }
 
+/// Shared parser body for 1-D barcodes with the `o,e,h,f,g` parameter shape,
+/// plus any trailing symbology-specific parameters, which are returned as is.
+fn parse_barcode_1d_check_first(
+    input: Span,
+    kind: Barcode1DKind,
+) -> Res<(cmd::Command, [Option<char>; 2])> {
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, check_digit) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, interpretation_line) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, interpretation_line_above) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, extra_1) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, extra_2) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        (
+            cmd::Command::Barcode1D {
+                kind,
+                orientation,
+                height,
+                interpretation_line,
+                interpretation_line_above,
+                check_digit,
+                print_check_digit: None,
+                start_character: None,
+                stop_character: None,
+            },
+            [extra_1, extra_2],
+        ),
+    ))
+}
+
 /// ^BE - EAN-13 Barcode
 pub fn cmd_be(input: Span) -> Res<cmd::Command> {
     let (input, _) = tag("^BE").parse(input)?;
@@ -429,6 +536,372 @@ pub fn cmd_ba(input: Span) -> Res<cmd::Command> {
     parse_barcode_1d(input, Barcode1DKind::Code93)
 }
 
+/// ^B8 - EAN-8 Barcode
+pub fn cmd_b8(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^B8").parse(input)?;
+    parse_barcode_1d(input, Barcode1DKind::Ean8)
+}
+
+/// ^B9 - UPC-E Barcode
+pub fn cmd_b9(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^B9").parse(input)?;
+    parse_barcode_1d(input, Barcode1DKind::UpcE)
+}
+
+/// ^BS - UPC/EAN Extensions
+pub fn cmd_bs(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BS").parse(input)?;
+    parse_barcode_1d(input, Barcode1DKind::UpcEanExtension)
+}
+
+/// ^BK - ANSI Codabar Barcode
+pub fn cmd_bk(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BK").parse(input)?;
+    let (rest, (mut command, [start, stop])) =
+        parse_barcode_1d_check_first(input, Barcode1DKind::Codabar)?;
+    if let cmd::Command::Barcode1D {
+        start_character,
+        stop_character,
+        ..
+    } = &mut command
+    {
+        *start_character = start;
+        *stop_character = stop;
+    }
+    Ok((rest, command))
+}
+
+/// ^BM - MSI Barcode
+pub fn cmd_bm(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BM").parse(input)?;
+    let (rest, (mut command, [print, _])) =
+        parse_barcode_1d_check_first(input, Barcode1DKind::Msi)?;
+    if let cmd::Command::Barcode1D {
+        print_check_digit, ..
+    } = &mut command
+    {
+        *print_check_digit = print;
+    }
+    Ok((rest, command))
+}
+
+/// ^BP - Plessey Barcode
+pub fn cmd_bp(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BP").parse(input)?;
+    let (rest, (mut command, _)) = parse_barcode_1d_check_first(input, Barcode1DKind::Plessey)?;
+    // Plessey always encodes its CRC; the `e` parameter only controls printing it.
+    if let cmd::Command::Barcode1D {
+        check_digit,
+        print_check_digit,
+        ..
+    } = &mut command
+    {
+        *print_check_digit = check_digit.take();
+    }
+    Ok((rest, command))
+}
+
+/// ^B1 - Code 11 Barcode
+pub fn cmd_b1(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^B1").parse(input)?;
+    let (rest, (command, _)) = parse_barcode_1d_check_first(input, Barcode1DKind::Code11)?;
+    Ok((rest, command))
+}
+
+/// ^BJ - Standard 2 of 5 Barcode
+pub fn cmd_bj(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BJ").parse(input)?;
+    parse_barcode_1d(input, Barcode1DKind::Standard2of5)
+}
+
+/// ^BI - Industrial 2 of 5 Barcode
+pub fn cmd_bi(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BI").parse(input)?;
+    parse_barcode_1d(input, Barcode1DKind::Industrial2of5)
+}
+
+/// ^BL - LOGMARS Barcode
+pub fn cmd_bl(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BL").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, interpretation_line_above) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::Barcode1D {
+            kind: Barcode1DKind::Logmars,
+            orientation,
+            height,
+            // LOGMARS has no `f` parameter: the interpretation line is always printed.
+            interpretation_line: Some('Y'),
+            interpretation_line_above,
+            check_digit: None,
+            print_check_digit: None,
+            start_character: None,
+            stop_character: None,
+        },
+    ))
+}
+
+/// Shared parser body for `^B0` and `^BO`, which are aliases; `code` is
+/// the command being parsed.
+///
+/// Menu symbols, structured append (a symbol count above 1) and Aztec runes
+/// (error control 300) are rejected: the encoder cannot write the header
+/// bits these symbols need, so they fail here instead of at render time.
+fn parse_aztec<'a>(input: Span<'a>, code: &str) -> Res<'a, cmd::Command> {
+    let (after, _) = tag(code).parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(after)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, magnification) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, extended_channel) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, error_control) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, menu_symbol) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, symbol_count) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    // The structured append message ID that may follow is only meaningful
+    // with a symbol count, so it is left unparsed.
+    if menu_symbol == Some('Y') || symbol_count.is_some_and(|n| n > 1) || error_control == Some(300)
+    {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)));
+    }
+
+    Ok((
+        rest,
+        cmd::Command::Aztec {
+            orientation,
+            magnification,
+            extended_channel,
+            error_control,
+        },
+    ))
+}
+
+/// ^B0 - Aztec Barcode
+pub fn cmd_b0(input: Span) -> Res<cmd::Command> {
+    parse_aztec(input, "^B0")
+}
+
+/// ^BO - Aztec Barcode
+pub fn cmd_bo(input: Span) -> Res<cmd::Command> {
+    parse_aztec(input, "^BO")
+}
+
+/// ^BD - MaxiCode Barcode
+pub fn cmd_bd(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BD").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, mode) = opt_param(parse_u32).parse(args)?;
+    let (args_input, symbol_number) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, symbol_count) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::MaxiCode {
+            mode,
+            symbol_number,
+            symbol_count,
+        },
+    ))
+}
+
+/// ^BF - MicroPDF417 Barcode
+pub fn cmd_bf(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BF").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, mode) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::MicroPdf417 {
+            orientation,
+            height,
+            mode,
+        },
+    ))
+}
+
+/// ^BT - TLC39 Barcode
+pub fn cmd_bt(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BT").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, code39_width) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, ratio) = param(parse_f32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, code39_height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, micro_width) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, micro_height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::Tlc39 {
+            orientation,
+            code39_width,
+            ratio,
+            code39_height,
+            micro_width,
+            micro_height,
+        },
+    ))
+}
+
+/// ^BB - CODABLOCK Barcode
+pub fn cmd_bb(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BB").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, security) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, columns) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, rows) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, mode) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::Codablock {
+            orientation,
+            height,
+            security: security.map(YesNo::from),
+            columns,
+            rows,
+            mode,
+        },
+    ))
+}
+
+/// ^BR - GS1 DataBar
+pub fn cmd_br(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BR").parse(input)?;
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, symbology) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, magnification) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, separator_height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (_, segment_width) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        cmd::Command::DataBar {
+            orientation,
+            symbology,
+            magnification,
+            separator_height,
+            height,
+            segment_width,
+        },
+    ))
+}
+
+/// Shared parser body for postal barcodes with the `o,h,f,g` parameter shape.
+/// Returns the command (with `default_kind`) and the trailing parameter text.
+fn parse_postal(input: Span, default_kind: PostalKind) -> Res<(cmd::Command, Span)> {
+    let (rest, args) = cut(take_till(|c| c == '^')).parse(input)?;
+    let (args_input, orientation) = opt_param(parse_char).parse(args)?;
+    let (args_input, height) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, interpretation_line) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    let (args_input, interpretation_line_above) = param(parse_char)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+
+    Ok((
+        rest,
+        (
+            cmd::Command::PostalBarcode {
+                kind: default_kind,
+                orientation,
+                height,
+                interpretation_line,
+                interpretation_line_above,
+            },
+            args_input,
+        ),
+    ))
+}
+
+/// ^BZ - POSTAL Barcode
+pub fn cmd_bz(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^BZ").parse(input)?;
+    let (rest, (mut command, args_input)) = parse_postal(input, PostalKind::Postnet)?;
+    let (_, code_type) = param(parse_u32)
+        .parse(args_input)
+        .unwrap_or((args_input, None));
+    if let cmd::Command::PostalBarcode { kind, .. } = &mut command {
+        *kind = match code_type {
+            Some(1) => PostalKind::Planet,
+            Some(3) => PostalKind::IntelligentMail,
+            _ => PostalKind::Postnet,
+        };
+    }
+    Ok((rest, command))
+}
+
+/// ^B5 - Planet Code Barcode
+pub fn cmd_b5(input: Span) -> Res<cmd::Command> {
+    let (input, _) = tag("^B5").parse(input)?;
+    let (rest, (command, _)) = parse_postal(input, PostalKind::Planet)?;
+    Ok((rest, command))
+}
+
 /// ^GD - Graphic Diagonal Line
 pub fn cmd_gd(input: Span) -> Res<cmd::Command> {
     let (input, _) = tag("^GD").parse(input)?;
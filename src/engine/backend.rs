@@ -1,4 +1,4 @@
-use crate::engine::common::Barcode1DKind;
+use crate::engine::common::{Barcode1DKind, PostalKind, QrSegment, TextDecoration};
 use crate::{FontManager, ZplResult};
 
 /// Defines the interface for rendering ZPL instructions.
@@ -27,6 +27,7 @@ pub trait ZplForgeBackend {
     /// `orientation` follows `^A`: 'N' (normal), 'R' (rotated 90° clockwise),
     /// 'I' (inverted 180°), 'B' (read from bottom up, 270° clockwise).
     /// `(x, y)` is always the top-left corner of the rendered (rotated) cell.
+    /// `decoration` carries the `^TDC` letter spacing and lines.
     fn draw_text(
         &mut self,
         x: u32,
@@ -38,6 +39,7 @@ pub trait ZplForgeBackend {
         text: &str,
         reverse_print: bool,
         color: Option<String>,
+        decoration: TextDecoration,
     ) -> ZplResult<()>;
 
     /// Draws a rectangular box.
@@ -104,6 +106,14 @@ pub trait ZplForgeBackend {
     ) -> ZplResult<()>;
 
     /// Draws a Code 128 barcode.
+    ///
+    /// `mode` is the `^BC` mode: `N` follows the Zebra invocation codes in
+    /// `data` (`>9`/`>:`/`>;` start codes, `>5`-`>7` subset switches, `>8`
+    /// FNC1, ...), `A` picks subsets automatically, `U` encodes a UCC case
+    /// code (19 digits plus a Mod 10 check digit) and `D` encodes `(AI)data`
+    /// as GS1-128 with the AI parentheses in the interpretation line.
+    /// `check_digit` `'Y'` appends a Mod 10 check digit to numeric data in
+    /// modes `N` and `A`.
     fn draw_code128(
         &mut self,
         x: u32,
@@ -120,6 +130,14 @@ pub trait ZplForgeBackend {
     ) -> ZplResult<()>;
 
     /// Draws a QR Code.
+    ///
+    /// `data` is encoded in automatic mode unless `segments` (manual mode,
+    /// from the `^FD` prefix) is not empty. `model` 1 draws an original
+    /// Model 1 symbol. `structured_append` is `(symbol number, symbol count,
+    /// parity)` for one symbol of a mixed-mode (`D`) sequence. `gs1` encodes
+    /// automatic-mode `data` in bracketed AI notation as a GS1 QR Code
+    /// (FNC1 in first position).
+    #[allow(clippy::too_many_arguments)]
     fn draw_qr_code(
         &mut self,
         x: u32,
@@ -130,10 +148,17 @@ pub trait ZplForgeBackend {
         error_correction: char,
         mask: u32,
         data: &str,
+        segments: &[QrSegment],
+        structured_append: Option<(u32, u32, u8)>,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()>;
 
-    /// Draws a generic 1-D barcode (EAN-13, UPC-A, Interleaved 2 of 5, Code 93).
+    /// Draws a generic 1-D barcode (see [`Barcode1DKind`]).
+    ///
+    /// `ratio` is the `^BY` wide-to-narrow ratio used by two-width symbologies;
+    /// `check_digit` and `print_check_digit` carry the symbology's check digit
+    /// options as given by its command.
     #[allow(clippy::too_many_arguments)]
     fn draw_barcode_1d(
         &mut self,
@@ -143,6 +168,89 @@ pub trait ZplForgeBackend {
         orientation: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
+        interpretation_line: char,
+        interpretation_line_above: char,
+        check_digit: char,
+        print_check_digit: char,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws an Aztec barcode.
+    ///
+    /// `error_control` follows `^B0`: 0 for the default error correction,
+    /// 1-99 for a minimum error correction percentage, 101-104 for a compact
+    /// symbol with that many layers (minus 100) and 201-232 for a full-range
+    /// symbol (minus 200). Menu symbols, structured append and runes are
+    /// rejected by the parser.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_aztec(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        magnification: u32,
+        extended_channel: char,
+        error_control: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a MaxiCode barcode (`^BD`).
+    ///
+    /// MaxiCode has a fixed physical size (about 1.11 x 1.05 in), so the
+    /// module size follows the page resolution rather than `^BY`. In modes
+    /// 2 and 3, `data` starts with the structured carrier message (class of
+    /// service, country code, postal code) or an `[)>` RS `01` GS `yy`
+    /// transportation header carrying the same fields.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_maxicode(
+        &mut self,
+        x: u32,
+        y: u32,
+        mode: u32,
+        symbol_number: u32,
+        symbol_count: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a GS1 DataBar barcode (`^BR`).
+    ///
+    /// `symbology` is the `^BR` type: 1 Omnidirectional, 2 Truncated,
+    /// 3 Stacked, 4 Stacked Omnidirectional, 5 Limited, 6 Expanded and 7-12
+    /// the composite variants. `height` applies to the linear types and to
+    /// each Expanded row; the other stacked types use their fixed row heights
+    /// in modules. Expanded data wraps into stacked rows of `segment_width`
+    /// characters when it does not fit in one.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_databar(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        symbology: u32,
+        magnification: u32,
+        separator_height: u32,
+        height: u32,
+        segment_width: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a height-modulated postal barcode (POSTNET, PLANET, Intelligent Mail).
+    ///
+    /// `height` is the full bar height; `module_width` is the bar width.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_postal_barcode(
+        &mut self,
+        kind: PostalKind,
+        x: u32,
+        y: u32,
+        orientation: char,
+        height: u32,
+        module_width: u32,
         interpretation_line: char,
         interpretation_line_above: char,
         data: &str,
@@ -166,22 +274,45 @@ pub trait ZplForgeBackend {
 
     /// Draws a Data Matrix (ECC 200) barcode.
     ///
-    /// `module_size` is the side of each module cell in dots.
+    /// `module_size` is the side of each module cell in dots. `quality` is
+    /// the `^BX` quality level; the bundled backends ignore it and always
+    /// encode ECC 200, so the legacy levels 000-140 draw ECC 200 symbols too.
+    /// `columns` and `rows` fix the symbol size (0 derives it from the data,
+    /// square unless `rectangular`). `escape` introduces control sequences
+    /// in the data, e.g. `_1` for FNC1 (a leading FNC1 marks GS1 data) or
+    /// `_5NNN` for ECI `NNN` when it is `_`. `gs1` encodes `data` in
+    /// bracketed AI notation as GS1 Data Matrix instead, without escape
+    /// sequences.
+    #[allow(clippy::too_many_arguments)]
     fn draw_datamatrix(
         &mut self,
         x: u32,
         y: u32,
         orientation: char,
         module_size: u32,
+        quality: u32,
+        columns: u32,
+        rows: u32,
+        rectangular: bool,
+        escape: char,
         data: &str,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()>;
 
     /// Draws a PDF417 barcode.
     ///
-    /// `row_height` is the bar height per matrix row and `module_width` the
-    /// narrow bar width, both in dots. `security_level` is the PDF417 error
-    /// correction level (0-8).
+    /// `row_height` is the bar height of each symbol row and `module_width`
+    /// the narrow bar width, both in dots. `security_level` is the PDF417
+    /// error correction level (0-8). `columns` (1-30) and `rows` (3-90) fix
+    /// the symbol shape, 0 deriving one from the other (or both from the
+    /// data); `truncate` drops the right row indicators and stop pattern.
+    ///
+    /// When `macro_origins` (`^FM`) is not empty, the data is split into
+    /// Macro PDF417 segments drawn at those origins instead of `x`/`y`; each
+    /// symbol is filled before the next one starts, and `None` entries are
+    /// encoded but not printed.
+    #[allow(clippy::too_many_arguments)]
     fn draw_pdf417(
         &mut self,
         x: u32,
@@ -190,11 +321,80 @@ pub trait ZplForgeBackend {
         row_height: u32,
         module_width: u32,
         security_level: u32,
+        columns: u32,
+        rows: u32,
+        truncate: bool,
+        macro_origins: &[Option<(u32, u32)>],
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a MicroPDF417 barcode (`^BF`).
+    ///
+    /// `mode` (0-33) selects the columns, rows and error correction level;
+    /// `row_height` and `module_width` work as in
+    /// [`draw_pdf417`](Self::draw_pdf417), and so do `macro_origins`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_micro_pdf417(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        mode: u32,
+        macro_origins: &[Option<(u32, u32)>],
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a TLC39 composite barcode (`^BT`).
+    ///
+    /// The first six characters of `data` are the ECI number, drawn as Code
+    /// 39 with `module_width`, `ratio` and `height`. When a comma follows,
+    /// the rest is drawn as a MicroPDF417 above it with `micro_module_width`
+    /// and `row_height`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_tlc39(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        module_width: u32,
+        ratio: f64,
+        height: u32,
+        micro_module_width: u32,
+        row_height: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()>;
+
+    /// Draws a CODABLOCK barcode (`^BB`).
+    ///
+    /// `security` 'Y' adds the symbol check characters; `columns` (characters
+    /// per row) and `rows` are 0 when chosen automatically. `mode` is 'A'
+    /// (Code 39 based), 'E' (Code 128 with FNC1) or 'F' (Code 128); the
+    /// bundled backends draw modes E and F.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_codablock(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        security: char,
+        columns: u32,
+        rows: u32,
+        mode: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()>;
 
     /// Draws a Code 39 barcode.
+    ///
+    /// `ratio` is the `^BY` wide-to-narrow ratio.
+    #[allow(clippy::too_many_arguments)]
     fn draw_code39(
         &mut self,
         x: u32,
@@ -203,6 +403,7 @@ pub trait ZplForgeBackend {
         check_digit: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
         data: &str,
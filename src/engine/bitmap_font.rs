@@ -0,0 +1,125 @@
+//! Dot-matrix glyphs of the Zebra bitmap font A.
+//!
+//! This is the only bitmap font with a glyph table; fonts B-H keep
+//! drawing the outline font registered on their identifier.
+//!
+//! Font A is a 5 × 9 matrix: capitals span the top seven rows and
+//! lowercase descenders the last two. The glyphs follow the classic 5 × 7
+//! dot-matrix character set, with true descenders for `g`, `j`, `p`, `q`
+//! and `y`.
+
+/// Font A glyphs for ASCII 0x20-0x7E, as five columns of nine rows each
+/// (bit 0 is the top row).
+const FONT_A: [[u16; 5]; 95] = [
+    [0x000, 0x000, 0x000, 0x000, 0x000], // ' '
+    [0x000, 0x000, 0x05F, 0x000, 0x000], // '!'
+    [0x000, 0x007, 0x000, 0x007, 0x000], // '"'
+    [0x014, 0x07F, 0x014, 0x07F, 0x014], // '#'
+    [0x024, 0x02A, 0x07F, 0x02A, 0x012], // '$'
+    [0x023, 0x013, 0x008, 0x064, 0x062], // '%'
+    [0x036, 0x049, 0x055, 0x022, 0x050], // '&'
+    [0x000, 0x005, 0x003, 0x000, 0x000], // '''
+    [0x000, 0x01C, 0x022, 0x041, 0x000], // '('
+    [0x000, 0x041, 0x022, 0x01C, 0x000], // ')'
+    [0x008, 0x02A, 0x01C, 0x02A, 0x008], // '*'
+    [0x008, 0x008, 0x03E, 0x008, 0x008], // '+'
+    [0x000, 0x050, 0x030, 0x000, 0x000], // ','
+    [0x008, 0x008, 0x008, 0x008, 0x008], // '-'
+    [0x000, 0x060, 0x060, 0x000, 0x000], // '.'
+    [0x020, 0x010, 0x008, 0x004, 0x002], // '/'
+    [0x03E, 0x051, 0x049, 0x045, 0x03E], // '0'
+    [0x000, 0x042, 0x07F, 0x040, 0x000], // '1'
+    [0x042, 0x061, 0x051, 0x049, 0x046], // '2'
+    [0x021, 0x041, 0x045, 0x04B, 0x031], // '3'
+    [0x018, 0x014, 0x012, 0x07F, 0x010], // '4'
+    [0x027, 0x045, 0x045, 0x045, 0x039], // '5'
+    [0x03C, 0x04A, 0x049, 0x049, 0x030], // '6'
+    [0x001, 0x071, 0x009, 0x005, 0x003], // '7'
+    [0x036, 0x049, 0x049, 0x049, 0x036], // '8'
+    [0x006, 0x049, 0x049, 0x029, 0x01E], // '9'
+    [0x000, 0x036, 0x036, 0x000, 0x000], // ':'
+    [0x000, 0x056, 0x036, 0x000, 0x000], // ';'
+    [0x008, 0x014, 0x022, 0x041, 0x000], // '<'
+    [0x014, 0x014, 0x014, 0x014, 0x014], // '='
+    [0x000, 0x041, 0x022, 0x014, 0x008], // '>'
+    [0x002, 0x001, 0x051, 0x009, 0x006], // '?'
+    [0x032, 0x049, 0x079, 0x041, 0x03E], // '@'
+    [0x07E, 0x011, 0x011, 0x011, 0x07E], // 'A'
+    [0x07F, 0x049, 0x049, 0x049, 0x036], // 'B'
+    [0x03E, 0x041, 0x041, 0x041, 0x022], // 'C'
+    [0x07F, 0x041, 0x041, 0x022, 0x01C], // 'D'
+    [0x07F, 0x049, 0x049, 0x049, 0x041], // 'E'
+    [0x07F, 0x009, 0x009, 0x009, 0x001], // 'F'
+    [0x03E, 0x041, 0x049, 0x049, 0x07A], // 'G'
+    [0x07F, 0x008, 0x008, 0x008, 0x07F], // 'H'
+    [0x000, 0x041, 0x07F, 0x041, 0x000], // 'I'
+    [0x020, 0x040, 0x041, 0x03F, 0x001], // 'J'
+    [0x07F, 0x008, 0x014, 0x022, 0x041], // 'K'
+    [0x07F, 0x040, 0x040, 0x040, 0x040], // 'L'
+    [0x07F, 0x002, 0x00C, 0x002, 0x07F], // 'M'
+    [0x07F, 0x004, 0x008, 0x010, 0x07F], // 'N'
+    [0x03E, 0x041, 0x041, 0x041, 0x03E], // 'O'
+    [0x07F, 0x009, 0x009, 0x009, 0x006], // 'P'
+    [0x03E, 0x041, 0x051, 0x021, 0x05E], // 'Q'
+    [0x07F, 0x009, 0x019, 0x029, 0x046], // 'R'
+    [0x046, 0x049, 0x049, 0x049, 0x031], // 'S'
+    [0x001, 0x001, 0x07F, 0x001, 0x001], // 'T'
+    [0x03F, 0x040, 0x040, 0x040, 0x03F], // 'U'
+    [0x01F, 0x020, 0x040, 0x020, 0x01F], // 'V'
+    [0x03F, 0x040, 0x038, 0x040, 0x03F], // 'W'
+    [0x063, 0x014, 0x008, 0x014, 0x063], // 'X'
+    [0x007, 0x008, 0x070, 0x008, 0x007], // 'Y'
+    [0x061, 0x051, 0x049, 0x045, 0x043], // 'Z'
+    [0x000, 0x07F, 0x041, 0x041, 0x000], // '['
+    [0x002, 0x004, 0x008, 0x010, 0x020], // '\'
+    [0x000, 0x041, 0x041, 0x07F, 0x000], // ']'
+    [0x004, 0x002, 0x001, 0x002, 0x004], // '^'
+    [0x100, 0x100, 0x100, 0x100, 0x100], // '_'
+    [0x000, 0x001, 0x002, 0x004, 0x000], // '`'
+    [0x020, 0x054, 0x054, 0x054, 0x078], // 'a'
+    [0x07F, 0x048, 0x044, 0x044, 0x038], // 'b'
+    [0x038, 0x044, 0x044, 0x044, 0x020], // 'c'
+    [0x038, 0x044, 0x044, 0x048, 0x07F], // 'd'
+    [0x038, 0x054, 0x054, 0x054, 0x018], // 'e'
+    [0x008, 0x07E, 0x009, 0x001, 0x002], // 'f'
+    [0x098, 0x124, 0x124, 0x124, 0x0FC], // 'g'
+    [0x07F, 0x008, 0x004, 0x004, 0x078], // 'h'
+    [0x000, 0x044, 0x07D, 0x040, 0x000], // 'i'
+    [0x080, 0x100, 0x100, 0x0FD, 0x000], // 'j'
+    [0x07F, 0x010, 0x028, 0x044, 0x000], // 'k'
+    [0x000, 0x041, 0x07F, 0x040, 0x000], // 'l'
+    [0x07C, 0x004, 0x018, 0x004, 0x078], // 'm'
+    [0x07C, 0x008, 0x004, 0x004, 0x078], // 'n'
+    [0x038, 0x044, 0x044, 0x044, 0x038], // 'o'
+    [0x1FC, 0x024, 0x024, 0x024, 0x018], // 'p'
+    [0x018, 0x024, 0x024, 0x024, 0x1FC], // 'q'
+    [0x07C, 0x008, 0x004, 0x004, 0x008], // 'r'
+    [0x048, 0x054, 0x054, 0x054, 0x024], // 's'
+    [0x004, 0x03F, 0x044, 0x040, 0x020], // 't'
+    [0x03C, 0x040, 0x040, 0x020, 0x07C], // 'u'
+    [0x01C, 0x020, 0x040, 0x020, 0x01C], // 'v'
+    [0x03C, 0x040, 0x030, 0x040, 0x03C], // 'w'
+    [0x044, 0x028, 0x010, 0x028, 0x044], // 'x'
+    [0x09C, 0x120, 0x120, 0x120, 0x0FC], // 'y'
+    [0x044, 0x064, 0x054, 0x04C, 0x044], // 'z'
+    [0x000, 0x008, 0x036, 0x041, 0x000], // '{'
+    [0x000, 0x000, 0x07F, 0x000, 0x000], // '|'
+    [0x000, 0x041, 0x036, 0x008, 0x000], // '}'
+    [0x004, 0x002, 0x004, 0x008, 0x004], // '~'
+];
+
+/// Rows of the font A glyph for `c`, with bit 0 as the left column, or
+/// `None` outside ASCII 0x20-0x7E.
+pub(crate) fn font_a_rows(c: char) -> Option<[u64; 9]> {
+    let index = (c as usize).checked_sub(0x20)?;
+    let columns = FONT_A.get(index)?;
+    let mut rows = [0; 9];
+    for (col, bits) in columns.iter().enumerate() {
+        for (row, dots) in rows.iter_mut().enumerate() {
+            if bits & (1 << row) != 0 {
+                *dots |= 1 << col;
+            }
+        }
+    }
+    Some(rows)
+}
@@ -1,4 +1,13 @@
-pub use crate::ast::commons::Barcode1DKind;
+pub use crate::ast::commons::{Barcode1DKind, PostalKind};
+
+/// A manual-mode QR Code segment from the `^FD` data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrSegment {
+    /// Character mode: `N` numeric, `A` alphanumeric, `B` byte or `K` Kanji.
+    pub mode: char,
+    /// Characters encoded in this mode.
+    pub data: String,
+}
 
 /// Represents a self-contained ZPL instruction ready for rendering.
 ///
@@ -33,6 +42,10 @@ pub enum ZplInstruction {
         color: Option<String>,
         /// `^FB` block formatting (wrap, max lines, justification).
         block: Option<TextBlock>,
+        /// `^TDC` letter spacing and decoration lines.
+        decoration: TextDecoration,
+        /// `^TFC` shrink-to-fit box; the font height is chosen at render time.
+        fit: Option<TextFit>,
         /// Condition for this instruction.
         condition: Option<(String, String)>,
     },
@@ -108,6 +121,8 @@ pub enum ZplInstruction {
         check_digit: char,
         mode: char,
         data: String,
+        /// Whether `data` is GS1 AI data to validate (`^GSC`).
+        gs1: bool,
         reverse_print: bool,
         condition: Option<(String, String)>,
     },
@@ -120,11 +135,19 @@ pub enum ZplInstruction {
         magnification: u32,
         error_correction: char,
         mask: u32,
+        /// Automatic-mode data (without the `^FD` prefix).
         data: String,
+        /// Manual-mode segments; when not empty they replace `data`.
+        segments: Vec<QrSegment>,
+        /// Structured append `(symbol number, symbol count, parity)` from a
+        /// mixed-mode `D` prefix.
+        structured_append: Option<(u32, u32, u8)>,
+        /// Whether `data` is GS1 AI data to validate (`^GSC`).
+        gs1: bool,
         reverse_print: bool,
         condition: Option<(String, String)>,
     },
-    /// Draws a generic 1-D barcode (EAN-13, UPC-A, ITF, Code 93).
+    /// Draws a generic 1-D barcode (see [`Barcode1DKind`]).
     Barcode1D {
         kind: Barcode1DKind,
         x: u32,
@@ -132,6 +155,78 @@ pub enum ZplInstruction {
         orientation: char,
         height: u32,
         module_width: u32,
+        /// Wide-to-narrow bar ratio (from `^BY`).
+        ratio: f64,
+        interpretation_line: char,
+        interpretation_line_above: char,
+        /// Symbology-specific check digit selection.
+        check_digit: char,
+        /// Whether the check digit is printed in the interpretation line (Y/N).
+        print_check_digit: char,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws an Aztec barcode.
+    Aztec {
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// Module size in dots.
+        magnification: u32,
+        /// Whether the data carries Extended Channel Interpretation (Y/N).
+        extended_channel: char,
+        /// `^B0` error control and symbol size parameter.
+        error_control: u32,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a MaxiCode barcode.
+    MaxiCode {
+        x: u32,
+        y: u32,
+        /// Symbol mode (2-6).
+        mode: u32,
+        /// Structured append symbol number.
+        symbol_number: u32,
+        /// Structured append symbol count.
+        symbol_count: u32,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a GS1 DataBar barcode.
+    DataBar {
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// `^BR` symbology type (1-12).
+        symbology: u32,
+        /// Module width in dots.
+        magnification: u32,
+        /// Separator height in modules.
+        separator_height: u32,
+        /// Bar height in dots.
+        height: u32,
+        /// Segments per row for Expanded Stacked.
+        segment_width: u32,
+        data: String,
+        /// Whether `data` is GS1 AI data to validate (`^GSC`).
+        gs1: bool,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a height-modulated postal barcode (see [`PostalKind`]).
+    PostalBarcode {
+        kind: PostalKind,
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// Full bar height in dots.
+        height: u32,
+        /// Bar width in dots (from `^BY`).
+        module_width: u32,
         interpretation_line: char,
         interpretation_line_above: char,
         data: String,
@@ -159,7 +254,19 @@ pub enum ZplInstruction {
         orientation: char,
         /// Module size in dots (`^BX` dimensional height).
         module_size: u32,
+        /// `^BX` quality level; only 200 (ECC 200) is encodable.
+        quality: u32,
+        /// Symbol columns (0 = derived from the data).
+        columns: u32,
+        /// Symbol rows (0 = derived from the data).
+        rows: u32,
+        /// Whether a derived symbol size is rectangular.
+        rectangular: bool,
+        /// Escape character for control sequences in `data`.
+        escape: char,
         data: String,
+        /// Whether `data` is GS1 AI data to validate (`^GSC`).
+        gs1: bool,
         reverse_print: bool,
         condition: Option<(String, String)>,
     },
@@ -174,6 +281,73 @@ pub enum ZplInstruction {
         module_width: u32,
         /// Error correction security level (0-8).
         security_level: u32,
+        /// Data columns (0 = derived).
+        columns: u32,
+        /// Rows (0 = derived).
+        rows: u32,
+        /// Whether the symbol is truncated PDF417.
+        truncate: bool,
+        /// `^FM` origins of the Macro PDF417 segments; empty for a single
+        /// symbol at `x`/`y`. `None` entries are encoded but not printed.
+        macro_origins: Vec<Option<(u32, u32)>>,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a MicroPDF417 barcode.
+    MicroPdf417 {
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// Row height in dots.
+        row_height: u32,
+        /// Module width in dots (from `^BY`).
+        module_width: u32,
+        /// `^BF` mode (0-33).
+        mode: u32,
+        /// `^FM` origins of the macro segments; empty for a single symbol at
+        /// `x`/`y`. `None` entries are encoded but not printed.
+        macro_origins: Vec<Option<(u32, u32)>>,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a CODABLOCK barcode.
+    Codablock {
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// Row height in dots.
+        row_height: u32,
+        /// Module width in dots (from `^BY`).
+        module_width: u32,
+        /// Whether the symbol carries its check characters ('Y'/'N').
+        security: char,
+        /// Characters per row (0 = automatic).
+        columns: u32,
+        /// Number of rows (0 = automatic).
+        rows: u32,
+        /// `^BB` mode ('A', 'E' or 'F').
+        mode: char,
+        data: String,
+        reverse_print: bool,
+        condition: Option<(String, String)>,
+    },
+    /// Draws a TLC39 composite barcode.
+    Tlc39 {
+        x: u32,
+        y: u32,
+        orientation: char,
+        /// Code 39 narrow bar width in dots.
+        module_width: u32,
+        /// Code 39 wide-to-narrow bar ratio.
+        ratio: f64,
+        /// Code 39 bar height in dots.
+        height: u32,
+        /// MicroPDF417 module width in dots.
+        micro_module_width: u32,
+        /// MicroPDF417 row height in dots.
+        row_height: u32,
         data: String,
         reverse_print: bool,
         condition: Option<(String, String)>,
@@ -186,6 +360,8 @@ pub enum ZplInstruction {
         check_digit: char,
         height: u32,
         module_width: u32,
+        /// Wide-to-narrow bar ratio (from `^BY`).
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
         data: String,
@@ -209,6 +385,31 @@ pub struct TextBlock {
     pub indent: u32,
 }
 
+/// `^TDC` text attributes: letter spacing and decoration lines.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextDecoration {
+    /// Extra space between characters in dots; negative values tighten.
+    pub letter_spacing: i32,
+    /// Whether a line is drawn under the text.
+    pub underline: bool,
+    /// Whether a line is drawn through the text.
+    pub strike_through: bool,
+}
+
+/// `^TFC` shrink-to-fit parameters: the box a text field must fit and the
+/// range of font heights to choose from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextFit {
+    /// Box width in dots; text wraps to it.
+    pub width: u32,
+    /// Box height in dots.
+    pub height: u32,
+    /// Smallest font height tried, in dots; used when nothing fits.
+    pub min_height: u32,
+    /// Largest font height tried, in dots.
+    pub max_height: u32,
+}
+
 /// Represents common printer resolutions.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Resolution {
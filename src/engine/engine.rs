@@ -4,68 +4,81 @@ use std::sync::Arc;
 use crate::{
     FontManager, ZplError, ZplResult,
     ast::parse_zpl,
-    engine::{backend, common, font, intr},
+    engine::{backend, common, font, intr, wrap},
 };
 
-/// Measures the advance width of `text` in dots for the given ZPL font spec.
+/// Measures the advance width of `text` in dots for the given ZPL font spec
+/// and `^TDC` letter spacing.
 fn measure_text_dots(
     fm: &font::FontManager,
     font_char: char,
     height: Option<u32>,
     width: Option<u32>,
+    letter_spacing: i32,
     text: &str,
 ) -> u32 {
-    fm.measure_text(font_char, height, width, text)
+    fm.measure_text(font_char, height, width, text, letter_spacing)
 }
 
-/// Greedy word-wrap for `^FB`: fits words into `max_width` dots, hard-breaking
-/// words that are longer than a full line. `\&` acts as an explicit line break.
-fn wrap_text_block<F: Fn(&str) -> u32>(text: &str, max_width: u32, measure: F) -> Vec<String> {
-    let mut lines: Vec<String> = Vec::new();
-
-    for segment in text.split("\\&") {
-        if max_width == 0 {
-            lines.push(segment.trim().to_string());
-            continue;
-        }
-
-        let mut current = String::new();
-        for word in segment.split_whitespace() {
-            let candidate = if current.is_empty() {
-                word.to_string()
-            } else {
-                format!("{} {}", current, word)
-            };
-
-            if measure(&candidate) <= max_width {
-                current = candidate;
-                continue;
-            }
-
-            if !current.is_empty() {
-                lines.push(std::mem::take(&mut current));
-            }
-
-            // The word alone may still overflow: hard-break it by characters.
-            if measure(word) > max_width {
-                let mut piece = String::new();
-                for ch in word.chars() {
-                    piece.push(ch);
-                    if measure(&piece) > max_width && piece.chars().count() > 1 {
-                        piece.pop();
-                        lines.push(std::mem::take(&mut piece));
-                        piece.push(ch);
-                    }
-                }
-                current = piece;
-            } else {
-                current = word.to_string();
-            }
+/// `^TFC`: picks the largest font height in the fit range at which `text`,
+/// wrapped to the box width with the field's `^FB` settings, fits the box.
+/// Text that fits at a height also fits at every smaller one, so the range
+/// is binary searched. When nothing fits, the minimum height is used and
+/// the lines that do not fit below the box are dropped, not drawn. Returns
+/// the height, the `^A` width scaled along with it, and the block to lay
+/// the text out with.
+#[allow(clippy::too_many_arguments)]
+fn fit_text_block(
+    fm: &font::FontManager,
+    font_char: char,
+    width: Option<u32>,
+    height: Option<u32>,
+    block: Option<common::TextBlock>,
+    fit: &common::TextFit,
+    letter_spacing: i32,
+    text: &str,
+    hyphenation: Option<&wrap::HyphenationDictionary>,
+) -> (u32, Option<u32>, common::TextBlock) {
+    let spacing = block.map_or(0, |b| b.line_spacing);
+    let max_lines = block.map_or(u32::MAX, |b| b.max_lines.max(1));
+    let layout = |h: u32| {
+        // A `^A` width keeps its ratio to the height.
+        let w = match (width, height) {
+            (Some(w), Some(h0)) if h0 > 0 => Some((w as u64 * h as u64 / h0 as u64).max(1) as u32),
+            _ => width,
+        };
+        let measure = |s: &str| measure_text_dots(fm, font_char, Some(h), w, letter_spacing, s);
+        let lines = wrap::wrap_text_block(text, fit.width, hyphenation, measure);
+        let room = (fit.height as i32 + spacing) / (h as i32 + spacing).max(1);
+        let room = (room.max(0) as u32).min(max_lines);
+        let fits = lines.len() <= room as usize && lines.iter().all(|l| measure(l) <= fit.width);
+        (w, room, fits)
+    };
+
+    // `low` is the largest height known to fit, or the minimum height.
+    let (mut low, mut high) = (fit.min_height, fit.max_height.max(fit.min_height));
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if layout(mid).2 {
+            low = mid;
+        } else {
+            high = mid - 1;
         }
-        lines.push(current);
     }
-
-    lines
+    let h = low;
+    let (w, room, _) = layout(h);
+    let block = common::TextBlock {
+        width: fit.width,
+        max_lines: room.max(1),
+        ..block.unwrap_or(common::TextBlock {
+            width: fit.width,
+            max_lines: 1,
+            line_spacing: 0,
+            justification: 'L',
+            indent: 0,
+        })
+    };
+    (h, w, block)
 }
 
 /// The main entry point for processing and rendering ZPL labels.
@@ -79,6 +92,7 @@ pub struct ZplEngine {
     height: common::Unit,
     resolution: common::Resolution,
     fonts: Option<Arc<font::FontManager>>,
+    hyphenation: Option<Arc<wrap::HyphenationDictionary>>,
 }
 
 impl ZplEngine {
@@ -112,6 +126,7 @@ impl ZplEngine {
             height,
             resolution,
             fonts: None,
+            hyphenation: None,
         })
     }
 
@@ -122,6 +137,15 @@ impl ZplEngine {
         self.fonts = Some(fonts);
     }
 
+    /// Sets the hyphenation dictionary used when wrapping `^FB` and `^TB`
+    /// text blocks.
+    ///
+    /// Without a dictionary, words only break at hyphens and soft hyphens
+    /// already in the text.
+    pub fn set_hyphenation(&mut self, dictionary: Arc<wrap::HyphenationDictionary>) {
+        self.hyphenation = Some(dictionary);
+    }
+
     /// Renders the parsed instructions using the provided backend.
     ///
     /// # Arguments
@@ -242,6 +266,52 @@ impl ZplEngine {
             }
         }
 
+        // GS1 fields are checked up front, so invalid AI data fails the
+        // render before anything is drawn.
+        for instruction in &self.instructions {
+            let (data, condition) = match instruction {
+                common::ZplInstruction::Code128 {
+                    gs1: true,
+                    data,
+                    condition,
+                    ..
+                }
+                | common::ZplInstruction::QRCode {
+                    gs1: true,
+                    data,
+                    condition,
+                    ..
+                }
+                | common::ZplInstruction::DataMatrix {
+                    gs1: true,
+                    data,
+                    condition,
+                    ..
+                }
+                | common::ZplInstruction::DataBar {
+                    gs1: true,
+                    data,
+                    condition,
+                    ..
+                } => (data, condition),
+                _ => continue,
+            };
+            if let Some((var, expected)) = condition
+                && variables.get(var) != Some(expected)
+            {
+                continue;
+            }
+            let data = replace_vars(data, variables);
+            // DataBar composite data follows a `|` and is not AI data.
+            let linear = match instruction {
+                common::ZplInstruction::DataBar { .. } => {
+                    data.split('|').next().unwrap_or_default()
+                }
+                _ => &data,
+            };
+            crate::gs1::validate(linear)?;
+        }
+
         for instruction in &self.instructions {
             if let common::ZplInstruction::PageBreak = instruction {
                 backend.new_page()?;
@@ -259,9 +329,16 @@ impl ZplEngine {
                 common::ZplInstruction::Code128 { condition, .. } => condition,
                 common::ZplInstruction::QRCode { condition, .. } => condition,
                 common::ZplInstruction::Code39 { condition, .. } => condition,
+                common::ZplInstruction::Tlc39 { condition, .. } => condition,
                 common::ZplInstruction::DataMatrix { condition, .. } => condition,
                 common::ZplInstruction::Pdf417 { condition, .. } => condition,
+                common::ZplInstruction::MicroPdf417 { condition, .. } => condition,
+                common::ZplInstruction::Codablock { condition, .. } => condition,
                 common::ZplInstruction::Barcode1D { condition, .. } => condition,
+                common::ZplInstruction::PostalBarcode { condition, .. } => condition,
+                common::ZplInstruction::Aztec { condition, .. } => condition,
+                common::ZplInstruction::MaxiCode { condition, .. } => condition,
+                common::ZplInstruction::DataBar { condition, .. } => condition,
                 common::ZplInstruction::GraphicDiagonal { condition, .. } => condition,
             };
 
@@ -285,29 +362,57 @@ impl ZplEngine {
                     reverse_print,
                     color,
                     block,
+                    decoration,
+                    fit,
                 } => {
                     let resolved = replace_vars(text, variables);
 
+                    let (height, width, block) = match fit {
+                        Some(fit) => {
+                            let (h, w, b) = fit_text_block(
+                                font_manager,
+                                *font,
+                                *width,
+                                *height,
+                                *block,
+                                fit,
+                                decoration.letter_spacing,
+                                &resolved,
+                                self.hyphenation.as_deref(),
+                            );
+                            (Some(h), w, Some(b))
+                        }
+                        None => (*height, *width, *block),
+                    };
+
                     let Some(b) = block else {
                         backend.draw_text(
                             *x,
                             *y,
                             *font,
-                            *height,
-                            *width,
+                            height,
+                            width,
                             *orientation,
                             &resolved,
                             *reverse_print,
                             color.clone(),
+                            *decoration,
                         )?;
                         continue;
                     };
 
-                    // ^FB: wrap into lines, justify, and place each line
+                    // ^FB/^TB: wrap into lines, justify, and place each line
                     // according to the field orientation.
-                    let measure =
-                        |s: &str| measure_text_dots(font_manager, *font, *height, *width, s);
-                    let lines = wrap_text_block(&resolved, b.width, measure);
+                    let measure = |s: &str| {
+                        let spacing = decoration.letter_spacing;
+                        measure_text_dots(font_manager, *font, height, width, spacing, s)
+                    };
+                    let lines = wrap::wrap_text_block(
+                        &resolved,
+                        b.width,
+                        self.hyphenation.as_deref(),
+                        measure,
+                    );
                     let n_lines = lines.len().min(b.max_lines.max(1) as usize);
 
                     let font_h = height.unwrap_or(9) as i32;
@@ -343,12 +448,13 @@ impl ZplEngine {
                             fx,
                             fy,
                             *font,
-                            *height,
-                            *width,
+                            height,
+                            width,
                             *orientation,
                             line,
                             *reverse_print,
                             color.clone(),
+                            *decoration,
                         )?;
                     }
                 }
@@ -441,6 +547,7 @@ impl ZplEngine {
                     check_digit,
                     mode,
                     data,
+                    gs1: _,
                     reverse_print,
                 } => {
                     backend.draw_code128(
@@ -467,8 +574,18 @@ impl ZplEngine {
                     error_correction,
                     mask,
                     data,
+                    segments,
+                    structured_append,
+                    gs1,
                     reverse_print,
                 } => {
+                    let segments: Vec<common::QrSegment> = segments
+                        .iter()
+                        .map(|segment| common::QrSegment {
+                            mode: segment.mode,
+                            data: replace_vars(&segment.data, variables).into_owned(),
+                        })
+                        .collect();
                     backend.draw_qr_code(
                         *x,
                         *y,
@@ -478,6 +595,9 @@ impl ZplEngine {
                         *error_correction,
                         *mask,
                         &replace_vars(data, variables),
+                        &segments,
+                        *structured_append,
+                        *gs1,
                         *reverse_print,
                     )?;
                 }
@@ -489,12 +609,113 @@ impl ZplEngine {
                     orientation,
                     height,
                     module_width,
+                    ratio,
                     interpretation_line,
                     interpretation_line_above,
+                    check_digit,
+                    print_check_digit,
                     data,
                     reverse_print,
                 } => {
                     backend.draw_barcode_1d(
+                        *kind,
+                        *x,
+                        *y,
+                        *orientation,
+                        *height,
+                        *module_width,
+                        *ratio,
+                        *interpretation_line,
+                        *interpretation_line_above,
+                        *check_digit,
+                        *print_check_digit,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::Aztec {
+                    condition: _,
+                    x,
+                    y,
+                    orientation,
+                    magnification,
+                    extended_channel,
+                    error_control,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_aztec(
+                        *x,
+                        *y,
+                        *orientation,
+                        *magnification,
+                        *extended_channel,
+                        *error_control,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::MaxiCode {
+                    condition: _,
+                    x,
+                    y,
+                    mode,
+                    symbol_number,
+                    symbol_count,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_maxicode(
+                        *x,
+                        *y,
+                        *mode,
+                        *symbol_number,
+                        *symbol_count,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::DataBar {
+                    condition: _,
+                    x,
+                    y,
+                    orientation,
+                    symbology,
+                    magnification,
+                    separator_height,
+                    height,
+                    segment_width,
+                    data,
+                    gs1: _,
+                    reverse_print,
+                } => {
+                    backend.draw_databar(
+                        *x,
+                        *y,
+                        *orientation,
+                        *symbology,
+                        *magnification,
+                        *separator_height,
+                        *height,
+                        *segment_width,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::PostalBarcode {
+                    condition: _,
+                    kind,
+                    x,
+                    y,
+                    orientation,
+                    height,
+                    module_width,
+                    interpretation_line,
+                    interpretation_line_above,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_postal_barcode(
                         *kind,
                         *x,
                         *y,
@@ -537,7 +758,13 @@ impl ZplEngine {
                     y,
                     orientation,
                     module_size,
+                    quality,
+                    columns,
+                    rows,
+                    rectangular,
+                    escape,
                     data,
+                    gs1,
                     reverse_print,
                 } => {
                     backend.draw_datamatrix(
@@ -545,7 +772,13 @@ impl ZplEngine {
                         *y,
                         *orientation,
                         *module_size,
+                        *quality,
+                        *columns,
+                        *rows,
+                        *rectangular,
+                        *escape,
                         &replace_vars(data, variables),
+                        *gs1,
                         *reverse_print,
                     )?;
                 }
@@ -557,6 +790,10 @@ impl ZplEngine {
                     row_height,
                     module_width,
                     security_level,
+                    columns,
+                    rows,
+                    truncate,
+                    macro_origins,
                     data,
                     reverse_print,
                 } => {
@@ -567,6 +804,88 @@ impl ZplEngine {
                         *row_height,
                         *module_width,
                         *security_level,
+                        *columns,
+                        *rows,
+                        *truncate,
+                        macro_origins,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::MicroPdf417 {
+                    condition: _,
+                    x,
+                    y,
+                    orientation,
+                    row_height,
+                    module_width,
+                    mode,
+                    macro_origins,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_micro_pdf417(
+                        *x,
+                        *y,
+                        *orientation,
+                        *row_height,
+                        *module_width,
+                        *mode,
+                        macro_origins,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::Codablock {
+                    condition: _,
+                    x,
+                    y,
+                    orientation,
+                    row_height,
+                    module_width,
+                    security,
+                    columns,
+                    rows,
+                    mode,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_codablock(
+                        *x,
+                        *y,
+                        *orientation,
+                        *row_height,
+                        *module_width,
+                        *security,
+                        *columns,
+                        *rows,
+                        *mode,
+                        &replace_vars(data, variables),
+                        *reverse_print,
+                    )?;
+                }
+                common::ZplInstruction::Tlc39 {
+                    condition: _,
+                    x,
+                    y,
+                    orientation,
+                    module_width,
+                    ratio,
+                    height,
+                    micro_module_width,
+                    row_height,
+                    data,
+                    reverse_print,
+                } => {
+                    backend.draw_tlc39(
+                        *x,
+                        *y,
+                        *orientation,
+                        *module_width,
+                        *ratio,
+                        *height,
+                        *micro_module_width,
+                        *row_height,
                         &replace_vars(data, variables),
                         *reverse_print,
                     )?;
@@ -579,6 +898,7 @@ impl ZplEngine {
                     check_digit,
                     height,
                     module_width,
+                    ratio,
                     interpretation_line,
                     interpretation_line_above,
                     data,
@@ -591,6 +911,7 @@ impl ZplEngine {
                         *check_digit,
                         *height,
                         *module_width,
+                        *ratio,
                         *interpretation_line,
                         *interpretation_line_above,
                         &replace_vars(data, variables),
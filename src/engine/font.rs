@@ -1,7 +1,17 @@
 use std::collections::HashMap;
 
+#[cfg(any(feature = "png", feature = "pdf"))]
+use super::bitmap_font::font_a_rows;
+#[cfg(any(feature = "png", feature = "pdf"))]
+use super::common::TextDecoration;
 use crate::{ZplError, ZplResult};
-use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+#[cfg(any(feature = "png", feature = "pdf"))]
+use ab_glyph::point;
+use ab_glyph::{Font, FontArc, FontVec, GlyphId, PxScale, VariableFont};
+#[cfg(any(feature = "png", feature = "pdf"))]
+use rustybuzz::ttf_parser::LineMetrics;
+use rustybuzz::{Direction, UnicodeBuffer, Variation, ttf_parser::Tag};
+use unicode_bidi::BidiInfo;
 
 /// Default fallback font bytes embedded in the binary.
 /// This guarantees the library runs on any OS/Platform without C dependencies.
@@ -72,6 +82,37 @@ fn bitmap_cell(font_char: char) -> Option<BitmapCell> {
     })
 }
 
+impl BitmapCell {
+    /// Integer `(height, width)` magnification for an `^A` height `h` and
+    /// optional width, rounded to the nearest factor like the printer.
+    fn magnification(&self, h: f32, width: Option<u32>) -> (f32, f32) {
+        let mag_h = (h / self.base_h).round().max(1.0);
+        let mag_w = match width {
+            Some(w) if w > 0 => (w as f32 / self.base_w).round().max(1.0),
+            _ => mag_h,
+        };
+        (mag_h, mag_w)
+    }
+}
+
+/// Coverage from which a rasterized pixel becomes a dot of a bitmap glyph.
+#[cfg(any(feature = "png", feature = "pdf"))]
+const DOT_COVERAGE: f32 = 0.5;
+
+/// Text set as dot-matrix glyphs of bitmap font A.
+#[cfg(any(feature = "png", feature = "pdf"))]
+#[derive(Debug, Clone)]
+pub(crate) struct BitmapText {
+    /// Filled rectangles `(x, y, w, h)`: magnified dots merged into
+    /// horizontal runs, in dots from the top-left corner of the unrotated
+    /// text.
+    pub(crate) rects: Vec<(u32, u32, u32, u32)>,
+    /// Advance width of the text, in dots.
+    pub(crate) width: u32,
+    /// Magnified matrix height, in dots.
+    pub(crate) height: u32,
+}
+
 /// Normalization metrics extracted once per registered font, in font units.
 #[derive(Debug, Clone, Copy)]
 struct FontMetrics {
@@ -116,6 +157,7 @@ impl FontMetrics {
 /// from the raw `^A` parameters: `PxScale` maps `ascent - descent` (not the
 /// em) to its `y` value, which shrinks glyphs and misplaces the baseline.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(any(feature = "png", feature = "pdf")), allow(dead_code))]
 pub(crate) struct TextLayout {
     /// Rasterization scale for `ab_glyph`/`imageproc`.
     pub px: PxScale,
@@ -130,6 +172,187 @@ pub(crate) struct TextLayout {
     pub cell_h: f32,
 }
 
+/// One glyph of a shaped run, in font units (y up).
+#[derive(Debug, Clone)]
+pub(crate) struct ShapedGlyph {
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub id: GlyphId,
+    /// Pen advance after the glyph.
+    pub x_advance: i32,
+    /// Offset of the glyph from the pen position.
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub x_offset: i32,
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub y_offset: i32,
+    /// Source text of the glyph's cluster (several characters for a
+    /// ligature); empty for the second and later glyphs of a cluster.
+    #[cfg(feature = "pdf")]
+    pub text: String,
+    /// Byte offset of the cluster in the run's text; shared by all glyphs
+    /// of a cluster.
+    pub cluster: usize,
+}
+
+/// A run of glyphs shaped with one font of an identifier's fallback chain.
+#[derive(Debug, Clone)]
+pub(crate) struct ShapedRun {
+    /// Internal name of the font the run is shaped with.
+    #[cfg(feature = "pdf")]
+    pub font_name: String,
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub font: FontArc,
+    /// Rasterization scale of `font` for `ab_glyph`/`imageproc`.
+    #[cfg(feature = "png")]
+    pub px: PxScale,
+    pub glyphs: Vec<ShapedGlyph>,
+    /// Font units per em.
+    #[cfg(feature = "pdf")]
+    pub units_per_em: f32,
+    /// Dots per font unit, horizontally and vertically.
+    pub scale_x: f32,
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub scale_y: f32,
+}
+
+impl ShapedRun {
+    /// Advance width of the run, in dots.
+    pub(crate) fn width(&self) -> f32 {
+        self.glyphs.iter().map(|g| g.x_advance).sum::<i32>() as f32 * self.scale_x
+    }
+}
+
+/// Text shaped into runs of glyphs in visual (left-to-right) order: bidi
+/// runs are reordered and split by font coverage, and each run carries the
+/// substitutions (ligatures, joining forms, reordered marks) and
+/// positioning (kerning, mark attachment) of its font.
+#[derive(Debug, Clone)]
+pub(crate) struct ShapedText {
+    pub runs: Vec<ShapedRun>,
+}
+
+impl ShapedText {
+    /// Advance width of the whole text, in dots.
+    pub(crate) fn width(&self) -> f32 {
+        self.runs.iter().map(ShapedRun::width).sum()
+    }
+
+    /// Adds `dots` of letter spacing after every cluster but the last, so
+    /// marks stay attached to their base and ligatures stay whole.
+    fn letter_space(&mut self, dots: i32) {
+        if dots == 0 {
+            return;
+        }
+        let last_run = self.runs.len().saturating_sub(1);
+        for (r, run) in self.runs.iter_mut().enumerate() {
+            let extra = (dots as f32 / run.scale_x).round() as i32;
+            let count = run.glyphs.len();
+            for i in 0..count {
+                let cluster_end = match run.glyphs.get(i + 1) {
+                    Some(next) => next.cluster != run.glyphs[i].cluster,
+                    None => r != last_run,
+                };
+                if cluster_end {
+                    run.glyphs[i].x_advance += extra;
+                }
+            }
+        }
+    }
+}
+
+/// Characters that stay in the font of the preceding character when it
+/// covers them, so spaces and combining marks do not split a run.
+fn joins_previous(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(c,
+            '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{200C}'..='\u{200D}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}')
+}
+
+/// Shapes one run with a font `ab_glyph` reads but the shaper cannot parse:
+/// one glyph per character with `kern` table kerning.
+fn shape_unshaped(font: &FontArc, text: &str, glyphs: &mut Vec<ShapedGlyph>) {
+    let mut last: Option<GlyphId> = None;
+    for (cluster, c) in text.char_indices() {
+        let id = font.glyph_id(c);
+        if let (Some(prev), Some(g)) = (last, glyphs.last_mut()) {
+            g.x_advance += font.kern_unscaled(prev, id).round() as i32;
+        }
+        glyphs.push(ShapedGlyph {
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            id,
+            x_advance: font.h_advance_unscaled(id).round() as i32,
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            x_offset: 0,
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            y_offset: 0,
+            #[cfg(feature = "pdf")]
+            text: c.to_string(),
+            cluster,
+        });
+        last = Some(id);
+    }
+}
+
+/// Shapes one bidi run, whose glyphs come out in visual order.
+fn shape_run(face: &rustybuzz::Face, text: &str, rtl: bool, glyphs: &mut Vec<ShapedGlyph>) {
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.set_direction(if rtl {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let output = rustybuzz::shape(face, &[], buffer);
+
+    // Source text of the cluster starting at a byte offset, given to its
+    // first glyph only.
+    #[cfg(feature = "pdf")]
+    let mut cluster_text = {
+        let mut starts: Vec<usize> = output
+            .glyph_infos()
+            .iter()
+            .map(|i| i.cluster as usize)
+            .collect();
+        starts.sort_unstable();
+        starts.dedup();
+        let mut seen = Vec::new();
+        move |start: usize| {
+            let end = starts
+                .iter()
+                .find(|&&s| s > start)
+                .copied()
+                .unwrap_or(text.len());
+            if seen.contains(&start) {
+                String::new()
+            } else {
+                seen.push(start);
+                text.get(start..end).unwrap_or_default().to_string()
+            }
+        }
+    };
+    for (info, pos) in output.glyph_infos().iter().zip(output.glyph_positions()) {
+        let start = info.cluster as usize;
+        glyphs.push(ShapedGlyph {
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            id: GlyphId(info.glyph_id as u16),
+            x_advance: pos.x_advance,
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            x_offset: pos.x_offset,
+            #[cfg(any(feature = "png", feature = "pdf"))]
+            y_offset: pos.y_offset,
+            #[cfg(feature = "pdf")]
+            text: cluster_text(start),
+            cluster: start,
+        });
+    }
+}
+
 /// Interpretation-line geometry for 1-D barcodes: `(font_height, gap)` in
 /// dots, both proportional to the module width as rendered by Labelary
 /// (digit ink measured 36 dots tall, 6 dots below the bars, at `^BY5`).
@@ -154,6 +377,14 @@ pub struct FontManager {
     font_bytes: HashMap<String, Vec<u8>>,
     /// Normalization metrics per internal font name, computed at registration.
     font_metrics: HashMap<String, FontMetrics>,
+    /// Ordered fallback fonts (internal names) per ZPL identifier, tried for
+    /// characters the identifier's own font lacks.
+    fallbacks: HashMap<String, Vec<String>>,
+    /// Variation axis coordinates of variable font instances, indexed by
+    /// internal font names. Absent for static fonts.
+    font_variations: HashMap<String, Vec<([u8; 4], f32)>>,
+    /// Whether font A uses the built-in dot-matrix glyphs.
+    bitmap_fonts: bool,
 }
 
 impl Default for FontManager {
@@ -170,6 +401,9 @@ impl Default for FontManager {
             font_index: HashMap::new(),
             font_bytes: HashMap::new(),
             font_metrics: HashMap::new(),
+            fallbacks: HashMap::new(),
+            font_variations: HashMap::new(),
+            bitmap_fonts: false,
         };
 
         // Register default fonts for their respective alphanumeric ZPL identifiers
@@ -209,20 +443,60 @@ impl FontManager {
         }
     }
 
+    /// Resolves a ZPL font identifier to the internal name of the font that
+    /// renders it, falling back to font '0' like [`Self::text_layout`].
+    pub(crate) fn resolve_font_name(&self, font_char: char) -> Option<&str> {
+        let mut buf = [0; 4];
+        let key = font_char.encode_utf8(&mut buf);
+        self.font_map
+            .get(key)
+            .or_else(|| self.font_map.get("0"))
+            .map(|s| s.as_str())
+    }
+
+    /// Retrieves the raw TTF/OTF bytes of a font by its internal name.
+    #[cfg(feature = "pdf")]
+    pub(crate) fn font_bytes_by_name(&self, name: &str) -> Option<&[u8]> {
+        self.font_bytes.get(name).map(|v| v.as_slice())
+    }
+
+    /// Retrieves a font by its internal name, at its variation instance.
+    #[cfg(feature = "pdf")]
+    pub(crate) fn font_by_name(&self, name: &str) -> Option<&FontArc> {
+        self.font_index.get(name)
+    }
+
+    /// Variation axis coordinates a font was registered with, by internal
+    /// name; empty for static fonts and default instances.
+    pub(crate) fn font_variations(&self, name: &str) -> &[([u8; 4], f32)] {
+        self.font_variations.get(name).map_or(&[], |v| v.as_slice())
+    }
+
     /// Resolves a ZPL font identifier (falling back to font '0') and computes
     /// the Zebra-calibrated [`TextLayout`] for the given `^A` height/width.
     ///
     /// Bitmap identifiers (A-H) use integer cell magnification like real
     /// printers; every other identifier uses the scalable-font model.
+    #[cfg(any(feature = "png", feature = "pdf"))]
     pub(crate) fn text_layout(
         &self,
         font_char: char,
         height: Option<u32>,
         width: Option<u32>,
     ) -> Option<(&FontArc, TextLayout)> {
-        let mut buf = [0; 4];
-        let key = font_char.encode_utf8(&mut buf);
-        let name = self.font_map.get(key).or_else(|| self.font_map.get("0"))?;
+        let name = self.resolve_font_name(font_char)?;
+        self.layout_with(name, font_char, height, width)
+    }
+
+    /// Computes the [`TextLayout`] of the font `name` for the `^A` spec of
+    /// `font_char`; fallback fonts are sized like the identifier's own font.
+    fn layout_with(
+        &self,
+        name: &str,
+        font_char: char,
+        height: Option<u32>,
+        width: Option<u32>,
+    ) -> Option<(&FontArc, TextLayout)> {
         let font = self.font_index.get(name)?;
         let metrics = self
             .font_metrics
@@ -234,11 +508,7 @@ impl FontManager {
 
         let (em_x, em_y, baseline, cell_h) = if let Some(cell) = bitmap_cell(font_char) {
             // Bitmap fonts magnify a fixed dot matrix by integer factors.
-            let mag_h = (h / cell.base_h).round().max(1.0);
-            let mag_w = match width {
-                Some(w) if w > 0 => (w as f32 / cell.base_w).round().max(1.0),
-                _ => mag_h,
-            };
+            let (mag_h, mag_w) = cell.magnification(h, width);
             let cap_px = cell.baseline * mag_h;
             let advance_px = cell.cell_w * mag_w;
             let em_y = cap_px * metrics.units_per_em / metrics.cap_height;
@@ -274,30 +544,349 @@ impl FontManager {
         ))
     }
 
-    /// Measures the advance width of `text` in dots for the given `^A` spec.
-    /// Single source of truth for every backend and for `^FB` wrapping.
+    /// Shapes `text` for the given `^A` spec: bidi reordering into runs,
+    /// splitting by font coverage, then OpenType shaping of each run, with
+    /// `letter_spacing` dots added between clusters (`^TDC`). Both backends
+    /// draw these runs and [`Self::measure_text`] sums their advances.
+    ///
+    /// Each character goes to the first font of the identifier's chain (its
+    /// own font, then its fallbacks) that has a glyph for it, or to its own
+    /// font when none does.
+    pub(crate) fn shape_text(
+        &self,
+        font_char: char,
+        height: Option<u32>,
+        width: Option<u32>,
+        text: &str,
+        letter_spacing: i32,
+    ) -> Option<ShapedText> {
+        let mut buf = [0; 4];
+        let key = font_char.encode_utf8(&mut buf);
+        let chain: Vec<&str> = std::iter::once(self.resolve_font_name(font_char)?)
+            .chain(
+                self.fallbacks
+                    .get(key)
+                    .into_iter()
+                    .flatten()
+                    .map(|s| s.as_str()),
+            )
+            .collect();
+        let fonts: Vec<&FontArc> = chain
+            .iter()
+            .map(|name| self.font_index.get(*name))
+            .collect::<Option<_>>()?;
+
+        // Font of each character, by byte offset.
+        let mut font_at = vec![0; text.len()];
+        let mut prev = 0;
+        for (i, c) in text.char_indices() {
+            let covers = |f: usize| fonts[f].glyph_id(c).0 != 0;
+            let f = if joins_previous(c) && covers(prev) {
+                prev
+            } else {
+                (0..fonts.len()).find(|&f| covers(f)).unwrap_or(0)
+            };
+            font_at[i..i + c.len_utf8()].fill(f);
+            prev = f;
+        }
+
+        let mut faces: Vec<Option<Option<rustybuzz::Face>>> = chain.iter().map(|_| None).collect();
+        let mut runs = Vec::new();
+        let bidi = BidiInfo::new(text, None);
+        for para in &bidi.paragraphs {
+            let (levels, visual_runs) = bidi.visual_runs(para, para.range.clone());
+            for range in visual_runs {
+                let rtl = levels[range.start].is_rtl();
+                let mut pieces = Vec::new();
+                let mut start = range.start;
+                for i in range.clone() {
+                    if text.is_char_boundary(i) && font_at[i] != font_at[start] {
+                        pieces.push((start..i, font_at[start]));
+                        start = i;
+                    }
+                }
+                pieces.push((start..range.end, font_at[start]));
+                if rtl {
+                    pieces.reverse();
+                }
+
+                for (piece, f) in pieces {
+                    let (font, layout) = self.layout_with(chain[f], font_char, height, width)?;
+                    let face = faces[f].get_or_insert_with(|| {
+                        self.font_bytes
+                            .get(chain[f])
+                            .and_then(|bytes| rustybuzz::Face::from_slice(bytes, 0))
+                            .map(|mut face| {
+                                let variations: Vec<Variation> = self
+                                    .font_variations(chain[f])
+                                    .iter()
+                                    .map(|(tag, value)| Variation {
+                                        tag: Tag::from_bytes(tag),
+                                        value: *value,
+                                    })
+                                    .collect();
+                                face.set_variations(&variations);
+                                face
+                            })
+                    });
+                    let mut glyphs = Vec::new();
+                    let units_per_em = match face {
+                        Some(face) => {
+                            shape_run(face, &text[piece], rtl, &mut glyphs);
+                            face.units_per_em() as f32
+                        }
+                        None => {
+                            shape_unshaped(font, &text[piece], &mut glyphs);
+                            font.units_per_em().unwrap_or(1000.0)
+                        }
+                    };
+                    runs.push(ShapedRun {
+                        #[cfg(feature = "pdf")]
+                        font_name: chain[f].to_string(),
+                        #[cfg(any(feature = "png", feature = "pdf"))]
+                        font: font.clone(),
+                        #[cfg(feature = "png")]
+                        px: layout.px,
+                        glyphs,
+                        #[cfg(feature = "pdf")]
+                        units_per_em,
+                        scale_x: layout.em_x / units_per_em,
+                        #[cfg(any(feature = "png", feature = "pdf"))]
+                        scale_y: layout.em_y / units_per_em,
+                    });
+                }
+            }
+        }
+        let mut shaped = ShapedText { runs };
+        shaped.letter_space(letter_spacing);
+        Some(shaped)
+    }
+
+    /// Measures the advance width of `text` in dots for the given `^A` spec
+    /// and `^TDC` letter spacing. Single source of truth for every backend
+    /// and for `^FB` wrapping.
     pub(crate) fn measure_text(
         &self,
         font_char: char,
         height: Option<u32>,
         width: Option<u32>,
         text: &str,
+        letter_spacing: i32,
     ) -> u32 {
-        let Some((font, layout)) = self.text_layout(font_char, height, width) else {
-            return 0;
-        };
-        let scaled = font.as_scaled(layout.px);
-        let mut w = 0.0_f32;
-        let mut last = None;
-        for c in text.chars() {
-            let gid = font.glyph_id(c);
-            if let Some(prev) = last {
-                w += scaled.kern(prev, gid);
+        if self.bitmap_fonts
+            && let Some(cell) = bitmap_cell(font_char)
+        {
+            let h = height.unwrap_or(DEFAULT_FONT_HEIGHT).max(1) as f32;
+            let (_, mag_w) = cell.magnification(h, width);
+            let cell_dots = (cell.cell_w * mag_w) as i32;
+            return match text.chars().count() as i32 {
+                0 => 0,
+                n => ((n - 1) * (cell_dots + letter_spacing).max(0) + cell_dots) as u32,
+            };
+        }
+        self.shape_text(font_char, height, width, text, letter_spacing)
+            .map_or(0, |shaped| shaped.width().ceil().max(0.0) as u32)
+    }
+
+    /// Lays out `text` with the built-in dot-matrix glyphs when bitmap fonts
+    /// are enabled and `font_char` is font A, or returns `None` so the
+    /// caller draws outline text.
+    ///
+    /// Each glyph fills a `cell_w × base_h` matrix magnified by the same
+    /// integer factors as [`Self::text_layout`]. Characters outside the
+    /// font A table are rasterized from the identifier's font at the matrix
+    /// size. `letter_spacing` dots separate consecutive cells.
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub(crate) fn bitmap_text(
+        &self,
+        font_char: char,
+        height: Option<u32>,
+        width: Option<u32>,
+        text: &str,
+        letter_spacing: i32,
+    ) -> Option<BitmapText> {
+        if !self.bitmap_fonts || font_char != 'A' {
+            return None;
+        }
+        let cell = bitmap_cell(font_char)?;
+        let h = height.unwrap_or(DEFAULT_FONT_HEIGHT).max(1) as f32;
+        let (mag_h, mag_w) = cell.magnification(h, width);
+        let (mag_h, mag_w) = (mag_h as u32, mag_w as u32);
+        let (cols, rows) = (cell.cell_w as u32, cell.base_h as u32);
+        // Unmagnified layout of the outline font the dots are rasterized from.
+        let (font, layout) = self.layout_with(
+            self.resolve_font_name(font_char)?,
+            font_char,
+            Some(rows),
+            Some(cell.base_w as u32),
+        )?;
+
+        let advance = (cols as i32 * mag_w as i32 + letter_spacing).max(0) as u32;
+        let mut rects = Vec::new();
+        let mut pen = 0;
+        for (i, c) in text.chars().enumerate() {
+            if i > 0 {
+                pen += advance;
+            }
+            let mut dots = vec![0u64; rows as usize];
+            match font_a_rows(c) {
+                Some(glyph) => dots.copy_from_slice(&glyph),
+                None => {
+                    let glyph = font
+                        .glyph_id(c)
+                        .with_scale_and_position(layout.px, point(0.0, layout.baseline));
+                    if let Some(outline) = font.outline_glyph(glyph) {
+                        let bounds = outline.px_bounds();
+                        outline.draw(|px, py, coverage| {
+                            let x = px as i32 + bounds.min.x as i32;
+                            let y = py as i32 + bounds.min.y as i32;
+                            if coverage >= DOT_COVERAGE
+                                && (0..cols as i32).contains(&x)
+                                && (0..rows as i32).contains(&y)
+                            {
+                                dots[y as usize] |= 1 << x;
+                            }
+                        });
+                    }
+                }
+            }
+
+            for (row, &bits) in dots.iter().enumerate() {
+                let mut col = 0;
+                while col < cols {
+                    if bits & (1 << col) == 0 {
+                        col += 1;
+                        continue;
+                    }
+                    let start = col;
+                    while col < cols && bits & (1 << col) != 0 {
+                        col += 1;
+                    }
+                    rects.push((
+                        pen + start * mag_w,
+                        row as u32 * mag_h,
+                        (col - start) * mag_w,
+                        mag_h,
+                    ));
+                }
             }
-            w += scaled.h_advance(gid);
-            last = Some(gid);
         }
-        w.ceil() as u32
+        Some(BitmapText {
+            rects,
+            width: if text.is_empty() {
+                0
+            } else {
+                pen + cols * mag_w
+            },
+            height: rows * mag_h,
+        })
+    }
+
+    /// Underline and strike-through bars of `text` as filled rectangles
+    /// relative to the unrotated cell, or `None` when `decoration` draws no
+    /// line.
+    ///
+    /// Outline fonts place the bars with the font's `post` underline and
+    /// `OS/2` strikeout metrics; bitmap fonts use one magnified dot row just
+    /// below the baseline and across the middle of the capitals.
+    #[cfg(any(feature = "png", feature = "pdf"))]
+    pub(crate) fn decoration_rects(
+        &self,
+        font_char: char,
+        height: Option<u32>,
+        width: Option<u32>,
+        text: &str,
+        decoration: &TextDecoration,
+    ) -> Option<BitmapText> {
+        if !decoration.underline && !decoration.strike_through {
+            return None;
+        }
+        let text_w = self.measure_text(font_char, height, width, text, decoration.letter_spacing);
+        if text_w == 0 {
+            return None;
+        }
+
+        // (top, thickness) in dots from the cell top.
+        let (cell_h, underline, strike) = match bitmap_cell(font_char).filter(|_| self.bitmap_fonts)
+        {
+            Some(cell) => {
+                let h = height.unwrap_or(DEFAULT_FONT_HEIGHT).max(1) as f32;
+                let (mag_h, _) = cell.magnification(h, width);
+                let row = |r: f32| (r * mag_h, mag_h);
+                (
+                    cell.base_h * mag_h,
+                    row(cell.baseline),
+                    row(((cell.baseline - 1.0) / 2.0).round()),
+                )
+            }
+            None => {
+                let name = self.resolve_font_name(font_char)?;
+                let (_, layout) = self.layout_with(name, font_char, height, width)?;
+                let face = self
+                    .font_bytes
+                    .get(name)
+                    .and_then(|bytes| rustybuzz::ttf_parser::Face::parse(bytes, 0).ok());
+                let upem = face.as_ref().map_or(1000.0, |f| f.units_per_em() as f32);
+                // Metrics in ems (y up), defaulting to typical values.
+                let bar = |metrics: Option<LineMetrics>, position, thickness| {
+                    let (position, thickness) = metrics.map_or((position, thickness), |m| {
+                        (m.position as f32 / upem, m.thickness as f32 / upem)
+                    });
+                    (
+                        layout.baseline - position * layout.em_y,
+                        (thickness * layout.em_y).round().max(1.0),
+                    )
+                };
+                (
+                    layout.cell_h,
+                    bar(
+                        face.as_ref().and_then(|f| f.underline_metrics()),
+                        -0.1,
+                        0.05,
+                    ),
+                    bar(face.as_ref().and_then(|f| f.strikeout_metrics()), 0.3, 0.05),
+                )
+            }
+        };
+
+        let rect = |(top, thickness): (f32, f32)| {
+            (0, top.round().max(0.0) as u32, text_w, thickness as u32)
+        };
+        let mut rects = Vec::new();
+        if decoration.underline {
+            rects.push(rect(underline));
+        }
+        if decoration.strike_through {
+            rects.push(rect(strike));
+        }
+        Some(BitmapText {
+            rects,
+            width: text_w,
+            height: cell_h.ceil() as u32,
+        })
+    }
+
+    /// Enables or disables the built-in dot-matrix glyphs of Zebra bitmap
+    /// font A (off by default).
+    ///
+    /// When enabled, text in font A is drawn from a hand-drawn 5 × 9 matrix
+    /// for ASCII, magnified by integer factors like on the printer — every
+    /// dot a solid square of whole dots in PNG and a vector rectangle in
+    /// PDF — instead of the registered outline font scaled into the same
+    /// cells. Characters outside ASCII take their dots from the outline font
+    /// rasterized at the matrix size. Fonts B-H have no glyph tables and
+    /// keep drawing outline glyphs in their bitmap cells.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use zpl_forge::FontManager;
+    ///
+    /// let mut font_manager = FontManager::default();
+    /// font_manager.set_bitmap_fonts(true);
+    /// ```
+    pub fn set_bitmap_fonts(&mut self, enabled: bool) {
+        self.bitmap_fonts = enabled;
     }
 
     /// Registers a new font and maps it to a range of ZPL identifiers.
@@ -338,31 +927,174 @@ impl FontManager {
         from: char,
         to: char,
     ) -> ZplResult<()> {
-        let font = FontArc::try_from_vec(bytes.to_vec())
+        self.load_font(name, bytes, &[])?;
+        self.assign_font(name, from, to);
+        Ok(())
+    }
+
+    /// Registers an instance of a variable font and maps it to a range of
+    /// ZPL identifiers.
+    ///
+    /// The instance is selected by axis coordinates in the font's own
+    /// design units, e.g. `("wght", 700.0)` or `("wdth", 75.0)`; axes left
+    /// out keep their default. Each instance is registered under its own
+    /// internal name, so one variable font can provide several weights and
+    /// widths. Both backends render the instance: PNG rasterizes it directly
+    /// and the native PDF embeds a static copy of the glyphs it shows.
+    ///
+    /// # Arguments
+    /// * `name` - An internal name for the instance.
+    /// * `bytes` - The raw TrueType/OpenType variable font data.
+    /// * `axes` - Axis tags (four characters) and their coordinates.
+    /// * `from` - The starting ZPL identifier in the range (A-Z, 0-9).
+    /// * `to` - The ending ZPL identifier in the range (A-Z, 0-9).
+    ///
+    /// # Errors
+    /// Returns an error if the font data is invalid or the font has no axis
+    /// with one of the given tags.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use zpl_forge::FontManager;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut font_manager = FontManager::default();
+    ///
+    /// // One variable font, three weights and a condensed bold:
+    /// // let vf_bytes = std::fs::read("fonts/RobotoFlex-VF.ttf")?;
+    /// // font_manager.register_font_instance("Roboto Light", &vf_bytes, &[("wght", 300.0)], 'L', 'L')?;
+    /// // font_manager.register_font_instance("Roboto Regular", &vf_bytes, &[("wght", 400.0)], 'R', 'R')?;
+    /// // font_manager.register_font_instance("Roboto Bold", &vf_bytes, &[("wght", 700.0)], 'B', 'B')?;
+    /// // font_manager.register_font_instance(
+    /// //     "Roboto Bold Condensed",
+    /// //     &vf_bytes,
+    /// //     &[("wght", 700.0), ("wdth", 75.0)],
+    /// //     'C',
+    /// //     'C',
+    /// // )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_font_instance(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+        axes: &[(&str, f32)],
+        from: char,
+        to: char,
+    ) -> ZplResult<()> {
+        let variations = axes
+            .iter()
+            .map(|&(tag, value)| {
+                let tag: [u8; 4] = tag.as_bytes().try_into().map_err(|_| {
+                    ZplError::FontError(format!("Invalid variation axis tag: {}", tag))
+                })?;
+                Ok((tag, value))
+            })
+            .collect::<ZplResult<Vec<_>>>()?;
+        self.load_font(name, bytes, &variations)?;
+        self.assign_font(name, from, to);
+        Ok(())
+    }
+
+    /// Registers a fallback font for a range of ZPL identifiers.
+    ///
+    /// Characters the identifier's own font has no glyph for are drawn with
+    /// the first fallback that has one, in registration order, at the size
+    /// the `^A` spec gives the identifier's font. Text measurement and `^FB`
+    /// wrapping use the same mixed-font runs.
+    ///
+    /// # Arguments
+    /// * `name` - An internal name for the font. Registering a font under
+    ///   the name of an already registered font replaces its data.
+    /// * `bytes` - The raw TrueType/OpenType font data.
+    /// * `from` - The starting ZPL identifier in the range (A-Z, 0-9).
+    /// * `to` - The ending ZPL identifier in the range (A-Z, 0-9).
+    ///
+    /// # Errors
+    /// Returns an error if the font data is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use zpl_forge::FontManager;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut font_manager = FontManager::default();
+    ///
+    /// // Chinese names inside Latin addresses printed with ^A0-^A9:
+    /// // let cjk_bytes = std::fs::read("fonts/NotoSansSC-Regular.otf")?;
+    /// // font_manager.add_fallback_font("Noto Sans SC", &cjk_bytes, '0', '9')?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_fallback_font(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+        from: char,
+        to: char,
+    ) -> ZplResult<()> {
+        self.load_font(name, bytes, &[])?;
+        for key in identifier_range(from, to) {
+            let chain = self.fallbacks.entry(key.to_string()).or_default();
+            if !chain.iter().any(|n| n == name) {
+                chain.push(name.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Internal helper to parse and store font data under an internal name,
+    /// at the instance given by `variations` for variable fonts.
+    fn load_font(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+        variations: &[([u8; 4], f32)],
+    ) -> ZplResult<()> {
+        let mut font = FontVec::try_from_vec(bytes.to_vec())
             .map_err(|_| ZplError::FontError("Invalid font data".into()))?;
+        let axes = font.variations();
+        for (tag, value) in variations {
+            // `set_variation` accepts any tag on a variable font.
+            if !axes.iter().any(|axis| axis.tag == *tag) || !font.set_variation(tag, *value) {
+                return Err(ZplError::FontError(format!(
+                    "Font has no variation axis: {}",
+                    String::from_utf8_lossy(tag)
+                )));
+            }
+        }
+        let font = FontArc::new(font);
+        if variations.is_empty() {
+            self.font_variations.remove(name);
+        } else {
+            self.font_variations
+                .insert(name.to_string(), variations.to_vec());
+        }
         self.font_metrics
             .insert(name.to_string(), FontMetrics::from_font(&font));
         self.font_index.insert(name.to_string(), font);
         self.font_bytes.insert(name.to_string(), bytes.to_vec());
-        self.assign_font(name, from, to);
         Ok(())
     }
 
     /// Internal helper to assign a registered font to a range of ZPL identifiers.
     fn assign_font(&mut self, name: &str, from: char, to: char) {
-        let from_idx = FONT_MAP.iter().position(|&x| x == from);
-        let to_idx = FONT_MAP.iter().position(|&x| x == to);
-
-        if from_idx.is_none() || to_idx.is_none() {
-            return;
+        for key in identifier_range(from, to) {
+            self.font_map.insert(key.to_string(), name.to_string());
         }
+    }
+}
 
-        if let (Some(start), Some(end)) = (from_idx, to_idx)
-            && start <= end
-        {
-            for key in &FONT_MAP[start..=end] {
-                self.font_map.insert(key.to_string(), name.to_string());
-            }
-        }
+/// The ZPL identifiers from `from` to `to` in [`FONT_MAP`] order, or none
+/// when either is not an identifier or the range is reversed.
+fn identifier_range(from: char, to: char) -> &'static [char] {
+    let from_idx = FONT_MAP.iter().position(|&x| x == from);
+    let to_idx = FONT_MAP.iter().position(|&x| x == to);
+    match (from_idx, to_idx) {
+        (Some(start), Some(end)) if start <= end => &FONT_MAP[start..=end],
+        _ => &[],
     }
 }
@@ -1,8 +1,181 @@
-use super::{common, state};
+use super::{common, font, state};
 use crate::ZplResult;
 use crate::ast::cmd;
 use crate::tools;
 
+/// Replaces `^FH` escapes (`indicator` followed by two hex digits) in field
+/// data with the bytes they encode. The result is read as UTF-8 when valid
+/// and as Latin-1 otherwise; malformed escapes are kept verbatim.
+fn decode_field_hex(data: &str, indicator: char) -> String {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(c) = rest.chars().next() {
+        let hex = rest
+            .get(c.len_utf8()..c.len_utf8() + 2)
+            .filter(|h| h.bytes().all(|b| b.is_ascii_hexdigit()));
+        match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(byte) if c == indicator => {
+                bytes.push(byte);
+                rest = &rest[c.len_utf8() + 2..];
+            }
+            _ => {
+                bytes.extend_from_slice(&rest.as_bytes()[..c.len_utf8()]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    String::from_utf8(bytes)
+        .unwrap_or_else(|e| e.into_bytes().into_iter().map(char::from).collect())
+}
+
+/// Bar geometry `(y, height)` for a `^BS` add-on printed right after a
+/// UPC/EAN symbol on the same row.
+///
+/// Retail add-ons print their digits above the bars, level with the top of
+/// the parent symbol, and end their bars on the parent's bar bottom.
+fn align_extension(
+    parent: Option<&common::ZplInstruction>,
+    y: u32,
+    interpretation_line: char,
+    interpretation_line_above: char,
+    module_width: u32,
+) -> Option<(u32, u32)> {
+    let Some(common::ZplInstruction::Barcode1D {
+        kind,
+        y: parent_y,
+        height: parent_h,
+        ..
+    }) = parent
+    else {
+        return None;
+    };
+    if !kind.is_upc_ean() || *parent_y != y {
+        return None;
+    }
+    if interpretation_line != 'Y' || interpretation_line_above != 'Y' {
+        return Some((y, *parent_h));
+    }
+    let (text_h, gap) = font::interpretation_metrics(module_width);
+    let offset = text_h + gap;
+    Some((y + offset, parent_h.saturating_sub(offset).max(1)))
+}
+
+/// QR Code field data with its ZPL prefix split off.
+#[derive(Default)]
+struct QrFieldData {
+    /// Error correction level given by the prefix.
+    error_correction: Option<char>,
+    /// Structured append `(symbol number, symbol count, parity)`.
+    structured_append: Option<(u32, u32, u8)>,
+    /// Automatic-mode data.
+    data: String,
+    /// Manual-mode segments.
+    segments: Vec<common::QrSegment>,
+}
+
+/// Splits the ZPL QR Code prefix off `^FD` data.
+///
+/// The prefix is `<ec><input>,` where `ec` is `H`, `Q`, `M` or `L` and the
+/// input mode `A` (automatic) or `M` (manual), optionally preceded by the
+/// mixed-mode header `D<nn><tt><pp>,` (symbol number, symbol count and hex
+/// parity). Manual data is a sequence of segments, each a mode character
+/// followed by its data and optionally a comma: `N` digits, `A`
+/// alphanumerics, `B` a four-digit character count and that many
+/// characters, or `K` Kanji up to the next comma. Data without a valid
+/// prefix is encoded whole in automatic mode.
+fn parse_qr_data(data: &str) -> QrFieldData {
+    let mut rest = data;
+    let mut structured_append = None;
+    if let Some(header) = rest
+        .strip_prefix('D')
+        .and_then(|r| r.get(..7))
+        .filter(|h| h.is_ascii())
+    {
+        let number = header[..2].parse::<u32>().ok();
+        let count = header[2..4].parse::<u32>().ok();
+        let parity = u8::from_str_radix(&header[4..6], 16).ok();
+        if let (Some(number), Some(count), Some(parity), ",") =
+            (number, count, parity, &header[6..])
+        {
+            structured_append = Some((number, count, parity));
+            rest = &rest[8..];
+        }
+    }
+
+    let mut prefix = rest.chars();
+    let (Some(ec @ ('H' | 'Q' | 'M' | 'L')), Some(input @ ('A' | 'M')), Some(',')) =
+        (prefix.next(), prefix.next(), prefix.next())
+    else {
+        return QrFieldData {
+            data: data.to_owned(),
+            ..Default::default()
+        };
+    };
+    rest = &rest[3..];
+    if input == 'A' {
+        return QrFieldData {
+            error_correction: Some(ec),
+            structured_append,
+            data: rest.to_owned(),
+            segments: Vec::new(),
+        };
+    }
+
+    let is_alphanumeric =
+        |c: char| c.is_ascii_digit() || c.is_ascii_uppercase() || " $%*+-./:".contains(c);
+    let mut segments = Vec::new();
+    while let Some(mode) = rest.chars().next() {
+        let body = &rest[mode.len_utf8()..];
+        let len = match mode {
+            'N' => body.find(|c: char| !c.is_ascii_digit()),
+            'A' => body.find(|c: char| !is_alphanumeric(c)),
+            'K' => body.find(','),
+            'B' => {
+                let count = body.get(..4).and_then(|n| n.parse::<usize>().ok());
+                if let Some(count) = count {
+                    let chars = &body[4..];
+                    let len = chars
+                        .char_indices()
+                        .nth(count)
+                        .map_or(chars.len(), |(i, _)| i);
+                    segments.push(common::QrSegment {
+                        mode,
+                        data: chars[..len].to_owned(),
+                    });
+                    rest = &chars[len..];
+                    rest = rest.strip_prefix(',').unwrap_or(rest);
+                    continue;
+                }
+                None
+            }
+            ',' => {
+                rest = body;
+                continue;
+            }
+            _ => {
+                // Not a segment: keep the remainder as bytes.
+                segments.push(common::QrSegment {
+                    mode: 'B',
+                    data: rest.to_owned(),
+                });
+                break;
+            }
+        };
+        let len = len.unwrap_or(body.len());
+        segments.push(common::QrSegment {
+            mode,
+            data: body[..len].to_owned(),
+        });
+        rest = &body[len..];
+    }
+    QrFieldData {
+        error_correction: Some(ec),
+        structured_append,
+        data: String::new(),
+        segments,
+    }
+}
+
 /// A builder that converts a sequence of AST commands into renderable instructions.
 ///
 /// It maintains a state machine to track the current label configuration (position,
@@ -59,6 +232,10 @@ impl ZplInstructionBuilder {
                     }
                 }
 
+                cmd::Command::FieldMultipleOrigin { origins } => {
+                    self.state.field_origins = Some(origins);
+                }
+
                 cmd::Command::FieldReverse => {
                     self.state.reverse = !self.state.reverse;
                 }
@@ -95,8 +272,15 @@ impl ZplInstructionBuilder {
                     }
                 }
 
+                cmd::Command::FieldHex { indicator } => {
+                    self.state.field_hex = Some(indicator.unwrap_or('_'));
+                }
+
                 cmd::Command::FieldData { data } => {
-                    self.state.value = Some(data);
+                    self.state.value = Some(match self.state.field_hex {
+                        Some(indicator) => decode_field_hex(&data, indicator),
+                        None => data,
+                    });
                 }
 
                 cmd::Command::FieldBlock {
@@ -115,6 +299,18 @@ impl ZplInstructionBuilder {
                     });
                 }
 
+                cmd::Command::TextBlock {
+                    orientation,
+                    width,
+                    height,
+                } => {
+                    self.state.text_block = Some(state::ZplInstructionTextBlock {
+                        orientation,
+                        width: width.unwrap_or(0),
+                        height: height.unwrap_or(0),
+                    });
+                }
+
                 cmd::Command::GraphicBox {
                     width,
                     height,
@@ -158,6 +354,18 @@ impl ZplInstructionBuilder {
                     self.state.font.color = Some(color);
                 }
 
+                cmd::Command::TextDecoration {
+                    letter_spacing,
+                    underline,
+                    strike_through,
+                } => {
+                    self.state.font.decoration = common::TextDecoration {
+                        letter_spacing: letter_spacing.unwrap_or(0),
+                        underline: underline.map(char::from) == Some('Y'),
+                        strike_through: strike_through.map(char::from) == Some('Y'),
+                    };
+                }
+
                 cmd::Command::GraphicLineColor { color } => {
                     self.state.attributes.custom_line_color = Some(color);
                 }
@@ -272,9 +480,17 @@ impl ZplInstructionBuilder {
                     height,
                     interpretation_line,
                     interpretation_line_above,
-                    check_digit: _,
+                    check_digit,
+                    print_check_digit,
+                    start_character,
+                    stop_character,
                 } => {
                     self.state.attributes.orientation = orientation;
+                    self.state.attributes.check_digit = check_digit;
+                    self.state.attributes.print_check_digit = print_check_digit;
+                    self.state.attributes.start_character = start_character;
+                    self.state.attributes.stop_character = stop_character;
+                    self.state.params.explicit_height = height.is_some();
                     self.state.metrics.height =
                         height.unwrap_or(if self.state.barcode_metrics.height > 0 {
                             self.state.barcode_metrics.height
@@ -286,6 +502,71 @@ impl ZplInstructionBuilder {
                     self.state.instruction_type = Some(state::ZplInstructionType::Barcode1D(kind));
                 }
 
+                cmd::Command::Aztec {
+                    orientation,
+                    magnification,
+                    extended_channel,
+                    error_control,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.metrics.thickness =
+                        magnification.unwrap_or(if self.state.barcode_metrics.thickness > 0 {
+                            self.state.barcode_metrics.thickness
+                        } else {
+                            2
+                        });
+                    self.state.attributes.extended_channel = extended_channel;
+                    self.state.params.error_control = error_control.unwrap_or(0);
+                    self.state.instruction_type = Some(state::ZplInstructionType::Aztec);
+                }
+
+                cmd::Command::MaxiCode {
+                    mode,
+                    symbol_number,
+                    symbol_count,
+                } => {
+                    self.state.params.model = mode.unwrap_or(2);
+                    self.state.params.symbol_number = symbol_number.unwrap_or(1);
+                    self.state.params.symbol_count = symbol_count.unwrap_or(1);
+                    self.state.instruction_type = Some(state::ZplInstructionType::MaxiCode);
+                }
+
+                cmd::Command::DataBar {
+                    orientation,
+                    symbology,
+                    magnification,
+                    separator_height,
+                    height,
+                    segment_width,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.params.model = symbology.unwrap_or(1);
+                    self.state.metrics.thickness = magnification.unwrap_or(2);
+                    self.state.params.separator_height = separator_height.unwrap_or(1);
+                    self.state.metrics.height = height.unwrap_or(25);
+                    self.state.params.segment_width = segment_width.unwrap_or(22);
+                    self.state.instruction_type = Some(state::ZplInstructionType::DataBar);
+                }
+
+                cmd::Command::PostalBarcode {
+                    kind,
+                    orientation,
+                    height,
+                    interpretation_line,
+                    interpretation_line_above,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.metrics.height =
+                        height.unwrap_or(if self.state.barcode_metrics.height > 0 {
+                            self.state.barcode_metrics.height
+                        } else {
+                            10
+                        });
+                    self.state.attributes.interpretation_line = interpretation_line;
+                    self.state.attributes.interpretation_above = interpretation_line_above;
+                    self.state.instruction_type = Some(state::ZplInstructionType::Postal(kind));
+                }
+
                 cmd::Command::GraphicDiagonal {
                     width,
                     height,
@@ -304,9 +585,12 @@ impl ZplInstructionBuilder {
                 cmd::Command::DataMatrix {
                     orientation,
                     height,
-                    quality: _,
-                    columns: _,
-                    rows: _,
+                    quality,
+                    columns,
+                    rows,
+                    format: _,
+                    escape,
+                    aspect_ratio,
                 } => {
                     self.state.attributes.orientation = orientation;
                     // ^BX height = module size; fall back to ^BY module width.
@@ -316,6 +600,16 @@ impl ZplInstructionBuilder {
                         } else {
                             2
                         });
+                    self.state.params.quality = quality.unwrap_or(200);
+                    // Symbol sizes are even, 8-144 rows and 10-144 columns.
+                    self.state.params.columns = columns
+                        .filter(|c| (10..=144).contains(c) && c % 2 == 0)
+                        .unwrap_or(0);
+                    self.state.params.rows = rows
+                        .filter(|r| (8..=144).contains(r) && r % 2 == 0)
+                        .unwrap_or(0);
+                    self.state.params.rectangular = aspect_ratio == Some(2);
+                    self.state.params.escape = escape;
                     self.state.instruction_type = Some(state::ZplInstructionType::DataMatrix);
                 }
 
@@ -323,9 +617,9 @@ impl ZplInstructionBuilder {
                     orientation,
                     height,
                     security_level,
-                    columns: _,
-                    rows: _,
-                    truncate: _,
+                    columns,
+                    rows,
+                    truncate,
                 } => {
                     self.state.attributes.orientation = orientation;
                     self.state.metrics.height =
@@ -335,9 +629,65 @@ impl ZplInstructionBuilder {
                             8
                         });
                     self.state.params.model = security_level.unwrap_or(0);
+                    // Out-of-range shapes fall back to being derived.
+                    self.state.params.columns =
+                        columns.filter(|c| (1..=30).contains(c)).unwrap_or(0);
+                    self.state.params.rows = rows.filter(|r| (3..=90).contains(r)).unwrap_or(0);
+                    self.state.params.truncate = truncate.map(char::from) == Some('Y');
                     self.state.instruction_type = Some(state::ZplInstructionType::Pdf417);
                 }
 
+                cmd::Command::MicroPdf417 {
+                    orientation,
+                    height,
+                    mode,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.metrics.height =
+                        height.unwrap_or(if self.state.barcode_metrics.height > 0 {
+                            self.state.barcode_metrics.height
+                        } else {
+                            8
+                        });
+                    self.state.params.model = mode.unwrap_or(0);
+                    self.state.instruction_type = Some(state::ZplInstructionType::MicroPdf417);
+                }
+
+                cmd::Command::Codablock {
+                    orientation,
+                    height,
+                    security,
+                    columns,
+                    rows,
+                    mode,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.metrics.height = height.unwrap_or(8);
+                    self.state.attributes.check_digit = Some(security.map_or('Y', char::from));
+                    self.state.params.columns = columns.unwrap_or(0);
+                    self.state.params.rows = rows.unwrap_or(0);
+                    self.state.attributes.mode = Some(mode.unwrap_or('F'));
+                    self.state.instruction_type = Some(state::ZplInstructionType::Codablock);
+                }
+
+                cmd::Command::Tlc39 {
+                    orientation,
+                    code39_width,
+                    ratio,
+                    code39_height,
+                    micro_width,
+                    micro_height,
+                } => {
+                    self.state.attributes.orientation = orientation;
+                    self.state.metrics.thickness = code39_width.unwrap_or(2).clamp(1, 10);
+                    self.state.params.component_ratio = ratio.map_or(2.0, |r| r as f64);
+                    self.state.metrics.height = code39_height.unwrap_or(40).max(1);
+                    self.state.metrics.width = micro_width.unwrap_or(2).clamp(1, 10);
+                    self.state.params.component_row_height =
+                        micro_height.unwrap_or(4).clamp(1, 255);
+                    self.state.instruction_type = Some(state::ZplInstructionType::Tlc39);
+                }
+
                 cmd::Command::QRCode {
                     orientation,
                     model,
@@ -373,6 +723,24 @@ impl ZplInstructionBuilder {
                     self.state.condition = Some((variable, value));
                 }
 
+                cmd::Command::Gs1Data => {
+                    self.state.gs1 = true;
+                }
+
+                cmd::Command::TextFit {
+                    width,
+                    height,
+                    min_height,
+                    max_height,
+                } => {
+                    self.state.text_fit = Some(common::TextFit {
+                        width,
+                        height,
+                        min_height,
+                        max_height,
+                    });
+                }
+
                 // Apply the instruction with the current state
                 cmd::Command::FieldSeparator => {
                     let x = self.state.position.x;
@@ -380,6 +748,41 @@ impl ZplInstructionBuilder {
                     let data = self.state.value.take().unwrap_or_default();
                     let reverse_print = self.state.reverse;
                     let condition = self.state.condition.take();
+                    let gs1 = std::mem::take(&mut self.state.gs1);
+                    if gs1
+                        && !matches!(
+                            self.state.instruction_type,
+                            Some(
+                                state::ZplInstructionType::Code128
+                                    | state::ZplInstructionType::DataBar
+                                    | state::ZplInstructionType::DataMatrix
+                                    | state::ZplInstructionType::QRCode
+                            )
+                        )
+                    {
+                        return Err(crate::ZplError::InvalidGs1Data {
+                            ai: None,
+                            message: "^GSC needs a ^BC, ^BR, ^BX or ^BQ field".to_owned(),
+                        });
+                    }
+
+                    // ^TB: a left-aligned block holding as many lines as its
+                    // height fits at the current font height.
+                    let mut text_orientation = self.state.font.orientation.unwrap_or('N');
+                    if let Some(tb) = self.state.text_block.take() {
+                        let font_h = self.state.font.height.unwrap_or(9).max(1);
+                        self.state.field_block = Some(common::TextBlock {
+                            width: tb.width,
+                            max_lines: match tb.height {
+                                0 => u32::MAX,
+                                h => (h / font_h).max(1),
+                            },
+                            line_spacing: 0,
+                            justification: 'L',
+                            indent: 0,
+                        });
+                        text_orientation = tb.orientation.unwrap_or(text_orientation);
+                    }
 
                     if let Some(instr_type) = &self.state.instruction_type {
                         match instr_type {
@@ -467,8 +870,13 @@ impl ZplInstructionBuilder {
                                         .interpretation_above
                                         .unwrap_or('N'),
                                     check_digit: self.state.attributes.check_digit.unwrap_or('N'),
-                                    mode: self.state.attributes.mode.unwrap_or('N'),
+                                    mode: if gs1 {
+                                        'D'
+                                    } else {
+                                        self.state.attributes.mode.unwrap_or('N')
+                                    },
                                     data,
+                                    gs1,
                                     reverse_print,
                                     condition,
                                 });
@@ -485,6 +893,7 @@ impl ZplInstructionBuilder {
                                     } else {
                                         2
                                     },
+                                    ratio: self.state.params.ratio.unwrap_or(3.0),
                                     interpretation_line: self
                                         .state
                                         .attributes
@@ -501,7 +910,118 @@ impl ZplInstructionBuilder {
                                 });
                             }
                             state::ZplInstructionType::Barcode1D(kind) => {
+                                let module_width = if self.state.barcode_metrics.thickness > 0 {
+                                    self.state.barcode_metrics.thickness
+                                } else {
+                                    2
+                                };
+                                let is_extension = *kind == common::Barcode1DKind::UpcEanExtension;
+                                let interpretation_line =
+                                    self.state.attributes.interpretation_line.unwrap_or('Y');
+                                // ^BS prints its digits above the bars by default.
+                                let interpretation_line_above = self
+                                    .state
+                                    .attributes
+                                    .interpretation_above
+                                    .unwrap_or(if is_extension { 'Y' } else { 'N' });
+                                let (y, height) =
+                                    if is_extension && !self.state.params.explicit_height {
+                                        align_extension(
+                                            instructions.last(),
+                                            y,
+                                            interpretation_line,
+                                            interpretation_line_above,
+                                            module_width,
+                                        )
+                                        .unwrap_or((y, self.state.metrics.height))
+                                    } else {
+                                        (y, self.state.metrics.height)
+                                    };
+                                // Codabar start/stop characters are parameters, not data.
+                                let data = if *kind == common::Barcode1DKind::Codabar {
+                                    format!(
+                                        "{}{}{}",
+                                        self.state.attributes.start_character.unwrap_or('A'),
+                                        data,
+                                        self.state.attributes.stop_character.unwrap_or('A')
+                                    )
+                                } else {
+                                    data
+                                };
                                 instructions.push(common::ZplInstruction::Barcode1D {
+                                    kind: *kind,
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    height,
+                                    module_width,
+                                    ratio: self.state.params.ratio.unwrap_or(3.0),
+                                    interpretation_line,
+                                    interpretation_line_above,
+                                    check_digit: self.state.attributes.check_digit.unwrap_or(
+                                        if *kind == common::Barcode1DKind::Msi {
+                                            'B'
+                                        } else {
+                                            'N'
+                                        },
+                                    ),
+                                    print_check_digit: self
+                                        .state
+                                        .attributes
+                                        .print_check_digit
+                                        .unwrap_or('N'),
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::Aztec => {
+                                instructions.push(common::ZplInstruction::Aztec {
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    magnification: self.state.metrics.thickness,
+                                    extended_channel: self
+                                        .state
+                                        .attributes
+                                        .extended_channel
+                                        .unwrap_or('N'),
+                                    error_control: self.state.params.error_control,
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::MaxiCode => {
+                                instructions.push(common::ZplInstruction::MaxiCode {
+                                    x,
+                                    y,
+                                    mode: self.state.params.model,
+                                    symbol_number: self.state.params.symbol_number,
+                                    symbol_count: self.state.params.symbol_count,
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::DataBar => {
+                                instructions.push(common::ZplInstruction::DataBar {
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    symbology: self.state.params.model,
+                                    magnification: self.state.metrics.thickness,
+                                    separator_height: self.state.params.separator_height,
+                                    height: self.state.metrics.height,
+                                    segment_width: self.state.params.segment_width,
+                                    data,
+                                    gs1,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::Postal(kind) => {
+                                instructions.push(common::ZplInstruction::PostalBarcode {
                                     kind: *kind,
                                     x,
                                     y,
@@ -516,7 +1036,7 @@ impl ZplInstructionBuilder {
                                         .state
                                         .attributes
                                         .interpretation_line
-                                        .unwrap_or('Y'),
+                                        .unwrap_or('N'),
                                     interpretation_line_above: self
                                         .state
                                         .attributes
@@ -547,7 +1067,13 @@ impl ZplInstructionBuilder {
                                     y,
                                     orientation: self.state.attributes.orientation.unwrap_or('N'),
                                     module_size: self.state.metrics.thickness,
+                                    quality: self.state.params.quality,
+                                    columns: self.state.params.columns,
+                                    rows: self.state.params.rows,
+                                    rectangular: self.state.params.rectangular,
+                                    escape: self.state.params.escape.unwrap_or('~'),
                                     data,
+                                    gs1,
                                     reverse_print,
                                     condition,
                                 });
@@ -564,25 +1090,93 @@ impl ZplInstructionBuilder {
                                         2
                                     },
                                     security_level: self.state.params.model,
+                                    columns: self.state.params.columns,
+                                    rows: self.state.params.rows,
+                                    truncate: self.state.params.truncate,
+                                    macro_origins: self
+                                        .state
+                                        .field_origins
+                                        .take()
+                                        .unwrap_or_default(),
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::MicroPdf417 => {
+                                instructions.push(common::ZplInstruction::MicroPdf417 {
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    row_height: self.state.metrics.height,
+                                    module_width: if self.state.barcode_metrics.thickness > 0 {
+                                        self.state.barcode_metrics.thickness
+                                    } else {
+                                        2
+                                    },
+                                    mode: self.state.params.model,
+                                    macro_origins: self
+                                        .state
+                                        .field_origins
+                                        .take()
+                                        .unwrap_or_default(),
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::Codablock => {
+                                instructions.push(common::ZplInstruction::Codablock {
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    row_height: self.state.metrics.height,
+                                    module_width: if self.state.barcode_metrics.thickness > 0 {
+                                        self.state.barcode_metrics.thickness
+                                    } else {
+                                        2
+                                    },
+                                    security: self.state.attributes.check_digit.unwrap_or('Y'),
+                                    columns: self.state.params.columns,
+                                    rows: self.state.params.rows,
+                                    mode: self.state.attributes.mode.unwrap_or('F'),
+                                    data,
+                                    reverse_print,
+                                    condition,
+                                });
+                            }
+                            state::ZplInstructionType::Tlc39 => {
+                                instructions.push(common::ZplInstruction::Tlc39 {
+                                    x,
+                                    y,
+                                    orientation: self.state.attributes.orientation.unwrap_or('N'),
+                                    module_width: self.state.metrics.thickness,
+                                    ratio: self.state.params.component_ratio,
+                                    height: self.state.metrics.height,
+                                    micro_module_width: self.state.metrics.width,
+                                    row_height: self.state.params.component_row_height,
                                     data,
                                     reverse_print,
                                     condition,
                                 });
                             }
                             state::ZplInstructionType::QRCode => {
+                                let qr = parse_qr_data(&data);
                                 instructions.push(common::ZplInstruction::QRCode {
                                     x,
                                     y,
                                     orientation: self.state.attributes.orientation.unwrap_or('N'),
                                     model: self.state.params.model,
                                     magnification: self.state.metrics.thickness,
-                                    error_correction: self
-                                        .state
-                                        .attributes
+                                    error_correction: qr
                                         .error_correction
+                                        .or(self.state.attributes.error_correction)
                                         .unwrap_or('M'),
                                     mask: self.state.params.mask,
-                                    data,
+                                    data: qr.data,
+                                    segments: qr.segments,
+                                    structured_append: qr.structured_append,
+                                    gs1,
                                     reverse_print,
                                     condition,
                                 });
@@ -594,11 +1188,13 @@ impl ZplInstructionBuilder {
                                     font: self.state.font.font_name,
                                     height: self.state.font.height,
                                     width: self.state.font.width,
-                                    orientation: self.state.font.orientation.unwrap_or('N'),
+                                    orientation: text_orientation,
                                     text: data,
                                     reverse_print,
                                     color: self.state.font.color.clone(),
+                                    decoration: self.state.font.decoration,
                                     block: self.state.field_block.take(),
+                                    fit: self.state.text_fit.take(),
                                     condition,
                                 });
                             }
@@ -610,11 +1206,13 @@ impl ZplInstructionBuilder {
                             font: self.state.font.font_name,
                             height: self.state.font.height,
                             width: self.state.font.width,
-                            orientation: self.state.font.orientation.unwrap_or('N'),
+                            orientation: text_orientation,
                             text: data.clone(),
                             reverse_print,
                             color: self.state.font.color.clone(),
+                            decoration: self.state.font.decoration,
                             block: self.state.field_block.take(),
+                            fit: self.state.text_fit.take(),
                             condition,
                         });
                     }
@@ -622,6 +1220,10 @@ impl ZplInstructionBuilder {
                     self.state.instruction_type = None;
                     self.state.reverse = false;
                     self.state.field_block = None;
+                    self.state.text_fit = None;
+                    self.state.font.decoration = common::TextDecoration::default();
+                    self.state.field_hex = None;
+                    self.state.field_origins = None;
                 }
 
                 _ => {}
@@ -12,14 +12,21 @@
 //! 4. Produces a final `Vec<ZplInstruction>` ready for the rendering backends.
 
 mod backend;
+#[cfg(any(feature = "png", feature = "pdf"))]
+mod bitmap_font;
 mod common;
 #[allow(clippy::module_inception)]
 mod engine;
 pub(crate) mod font;
 mod intr;
 mod state;
+mod wrap;
 
 pub use backend::ZplForgeBackend;
-pub use common::{Barcode1DKind, Resolution, TextBlock, Unit, ZplInstruction};
+pub use common::{
+    Barcode1DKind, PostalKind, QrSegment, Resolution, TextBlock, TextDecoration, TextFit, Unit,
+    ZplInstruction,
+};
 pub use engine::ZplEngine;
 pub use font::FontManager;
+pub use wrap::HyphenationDictionary;
@@ -32,6 +32,16 @@ pub struct ZplInstructionState {
     pub condition: Option<(String, String)>,
     /// `^FB` block formatting for the next text field.
     pub field_block: Option<crate::engine::common::TextBlock>,
+    /// `^TB` block for the next text field, resolved against the font at `^FS`.
+    pub text_block: Option<ZplInstructionTextBlock>,
+    /// `^TFC` shrink-to-fit box for the current text field.
+    pub text_fit: Option<crate::engine::common::TextFit>,
+    /// `^FH` escape character for the current field's data.
+    pub field_hex: Option<char>,
+    /// `^FM` symbol origins for the current Macro PDF417/MicroPDF417 field.
+    pub field_origins: Option<Vec<Option<(u32, u32)>>>,
+    /// Whether the current field holds GS1 AI data (`^GSC`).
+    pub gs1: bool,
 }
 
 /// Represents absolute positioning for a field.
@@ -43,6 +53,16 @@ pub struct ZplInstructionPosition {
     pub y: u32,
 }
 
+/// A `^TB` text block as given, before its line count is known.
+pub struct ZplInstructionTextBlock {
+    /// Field orientation overriding the font's.
+    pub orientation: Option<char>,
+    /// Block width in dots.
+    pub width: u32,
+    /// Block height in dots (`0` leaves the line count unlimited).
+    pub height: u32,
+}
+
 /// Represents typeset positioning for a field.
 #[derive(Default)]
 pub struct ZplInstructionTypeset {
@@ -74,6 +94,14 @@ pub struct ZplInstructionAttributes {
     pub interpretation_above: Option<char>,
     /// Check digit verification flag.
     pub check_digit: Option<char>,
+    /// Whether the check digit is printed in the interpretation line.
+    pub print_check_digit: Option<char>,
+    /// Codabar start character.
+    pub start_character: Option<char>,
+    /// Codabar stop character.
+    pub stop_character: Option<char>,
+    /// Whether Aztec data carries Extended Channel Interpretation codes.
+    pub extended_channel: Option<char>,
     /// Barcode-specific mode (e.g., UCC Case Mode).
     pub mode: Option<char>,
     /// Error correction level (e.g., for QR or PDF417).
@@ -93,8 +121,36 @@ pub struct ZplInstructionParams {
     pub model: u32,
     /// Data mask for 2D barcodes.
     pub mask: u32,
+    /// Aztec error control and symbol size.
+    pub error_control: u32,
     /// Wide-to-narrow bar ratio.
     pub ratio: Option<f64>,
+    /// Whether the barcode command gave its own height (instead of `^BY`).
+    pub explicit_height: bool,
+    /// Structured append symbol number.
+    pub symbol_number: u32,
+    /// Structured append symbol count.
+    pub symbol_count: u32,
+    /// Separator height for stacked symbologies, in modules.
+    pub separator_height: u32,
+    /// Segments per row for GS1 DataBar Expanded Stacked.
+    pub segment_width: u32,
+    /// Data columns (or characters per row) for stacked symbologies.
+    pub columns: u32,
+    /// Rows for stacked symbologies.
+    pub rows: u32,
+    /// Whether PDF417 is printed truncated.
+    pub truncate: bool,
+    /// Data Matrix quality level (ECC type).
+    pub quality: u32,
+    /// Whether a derived Data Matrix size is rectangular.
+    pub rectangular: bool,
+    /// Escape character for control sequences in barcode data.
+    pub escape: Option<char>,
+    /// Wide-to-narrow ratio of the Code 39 in a TLC39 composite.
+    pub component_ratio: f64,
+    /// Row height of the MicroPDF417 in a TLC39 composite.
+    pub component_row_height: u32,
 }
 
 /// Font specification state.
@@ -110,6 +166,8 @@ pub struct ZplInstructionFont {
     pub width: Option<u32>,
     /// Custom text color in hex format.
     pub color: Option<String>,
+    /// Letter spacing and decoration lines of the current field (`^TDC`).
+    pub decoration: crate::engine::common::TextDecoration,
 }
 
 /// Supported instruction types for rendering.
@@ -135,8 +193,22 @@ pub enum ZplInstructionType {
     DataMatrix,
     /// PDF417 barcode.
     Pdf417,
-    /// Generic 1-D barcode (EAN-13, UPC-A, ITF, Code 93).
+    /// MicroPDF417 barcode.
+    MicroPdf417,
+    /// CODABLOCK barcode.
+    Codablock,
+    /// TLC39 composite barcode.
+    Tlc39,
+    /// Generic 1-D barcode (see [`Barcode1DKind`](crate::engine::Barcode1DKind)).
     Barcode1D(crate::engine::common::Barcode1DKind),
+    /// Aztec barcode.
+    Aztec,
+    /// MaxiCode barcode.
+    MaxiCode,
+    /// GS1 DataBar barcode.
+    DataBar,
+    /// Height-modulated postal barcode (POSTNET, PLANET, IMb).
+    Postal(crate::engine::common::PostalKind),
     /// Diagonal line.
     GraphicDiagonal,
     /// Custom color image data.
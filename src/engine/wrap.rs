@@ -0,0 +1,234 @@
+//! # Text Block Wrapping
+//!
+//! Line breaking for `^FB` and `^TB` blocks. Lines end at the break
+//! opportunities of the Unicode Line Breaking Algorithm (UAX #14): after
+//! spaces and hyphens, between CJK ideographs (never before closing
+//! punctuation or small kana, never after opening brackets), and at soft
+//! hyphens (U+00AD), which print as a hyphen only where a line ends. An
+//! optional [`HyphenationDictionary`] adds soft hyphens to the words it
+//! knows. `\&` stays an explicit line break.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use unicode_linebreak::{BreakClass, BreakOpportunity, break_property, linebreaks};
+
+/// Soft hyphen: an invisible break opportunity shown as `-` at a line end.
+const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Characters after which an overlong run without break opportunities (a
+/// URL, a part number) is preferably broken.
+const EMERGENCY_BREAK_AFTER: &[char] = &['/', '.', '?', '&', '=', '_', '-', ':', '#', ',', ';'];
+
+/// Hyphenation points for words in `^FB`/`^TB` text blocks.
+///
+/// Words are listed hyphenated, e.g. `"hy-phen-ation"`, like TeX's
+/// `\hyphenation` exceptions, and matched case-insensitively. Words the
+/// dictionary knows may break at their hyphenation points, with a hyphen
+/// printed at the end of the line.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use zpl_forge::{HyphenationDictionary, Resolution, Unit, ZplEngine};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let zpl = "^XA^FO20,20^A0N,30,30^FB200,3^FDTemperature-controlled pharmaceuticals^FS^XZ";
+/// let mut engine = ZplEngine::new(zpl, Unit::Inches(2.0), Unit::Inches(1.0), Resolution::Dpi203)?;
+/// engine.set_hyphenation(Arc::new(HyphenationDictionary::from_words(
+///     "tem-per-a-ture phar-ma-ceu-ti-cals",
+/// )));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HyphenationDictionary {
+    /// Hyphenation points (character counts) by lowercase word.
+    words: HashMap<String, Vec<usize>>,
+}
+
+impl HyphenationDictionary {
+    /// Creates an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a dictionary from whitespace-separated hyphenated words.
+    pub fn from_words(list: &str) -> Self {
+        let mut dictionary = Self::new();
+        for word in list.split_whitespace() {
+            dictionary.insert(word);
+        }
+        dictionary
+    }
+
+    /// Adds a word with its hyphenation points marked by `-`, replacing any
+    /// previous entry for the word.
+    pub fn insert(&mut self, hyphenated: &str) {
+        let mut word = String::new();
+        let mut points = Vec::new();
+        let mut count = 0;
+        for c in hyphenated.chars() {
+            if c == '-' {
+                if count > 0 && points.last() != Some(&count) {
+                    points.push(count);
+                }
+            } else {
+                word.push(c);
+                count += 1;
+            }
+        }
+        points.retain(|&p| p < count);
+        if !word.is_empty() {
+            self.words.insert(word.to_lowercase(), points);
+        }
+    }
+
+    /// Inserts soft hyphens at the hyphenation points of the known words
+    /// in `text`. Words already holding soft hyphens are left alone.
+    fn hyphenate<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.words.is_empty() {
+            return Cow::Borrowed(text);
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(|c: char| c.is_alphabetic() || c == SOFT_HYPHEN) {
+            out.push_str(&rest[..start]);
+            let word_len = rest[start..]
+                .find(|c: char| !c.is_alphabetic() && c != SOFT_HYPHEN)
+                .unwrap_or(rest.len() - start);
+            let word = &rest[start..start + word_len];
+            match self.words.get(&word.to_lowercase()) {
+                Some(points) if !word.contains(SOFT_HYPHEN) => {
+                    for (i, c) in word.chars().enumerate() {
+                        if points.contains(&i) {
+                            out.push(SOFT_HYPHEN);
+                        }
+                        out.push(c);
+                    }
+                }
+                _ => out.push_str(word),
+            }
+            rest = &rest[start + word_len..];
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+}
+
+/// Wraps `^FB`/`^TB` text into lines of at most `max_width` dots (`0`
+/// disables wrapping), breaking at UAX #14 opportunities and at `\&`.
+///
+/// Runs without any opportunity that are wider than a full line break
+/// after URL-style punctuation, or else between characters.
+pub(crate) fn wrap_text_block<F: Fn(&str) -> u32>(
+    text: &str,
+    max_width: u32,
+    hyphenation: Option<&HyphenationDictionary>,
+    measure: F,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for segment in text.split("\\&") {
+        let segment = segment.trim();
+        if max_width == 0 {
+            lines.push(printed(segment));
+            continue;
+        }
+        let segment = match hyphenation {
+            Some(dictionary) => dictionary.hyphenate(segment),
+            None => Cow::Borrowed(segment),
+        };
+        wrap_segment(&segment, max_width, &measure, &mut lines);
+    }
+    lines
+}
+
+/// The printed form of a line: trailing whitespace and soft hyphens
+/// removed, plus a hyphen when the line ends at a soft hyphen.
+fn printed(line: &str) -> String {
+    let line = line.trim_end();
+    let mut out: String = line.chars().filter(|&c| c != SOFT_HYPHEN).collect();
+    if line.ends_with(SOFT_HYPHEN) {
+        out.push('-');
+    }
+    out
+}
+
+/// Greedy wrapping of one `\&`-free segment: each line takes as many break
+/// opportunities as fit.
+fn wrap_segment<F: Fn(&str) -> u32>(
+    text: &str,
+    max_width: u32,
+    measure: &F,
+    lines: &mut Vec<String>,
+) {
+    if text.is_empty() {
+        lines.push(String::new());
+        return;
+    }
+    let fits = |line: &str| measure(&printed(line)) <= max_width;
+    let opportunities: Vec<(usize, BreakOpportunity)> = linebreaks(text).collect();
+
+    let mut start = 0;
+    let mut last_fit = None;
+    let mut i = 0;
+    while i < opportunities.len() {
+        let (end, kind) = opportunities[i];
+        // An emergency break may already have ended a line here.
+        if end == start {
+            i += 1;
+            continue;
+        }
+        if fits(&text[start..end]) {
+            last_fit = Some(end);
+            if kind == BreakOpportunity::Mandatory {
+                lines.push(printed(&text[start..end]));
+                start = end;
+                last_fit = None;
+            }
+            i += 1;
+            continue;
+        }
+        // Overflow: end the line at the last opportunity that fit and retry
+        // this one on the next line.
+        let end_of_line = match last_fit.take() {
+            Some(fit) => fit,
+            None => start + emergency_break(&text[start..end], &fits),
+        };
+        lines.push(printed(&text[start..end_of_line]));
+        start = end_of_line;
+    }
+}
+
+/// Length in bytes of the longest prefix of `run` that fits, preferring to
+/// end after URL-style punctuation and never splitting a combining
+/// sequence; at least one character.
+fn emergency_break<F: Fn(&str) -> bool>(run: &str, fits: &F) -> usize {
+    let mut chars = run.char_indices().peekable();
+    let mut after_char = 0;
+    let mut after_punct = None;
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let Some(&(_, next)) = chars.peek() else {
+            break;
+        };
+        if matches!(
+            break_property(next as u32),
+            BreakClass::CombiningMark | BreakClass::ZeroWidthJoiner
+        ) {
+            continue;
+        }
+        if after_char > 0 && !fits(&run[..end]) {
+            break;
+        }
+        after_char = end;
+        if EMERGENCY_BREAK_AFTER.contains(&c) {
+            after_punct = Some(end);
+        }
+    }
+    if after_char == 0 {
+        return run.len();
+    }
+    after_punct.unwrap_or(after_char)
+}
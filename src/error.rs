@@ -22,6 +22,18 @@ pub enum ZplError {
     #[error("Rendering backend error: {0}")]
     BackendError(String),
 
+    /// GS1 Application Identifier data that fails validation.
+    #[error(
+        "Invalid GS1 data{}: {message}",
+        .ai.as_ref().map(|ai| format!(" in AI ({})", ai)).unwrap_or_default()
+    )]
+    InvalidGs1Data {
+        /// Application Identifier the error refers to, if any.
+        ai: Option<String>,
+        /// Description of the validation failure.
+        message: String,
+    },
+
     /// Error related to font loading or registration.
     #[error("Font error: {0}")]
     FontError(String),
@@ -0,0 +1,473 @@
+//! Native Code 128 (`^BC`) encoding.
+//!
+//! `rxing` only takes plain text and a forced code set, so it can neither
+//! follow the Zebra invocation codes (`>;`, `>8`, ...) that select subsets
+//! and function characters inside `^FD`, nor build the UCC/GS1 layouts of
+//! modes `U` and `D`. This module turns `^FD` data into Code 128 symbol
+//! values for each `^BC` mode and returns the interpretation line a printer
+//! prints with it.
+
+use super::check_digit;
+use crate::gs1;
+use crate::{ZplError, ZplResult};
+
+/// Bar/space patterns of symbol values 0-105, 11 modules each, `1` = bar.
+pub(crate) const PATTERNS: [u16; 106] = [
+    0x6CC, 0x66C, 0x666, 0x498, 0x48C, 0x44C, 0x4C8, 0x4C4, 0x464, 0x648, 0x644, 0x624, 0x59C,
+    0x4DC, 0x4CE, 0x5CC, 0x4EC, 0x4E6, 0x672, 0x65C, 0x64E, 0x6E4, 0x674, 0x76E, 0x74C, 0x72C,
+    0x726, 0x764, 0x734, 0x732, 0x6D8, 0x6C6, 0x636, 0x518, 0x458, 0x446, 0x588, 0x468, 0x462,
+    0x688, 0x628, 0x622, 0x5B8, 0x58E, 0x46E, 0x5D8, 0x5C6, 0x476, 0x776, 0x68E, 0x62E, 0x6E8,
+    0x6E2, 0x6EE, 0x758, 0x746, 0x716, 0x768, 0x762, 0x71A, 0x77A, 0x642, 0x78A, 0x530, 0x50C,
+    0x4B0, 0x486, 0x42C, 0x426, 0x590, 0x584, 0x4D0, 0x4C2, 0x434, 0x432, 0x612, 0x650, 0x7BA,
+    0x614, 0x47A, 0x53C, 0x4BC, 0x49E, 0x5E4, 0x4F4, 0x4F2, 0x7A4, 0x794, 0x792, 0x6DE, 0x6F6,
+    0x7B6, 0x578, 0x51E, 0x45E, 0x5E8, 0x5E2, 0x7A8, 0x7A2, 0x5DE, 0x5EE, 0x75E, 0x7AE, 0x684,
+    0x690, 0x69C,
+];
+/// Stop pattern, 13 modules including the final bar.
+pub(crate) const STOP: u16 = 0x18EB;
+
+pub(crate) const CODE_C: u8 = 99;
+pub(crate) const CODE_B: u8 = 100;
+pub(crate) const CODE_A: u8 = 101;
+pub(crate) const FNC1: u8 = 102;
+pub(crate) const SHIFT: u8 = 98;
+pub(crate) const START_A: u8 = 103;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Subset {
+    A,
+    B,
+    C,
+}
+
+impl Subset {
+    fn start(self) -> u8 {
+        START_A + self as u8
+    }
+
+    /// Code character switching to this subset.
+    pub(crate) fn switch(self) -> u8 {
+        match self {
+            Subset::A => CODE_A,
+            Subset::B => CODE_B,
+            Subset::C => CODE_C,
+        }
+    }
+
+    /// Value of an ASCII character in subset A or B.
+    pub(crate) fn value(self, c: u8) -> Option<u8> {
+        match (self, c) {
+            (Subset::A, 0..=31) => Some(c + 64),
+            (Subset::A, 32..=95) | (Subset::B, 32..=127) => Some(c - 32),
+            _ => None,
+        }
+    }
+
+    /// Character a symbol value stands for in subset A or B (for the
+    /// interpretation line).
+    fn character(self, value: u8) -> Option<char> {
+        match (self, value) {
+            (Subset::A, 0..=63) | (Subset::B, 0..=94) => Some((value + 32) as char),
+            _ => None,
+        }
+    }
+}
+
+/// A unit of data to encode: a character or FNC1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Item {
+    Char(u8),
+    Fnc1,
+}
+
+/// A `^FD` element in mode `N`: characters and Zebra invocation codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Char(u8),
+    /// A symbol value written as is (`>0`, `>=`, `>1`, `>2`, `>3`).
+    Value(u8),
+    /// `>5`, `>6`, `>7`: CODE C/B/A, or FNC4 in the subset they name.
+    Code(u8),
+    Fnc1,
+    Shift,
+    /// `>9`, `>:`, `>;`.
+    Start(Subset),
+}
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+fn ascii(c: char, data: &str) -> ZplResult<u8> {
+    if c.is_ascii() {
+        Ok(c as u8)
+    } else {
+        Err(generation_error(format!(
+            "Code 128 cannot encode {:?} in {:?}",
+            c, data
+        )))
+    }
+}
+
+/// Splits mode `N` data into characters and invocation codes.
+fn tokens(data: &str) -> ZplResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = data.chars();
+    while let Some(c) = chars.next() {
+        if c != '>' {
+            tokens.push(Token::Char(ascii(c, data)?));
+            continue;
+        }
+        let Some(code) = chars.next() else {
+            tokens.push(Token::Char(b'>'));
+            break;
+        };
+        let token = match code {
+            '<' => Token::Char(b'>'),
+            '0' => Token::Value(30),
+            '=' => Token::Value(94),
+            '1' => Token::Value(95),
+            '2' => Token::Value(96),
+            '3' => Token::Value(97),
+            '4' => Token::Shift,
+            '5' => Token::Code(CODE_C),
+            '6' => Token::Code(CODE_B),
+            '7' => Token::Code(CODE_A),
+            '8' => Token::Fnc1,
+            '9' => Token::Start(Subset::A),
+            ':' => Token::Start(Subset::B),
+            ';' => Token::Start(Subset::C),
+            other => {
+                tokens.push(Token::Char(b'>'));
+                Token::Char(ascii(other, data)?)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Mode `N`: subsets and function characters exactly as the invocation
+/// codes select them, starting in subset B unless the data begins with a
+/// start code. An unpaired digit in subset C is written in subset B.
+fn manual_values(tokens: &[Token], data: &str) -> ZplResult<(Vec<u8>, String)> {
+    let (mut subset, rest) = match tokens.first() {
+        Some(Token::Start(subset)) => (*subset, &tokens[1..]),
+        _ => (Subset::B, tokens),
+    };
+    let mut values = vec![subset.start()];
+    let mut text = String::new();
+    let mut shifted = false;
+    let mut i = 0;
+    while i < rest.len() {
+        // A start code after the first character switches subsets.
+        let token = match rest[i] {
+            Token::Start(target) if target != subset => Token::Code(target.switch()),
+            token => token,
+        };
+        match token {
+            Token::Char(c) if subset == Subset::C => {
+                let pair = match rest.get(i + 1) {
+                    Some(Token::Char(d)) if c.is_ascii_digit() && d.is_ascii_digit() => Some(*d),
+                    _ => None,
+                };
+                if let Some(d) = pair {
+                    values.push((c - b'0') * 10 + (d - b'0'));
+                    text.push(c as char);
+                    text.push(d as char);
+                    i += 2;
+                    continue;
+                }
+                if !c.is_ascii_digit() {
+                    return Err(generation_error(format!(
+                        "Code 128 subset C cannot encode {:?} in {:?}",
+                        c as char, data
+                    )));
+                }
+                subset = Subset::B;
+                values.push(CODE_B);
+                continue;
+            }
+            Token::Char(c) => {
+                let target = match (shifted, subset) {
+                    (true, Subset::A) => Subset::B,
+                    (true, _) => Subset::A,
+                    (false, subset) => subset,
+                };
+                let value = target.value(c).ok_or_else(|| {
+                    generation_error(format!(
+                        "Code 128 subset {:?} cannot encode {:?} in {:?}",
+                        target, c as char, data
+                    ))
+                })?;
+                values.push(value);
+                if c >= 32 {
+                    text.push(c as char);
+                }
+            }
+            Token::Value(value) => {
+                values.push(value);
+                if let Some(c) = subset.character(value) {
+                    text.push(c);
+                }
+            }
+            Token::Code(code) => {
+                let target = match code {
+                    CODE_A => Subset::A,
+                    CODE_B => Subset::B,
+                    _ => Subset::C,
+                };
+                if target != subset {
+                    subset = target;
+                    values.push(code);
+                } else if target != Subset::C {
+                    // CODE B in subset B and CODE A in subset A are FNC4.
+                    values.push(code);
+                }
+            }
+            Token::Fnc1 => values.push(FNC1),
+            Token::Start(_) => {}
+            Token::Shift => {
+                if subset != Subset::C {
+                    values.push(SHIFT);
+                    shifted = true;
+                    i += 1;
+                    continue;
+                }
+            }
+        }
+        shifted = false;
+        i += 1;
+    }
+    Ok((values, text))
+}
+
+/// Number of consecutive digits starting at `i`.
+fn digit_run(items: &[Item], i: usize) -> usize {
+    items[i..]
+        .iter()
+        .take_while(|item| matches!(item, Item::Char(c) if c.is_ascii_digit()))
+        .count()
+}
+
+/// Subset A or B for the character at `i`: the current one when it can
+/// encode it, otherwise whichever the next character needing a specific
+/// subset asks for (B by default).
+fn letter_subset(items: &[Item], i: usize, current: Option<Subset>) -> Subset {
+    let Item::Char(c) = items[i] else {
+        return current.unwrap_or(Subset::B);
+    };
+    if let Some(subset @ (Subset::A | Subset::B)) = current
+        && subset.value(c).is_some()
+    {
+        return subset;
+    }
+    items[i..]
+        .iter()
+        .find_map(|item| match item {
+            Item::Char(0..=31) => Some(Subset::A),
+            Item::Char(96..=127) => Some(Subset::B),
+            _ => None,
+        })
+        .unwrap_or(Subset::B)
+}
+
+/// Modes `A`, `D` and `U`: the subsets are chosen automatically, using
+/// subset C for runs of four or more digits (or data of exactly two digits)
+/// and a SHIFT for a single character of the other letter subset.
+fn automatic_values(items: &[Item]) -> Vec<u8> {
+    fn switch(values: &mut Vec<u8>, subset: &mut Option<Subset>, target: Subset) {
+        match *subset {
+            None => values.push(target.start()),
+            Some(current) if current != target => values.push(target.switch()),
+            _ => {}
+        }
+        *subset = Some(target);
+    }
+
+    let mut values = Vec::new();
+    let mut subset: Option<Subset> = None;
+
+    let mut i = 0;
+    while i < items.len() {
+        let c = match items[i] {
+            Item::Fnc1 => {
+                if subset.is_none() {
+                    let next = items[i..].iter().position(|item| *item != Item::Fnc1);
+                    let target = match next.map(|n| (i + n, digit_run(items, i + n))) {
+                        Some((_, run)) if run >= 4 || run == 2 => Subset::C,
+                        Some((n, _)) => letter_subset(items, n, None),
+                        None => Subset::C,
+                    };
+                    switch(&mut values, &mut subset, target);
+                }
+                values.push(FNC1);
+                i += 1;
+                continue;
+            }
+            Item::Char(c) => c,
+        };
+
+        let run = digit_run(items, i);
+        let use_c = match subset {
+            Some(Subset::C) => run >= 2,
+            None => run >= 4 || (run == 2 && i + 2 == items.len()),
+            _ => run >= 4,
+        };
+        if use_c && (run.is_multiple_of(2) || subset == Some(Subset::C)) {
+            switch(&mut values, &mut subset, Subset::C);
+            if let Item::Char(d) = items[i + 1] {
+                values.push((c - b'0') * 10 + (d - b'0'));
+            }
+            i += 2;
+            continue;
+        }
+
+        let target = letter_subset(items, i, subset);
+        if let Some(current @ (Subset::A | Subset::B)) = subset
+            && current != target
+            && matches!(items.get(i + 1), Some(Item::Char(n)) if current.value(*n).is_some())
+        {
+            values.push(SHIFT);
+        } else {
+            switch(&mut values, &mut subset, target);
+        }
+        values.push(target.value(c).unwrap_or_default());
+        i += 1;
+    }
+    values
+}
+
+/// Mode `U`: the digits of `data`, truncated or right-padded with zeros to
+/// 19, plus a Mod 10 check digit, as an SSCC behind FNC1.
+fn ucc_case(data: &str) -> (Vec<Item>, String) {
+    let mut digits: String = data.chars().filter(char::is_ascii_digit).take(19).collect();
+    while digits.len() < 19 {
+        digits.push('0');
+    }
+    digits.push(check_digit::mod10(&digits));
+    let mut items = vec![Item::Fnc1];
+    items.extend(digits.bytes().map(Item::Char));
+    let text = format!("({}){}", &digits[..2], &digits[2..]);
+    (items, text)
+}
+
+/// Mode `D`: `(AI)value` data with parentheses and spaces stripped, FNC1
+/// first and after each variable-length field, and the check digit of AIs
+/// 00, 01 and 02 appended when it is missing. `>8` is an explicit FNC1.
+fn gs1(data: &str) -> ZplResult<(Vec<Item>, String)> {
+    let mut items = vec![Item::Fnc1];
+    let push = |items: &mut Vec<Item>, text: &str| -> ZplResult<()> {
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix(">8") {
+                items.push(Item::Fnc1);
+                rest = after;
+                continue;
+            }
+            let c = rest.chars().next().unwrap_or_default();
+            if c != ' ' {
+                items.push(Item::Char(ascii(c, data)?));
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        Ok(())
+    };
+
+    if !data.trim_start().starts_with('(') {
+        push(&mut items, data)?;
+        return Ok((items, data.replace(">8", "")));
+    }
+
+    let mut text = String::new();
+    let mut needs_separator = false;
+    for gs1::Element { ai, mut value } in gs1::parse(data.trim_start())? {
+        let digits: String = value.chars().filter(|c| *c != ' ').collect();
+        let check_length = match ai.as_str() {
+            "00" => Some(17),
+            "01" | "02" => Some(13),
+            _ => None,
+        };
+        if check_length == Some(digits.len()) && digits.bytes().all(|b| b.is_ascii_digit()) {
+            let check = check_digit::mod10(&digits);
+            value = format!("{}{}", value.trim_end(), check);
+        }
+        if needs_separator && items.last() != Some(&Item::Fnc1) {
+            items.push(Item::Fnc1);
+        }
+        push(&mut items, &ai)?;
+        push(&mut items, &value)?;
+        text.push_str(&format!("({}){}", ai, value.replace(">8", "")));
+        needs_separator = !gs1::is_fixed_length(&ai);
+    }
+    Ok((items, text))
+}
+
+/// Encodes `^FD` data for `^BC` mode `mode` (`N`, `U`, `A` or `D`),
+/// returning the bar modules (without quiet zones) and the interpretation
+/// line text. `ucc_check_digit` appends a Mod 10 check digit to numeric
+/// data in modes `N` and `A`.
+pub(crate) fn encode(
+    mode: char,
+    ucc_check_digit: bool,
+    data: &str,
+) -> ZplResult<(Vec<bool>, String)> {
+    let append_check = |text: &str| {
+        (ucc_check_digit && !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| check_digit::mod10(text))
+    };
+
+    let (values, text) = match mode {
+        'U' => {
+            let (items, text) = ucc_case(data);
+            (automatic_values(&items), text)
+        }
+        'D' => {
+            let (items, text) = gs1(data)?;
+            (automatic_values(&items), text)
+        }
+        'A' => {
+            let mut items = data
+                .chars()
+                .map(|c| ascii(c, data).map(Item::Char))
+                .collect::<ZplResult<Vec<_>>>()?;
+            let mut text: String = data.chars().filter(|c| !c.is_ascii_control()).collect();
+            if let Some(check) = append_check(&text) {
+                items.push(Item::Char(check as u8));
+                text.push(check);
+            }
+            (automatic_values(&items), text)
+        }
+        _ => {
+            let mut tokens = tokens(data)?;
+            let printable: String = tokens
+                .iter()
+                .filter_map(|t| match t {
+                    Token::Char(c) if *c >= 32 => Some(*c as char),
+                    _ => None,
+                })
+                .collect();
+            if let Some(check) = append_check(&printable) {
+                tokens.push(Token::Char(check as u8));
+            }
+            manual_values(&tokens, data)?
+        }
+    };
+    if values.len() < 2 {
+        return Err(generation_error("Code 128 data is empty"));
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| i.max(1) as u32 * v as u32)
+        .sum::<u32>()
+        % 103;
+    let mut modules = Vec::with_capacity((values.len() + 2) * 11 + 2);
+    for value in values.into_iter().chain([checksum as u8]) {
+        let pattern = PATTERNS[value as usize];
+        modules.extend((0..11).rev().map(|b| pattern >> b & 1 == 1));
+    }
+    modules.extend((0..13).rev().map(|b| STOP >> b & 1 == 1));
+    Ok((modules, text))
+}
@@ -0,0 +1,1046 @@
+//! GS1 DataBar (`^BR`) encoder shared by both backends.
+//!
+//! `rxing` can read the DataBar family but not write it, so symbols are
+//! built here. Every type comes out as a stack of [`DataBarRow`]s: the
+//! linear types are a single row, the stacked types add their separator
+//! patterns between rows. Rows carry their height in dots, so the backends
+//! only paint dark modules at the `^BR` magnification.
+//!
+//! Data characters are derived with the width-combination algorithm of
+//! ISO/IEC 24724 (the inverse of `rxing`'s `getRSSvalue`), which keeps the
+//! tables below identical to the ones the reader side uses.
+
+use crate::gs1;
+use crate::{ZplError, ZplResult};
+
+/// One row of a DataBar symbol.
+#[derive(Debug, Clone)]
+pub(crate) struct DataBarRow {
+    /// Modules from left to right; `true` is dark.
+    pub modules: Vec<bool>,
+    /// Row height in dots.
+    pub height: u32,
+}
+
+impl DataBarRow {
+    /// Runs of dark modules as `(start, length)` pairs.
+    pub(crate) fn dark_runs(&self) -> Vec<(usize, usize)> {
+        let mut runs = Vec::new();
+        let mut start = None;
+        for (i, &dark) in self.modules.iter().chain([&false]).enumerate() {
+            match (dark, start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    runs.push((s, i - s));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        runs
+    }
+}
+
+/// `^BR` size parameters.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DataBarOptions {
+    /// Module width in dots.
+    pub magnification: u32,
+    /// Separator row height in modules (1 or 2).
+    pub separator_height: u32,
+    /// Bar height in dots for the linear types and each Expanded row.
+    pub bar_height: u32,
+    /// Data segments per row for Expanded Stacked (even, 2-22).
+    pub segment_width: u32,
+}
+
+/// GS1 FNC1, written as the ASCII group separator in element strings.
+const FNC1: char = gs1::GS;
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+// ─── Width combinations ─────────────────────────────────────────────────────
+
+fn combins(n: i32, r: i32) -> i32 {
+    if r < 0 || r > n {
+        return 0;
+    }
+    let r = r.min(n - r);
+    let mut value: i64 = 1;
+    for i in 0..r as i64 {
+        value = value * (n as i64 - i) / (i + 1);
+    }
+    value as i32
+}
+
+/// Splits `value` into `elements` widths summing to `modules`, none wider
+/// than `max_width`. With `no_narrow`, at least one element is wider than 1.
+fn rss_widths(
+    mut value: i32,
+    modules: u32,
+    elements: usize,
+    max_width: u32,
+    no_narrow: bool,
+) -> Vec<u32> {
+    let elements = elements as i32;
+    let max_width = max_width as i32;
+    let mut n = modules as i32;
+    let mut widths = Vec::with_capacity(elements as usize);
+    let mut narrow_mask = 0u32;
+
+    for bar in 0..elements - 1 {
+        let mut width = 1;
+        narrow_mask |= 1 << bar;
+        loop {
+            let mut sub = combins(n - width - 1, elements - bar - 2);
+            if no_narrow
+                && narrow_mask == 0
+                && n - width - (elements - bar - 1) >= elements - bar - 1
+            {
+                sub -= combins(n - width - (elements - bar), elements - bar - 2);
+            }
+            if elements - bar - 1 > 1 {
+                let mut less = 0;
+                let mut widest = n - width - (elements - bar - 2);
+                while widest > max_width {
+                    less += combins(n - width - widest - 1, elements - bar - 3);
+                    widest -= 1;
+                }
+                sub -= less * (elements - 1 - bar);
+            } else if n - width > max_width {
+                sub -= 1;
+            }
+            if value < sub {
+                break;
+            }
+            value -= sub;
+            width += 1;
+            narrow_mask &= !(1 << bar);
+        }
+        widths.push(width as u32);
+        n -= width;
+    }
+    widths.push(n as u32);
+    widths
+}
+
+/// Interleaves odd and even widths into the eight elements of a character.
+fn interleave(odd: &[u32], even: &[u32]) -> [u32; 8] {
+    let mut widths = [0; 8];
+    for i in 0..4 {
+        widths[2 * i] = odd[i];
+        widths[2 * i + 1] = even[i];
+    }
+    widths
+}
+
+/// Expands element widths into modules, starting with a dark element when
+/// `dark` is set.
+fn push_elements(modules: &mut Vec<bool>, widths: &[u32], mut dark: bool) {
+    for &width in widths {
+        modules.extend(std::iter::repeat_n(dark, width as usize));
+        dark = !dark;
+    }
+}
+
+// ─── Omnidirectional family (types 1-4) ─────────────────────────────────────
+
+/// Outside characters: group sums, even subset totals, odd modules and the
+/// widest odd element.
+const OMNI_OUTSIDE: [(u32, u32, u32, u32); 5] = [
+    (0, 1, 12, 8),
+    (161, 10, 10, 6),
+    (961, 34, 8, 4),
+    (2015, 70, 6, 3),
+    (2715, 126, 4, 1),
+];
+
+/// Inside characters: group sums, odd subset totals, odd modules and the
+/// widest odd element.
+const OMNI_INSIDE: [(u32, u32, u32, u32); 4] = [
+    (0, 4, 5, 2),
+    (336, 20, 7, 4),
+    (1036, 48, 9, 6),
+    (1516, 81, 11, 8),
+];
+
+/// Finder patterns, five elements each.
+const OMNI_FINDERS: [[u32; 5]; 9] = [
+    [3, 8, 2, 1, 1],
+    [3, 5, 5, 1, 1],
+    [3, 3, 7, 1, 1],
+    [3, 1, 9, 1, 1],
+    [2, 7, 4, 1, 1],
+    [2, 5, 6, 1, 1],
+    [2, 3, 8, 1, 1],
+    [1, 5, 7, 1, 1],
+    [1, 3, 9, 1, 1],
+];
+
+fn omni_character(value: u32, outside: bool) -> [u32; 8] {
+    if outside {
+        let &(gsum, t_even, odd_modules, odd_widest) = OMNI_OUTSIDE
+            .iter()
+            .rev()
+            .find(|group| value >= group.0)
+            .unwrap_or(&OMNI_OUTSIDE[0]);
+        let v = value - gsum;
+        let odd = rss_widths((v / t_even) as i32, odd_modules, 4, odd_widest, false);
+        let even = rss_widths(
+            (v % t_even) as i32,
+            16 - odd_modules,
+            4,
+            9 - odd_widest,
+            true,
+        );
+        interleave(&odd, &even)
+    } else {
+        let &(gsum, t_odd, odd_modules, odd_widest) = OMNI_INSIDE
+            .iter()
+            .rev()
+            .find(|group| value >= group.0)
+            .unwrap_or(&OMNI_INSIDE[0]);
+        let v = value - gsum;
+        let odd = rss_widths((v % t_odd) as i32, odd_modules, 4, odd_widest, true);
+        let even = rss_widths(
+            (v / t_odd) as i32,
+            15 - odd_modules,
+            4,
+            9 - odd_widest,
+            false,
+        );
+        interleave(&odd, &even)
+    }
+}
+
+/// Checksum contribution of a character: odd widths in base 9 plus three
+/// times the even widths in base 9.
+fn omni_checksum(widths: &[u32; 8]) -> u32 {
+    let odd = (0..4).rev().fold(0, |acc, i| acc * 9 + widths[2 * i]);
+    let even = (0..4).rev().fold(0, |acc, i| acc * 9 + widths[2 * i + 1]);
+    odd + 3 * even
+}
+
+/// Reads the GTIN for the omnidirectional family: an optional `(01)` or
+/// `01` prefix, then up to 13 digits (a 14th is the check digit and is
+/// recomputed by the reader). Anything after `|` is composite data.
+fn omni_value(data: &str) -> ZplResult<u64> {
+    let data = data.split('|').next().unwrap_or_default().trim();
+    let mut digits = data.strip_prefix("(01)").unwrap_or(data);
+    if digits.len() == 16 && digits.starts_with("01") {
+        digits = &digits[2..];
+    }
+    if digits.is_empty() || digits.len() > 14 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(generation_error(format!(
+            "GS1 DataBar needs a GTIN of up to 14 digits, got {:?}",
+            data
+        )));
+    }
+    if digits.len() == 14 {
+        digits = &digits[..13];
+    }
+    digits
+        .parse()
+        .map_err(|_| generation_error(format!("invalid GS1 DataBar data {:?}", data)))
+}
+
+/// Element widths of a DataBar Omnidirectional symbol: 46 elements over 96
+/// modules, starting with a space.
+fn omni_elements(data: &str) -> ZplResult<[u32; 46]> {
+    let value = omni_value(data)?;
+    let left = value / 4_537_077;
+    let right = value % 4_537_077;
+    let values = [
+        (left / 1597) as u32,
+        (left % 1597) as u32,
+        (right / 1597) as u32,
+        (right % 1597) as u32,
+    ];
+    let chars: Vec<[u32; 8]> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| omni_character(v, i % 2 == 0))
+        .collect();
+
+    let left_sum = omni_checksum(&chars[0]) + 4 * omni_checksum(&chars[1]);
+    let right_sum = omni_checksum(&chars[2]) + 4 * omni_checksum(&chars[3]);
+    let mut check = (left_sum + 16 * right_sum) % 79;
+    if check >= 8 {
+        check += 1;
+    }
+    if check >= 72 {
+        check += 1;
+    }
+    let left_finder = OMNI_FINDERS[(check / 9) as usize];
+    let right_finder = OMNI_FINDERS[(check % 9) as usize];
+
+    let mut elements = [0u32; 46];
+    elements[0] = 1;
+    elements[1] = 1;
+    elements[44] = 1;
+    elements[45] = 1;
+    for i in 0..8 {
+        elements[2 + i] = chars[0][i];
+        elements[15 + i] = chars[1][7 - i];
+        elements[23 + i] = chars[3][i];
+        elements[36 + i] = chars[2][7 - i];
+    }
+    for i in 0..5 {
+        elements[10 + i] = left_finder[i];
+        elements[31 + i] = right_finder[4 - i];
+    }
+    Ok(elements)
+}
+
+/// Separator below the top row (or above the bottom row) of DataBar
+/// Stacked Omnidirectional: the complement of `row`, with light finder
+/// modules in `finder` turned into an alternating pattern.
+fn omni_separator(row: &[bool], finder: std::ops::Range<usize>) -> Vec<bool> {
+    let mut separator = vec![false; row.len()];
+    for i in 4..row.len() - 4 {
+        separator[i] = !row[i];
+    }
+    let mut dark = true;
+    for i in finder {
+        if row[i] {
+            separator[i] = false;
+            dark = true;
+        } else {
+            separator[i] = dark;
+            dark = !dark;
+        }
+    }
+    separator
+}
+
+fn omni_rows(symbology: u32, data: &str, options: &DataBarOptions) -> ZplResult<Vec<DataBarRow>> {
+    let elements = omni_elements(data)?;
+    let x = options.magnification.max(1);
+    let separator_height = options.separator_height.clamp(1, 2) * x;
+
+    if symbology <= 2 {
+        let mut modules = Vec::with_capacity(96);
+        push_elements(&mut modules, &elements, false);
+        return Ok(vec![DataBarRow {
+            modules,
+            height: options.bar_height.max(1),
+        }]);
+    }
+
+    // Stacked types split the symbol after the left finder half and close
+    // each row with a guard.
+    let mut top = Vec::with_capacity(50);
+    push_elements(&mut top, &elements[..23], false);
+    top.extend([true, false]);
+    let mut bottom = vec![true, false];
+    push_elements(&mut bottom, &elements[23..], true);
+
+    if symbology == 3 {
+        let mut separator = vec![false; 50];
+        for i in 4..46 {
+            if top[i] == bottom[i] {
+                separator[i] = !top[i];
+            } else {
+                separator[i] = !separator[i - 1];
+            }
+        }
+        return Ok(vec![
+            DataBarRow {
+                modules: top,
+                height: 5 * x,
+            },
+            DataBarRow {
+                modules: separator,
+                height: separator_height,
+            },
+            DataBarRow {
+                modules: bottom,
+                height: 7 * x,
+            },
+        ]);
+    }
+
+    let upper = omni_separator(&top, 18..33);
+    let lower = omni_separator(&bottom, 17..32);
+    let middle: Vec<bool> = (0..50)
+        .map(|i| (5..46).contains(&i) && i % 2 == 1)
+        .collect();
+    Ok(vec![
+        DataBarRow {
+            modules: top,
+            height: 33 * x,
+        },
+        DataBarRow {
+            modules: upper,
+            height: separator_height,
+        },
+        DataBarRow {
+            modules: middle,
+            height: separator_height,
+        },
+        DataBarRow {
+            modules: lower,
+            height: separator_height,
+        },
+        DataBarRow {
+            modules: bottom,
+            height: 33 * x,
+        },
+    ])
+}
+
+// ─── Limited (type 5) ───────────────────────────────────────────────────────
+
+/// Data character groups: group sums, even subset totals, odd modules and
+/// the widest odd element. Even elements take the rest of the 26 modules
+/// and are at most `9 - widest odd` wide.
+const LIMITED_GROUPS: [(u32, u32, u32, u32); 7] = [
+    (0, 28, 17, 6),
+    (183_064, 728, 13, 5),
+    (820_064, 6454, 9, 3),
+    (1_000_776, 203, 15, 5),
+    (1_491_021, 2408, 11, 4),
+    (1_979_845, 1, 19, 8),
+    (1_996_939, 16632, 7, 1),
+];
+
+/// Values a Limited data character can take; the GTIN is split into a left
+/// and a right character in this base.
+const LIMITED_CHARACTER_VALUES: u64 = 2_013_571;
+
+/// Limited data character: 14 elements over 26 modules.
+fn limited_character(value: u32) -> [u32; 14] {
+    let &(gsum, t_even, odd_modules, odd_widest) = LIMITED_GROUPS
+        .iter()
+        .rev()
+        .find(|group| value >= group.0)
+        .unwrap_or(&LIMITED_GROUPS[0]);
+    let v = value - gsum;
+    let odd = rss_widths((v / t_even) as i32, odd_modules, 7, odd_widest, false);
+    let even = rss_widths(
+        (v % t_even) as i32,
+        26 - odd_modules,
+        7,
+        9 - odd_widest,
+        true,
+    );
+    let mut widths = [0; 14];
+    for i in 0..7 {
+        widths[2 * i] = odd[i];
+        widths[2 * i + 1] = even[i];
+    }
+    widths
+}
+
+/// Limited check character for `check` (0-88): 14 elements over 18
+/// modules. The first twelve elements are six odd and six even widths over
+/// 8 modules each (`check / 21` and `check % 21`), closed by two narrow
+/// elements.
+fn limited_check_character(check: u32) -> [u32; 14] {
+    let odd = rss_widths((check / 21) as i32, 8, 6, 3, false);
+    let even = rss_widths((check % 21) as i32, 8, 6, 3, false);
+    let mut widths = [1; 14];
+    for i in 0..6 {
+        widths[2 * i] = odd[i];
+        widths[2 * i + 1] = even[i];
+    }
+    widths
+}
+
+/// Element widths of a DataBar Limited symbol: left guard, left character,
+/// check character, right character and right guard, 46 elements over 74
+/// modules starting with a space.
+fn limited_elements(data: &str) -> ZplResult<[u32; 46]> {
+    let value = omni_value(data)?;
+    // The indicator digit of a Limited GTIN is 0 or 1.
+    if value >= 2_000_000_000_000 {
+        return Err(generation_error(format!(
+            "GS1 DataBar Limited needs a GTIN starting with 0 or 1, got {:?}",
+            data
+        )));
+    }
+    let left = limited_character((value / LIMITED_CHARACTER_VALUES) as u32);
+    let right = limited_character((value % LIMITED_CHARACTER_VALUES) as u32);
+
+    // Weights are successive powers of 3 modulo 89 over both characters.
+    let mut weight = 1;
+    let mut checksum = 0;
+    for &width in left.iter().chain(&right) {
+        checksum += weight * width;
+        weight = weight * 3 % 89;
+    }
+    let check = limited_check_character(checksum % 89);
+
+    let mut elements = [0u32; 46];
+    elements[0] = 1;
+    elements[1] = 1;
+    elements[44] = 1;
+    elements[45] = 1;
+    elements[2..16].copy_from_slice(&left);
+    elements[16..30].copy_from_slice(&check);
+    elements[30..44].copy_from_slice(&right);
+    Ok(elements)
+}
+
+fn limited_rows(data: &str, options: &DataBarOptions) -> ZplResult<Vec<DataBarRow>> {
+    let elements = limited_elements(data)?;
+    let mut modules = Vec::with_capacity(74);
+    push_elements(&mut modules, &elements, false);
+    Ok(vec![DataBarRow {
+        modules,
+        height: options.bar_height.max(1),
+    }])
+}
+
+// ─── Expanded (type 6) ──────────────────────────────────────────────────────
+
+/// Data character groups: group sums, even subset totals, odd modules and
+/// the widest odd element.
+const EXPANDED_GROUPS: [(u32, u32, u32, u32); 5] = [
+    (0, 4, 12, 7),
+    (348, 20, 10, 5),
+    (1388, 52, 8, 4),
+    (2948, 104, 6, 3),
+    (3988, 204, 4, 1),
+];
+
+/// Finder patterns A-F, five elements each.
+const EXPANDED_FINDERS: [[u32; 5]; 6] = [
+    [1, 8, 4, 1, 1],
+    [3, 6, 4, 1, 1],
+    [3, 4, 6, 1, 1],
+    [3, 2, 8, 1, 1],
+    [2, 6, 5, 1, 1],
+    [2, 2, 9, 1, 1],
+];
+
+/// Finder sequences by number of pairs (from two pairs up).
+const FINDER_SEQUENCES: [&[usize]; 10] = [
+    &[0, 0],
+    &[0, 1, 1],
+    &[0, 2, 1, 3],
+    &[0, 4, 1, 3, 2],
+    &[0, 4, 1, 3, 3, 5],
+    &[0, 4, 1, 3, 4, 5, 5],
+    &[0, 0, 1, 1, 2, 2, 3, 3],
+    &[0, 0, 1, 1, 2, 2, 3, 4, 4],
+    &[0, 0, 1, 1, 2, 2, 3, 4, 5, 5],
+    &[0, 0, 1, 1, 2, 3, 3, 4, 4, 5, 5],
+];
+
+/// Checksum weights per character position.
+const EXPANDED_WEIGHTS: [[u32; 8]; 23] = [
+    [1, 3, 9, 27, 81, 32, 96, 77],
+    [20, 60, 180, 118, 143, 7, 21, 63],
+    [189, 145, 13, 39, 117, 140, 209, 205],
+    [193, 157, 49, 147, 19, 57, 171, 91],
+    [62, 186, 136, 197, 169, 85, 44, 132],
+    [185, 133, 188, 142, 4, 12, 36, 108],
+    [113, 128, 173, 97, 80, 29, 87, 50],
+    [150, 28, 84, 41, 123, 158, 52, 156],
+    [46, 138, 203, 187, 139, 206, 196, 166],
+    [76, 17, 51, 153, 37, 111, 122, 155],
+    [43, 129, 176, 106, 107, 110, 119, 146],
+    [16, 48, 144, 10, 30, 90, 59, 177],
+    [109, 116, 137, 200, 178, 112, 125, 164],
+    [70, 210, 208, 202, 184, 130, 179, 115],
+    [134, 191, 151, 31, 93, 68, 204, 190],
+    [148, 22, 66, 198, 172, 94, 71, 2],
+    [6, 18, 54, 162, 64, 192, 154, 40],
+    [120, 149, 25, 75, 14, 42, 126, 167],
+    [79, 26, 78, 23, 69, 207, 199, 175],
+    [103, 98, 83, 38, 114, 131, 182, 124],
+    [161, 61, 183, 127, 170, 88, 53, 159],
+    [55, 165, 73, 8, 24, 72, 5, 15],
+    [45, 135, 194, 160, 58, 174, 100, 89],
+];
+
+/// Largest number of data characters an Expanded symbol can hold.
+const MAX_DATA_CHARACTERS: usize = 21;
+
+fn is_numeric(c: char) -> bool {
+    c.is_ascii_digit() || c == FNC1
+}
+
+fn alpha_value(c: char) -> Option<(u32, usize)> {
+    match c {
+        '0'..='9' => Some((c as u32 - '0' as u32 + 5, 5)),
+        FNC1 => Some((15, 5)),
+        'A'..='Z' => Some((c as u32 - 33, 6)),
+        '*' => Some((58, 6)),
+        ',' => Some((59, 6)),
+        '-' => Some((60, 6)),
+        '.' => Some((61, 6)),
+        '/' => Some((62, 6)),
+        _ => None,
+    }
+}
+
+fn iso_value(c: char) -> Option<(u32, usize)> {
+    const PUNCTUATION: &str = "!\"%&'()*+,-./:;<=>?_ ";
+    match c {
+        '0'..='9' | FNC1 => alpha_value(c),
+        'A'..='Z' => Some((c as u32 - 1, 7)),
+        'a'..='z' => Some((c as u32 - 7, 7)),
+        _ => PUNCTUATION.find(c).map(|i| (232 + i as u32, 8)),
+    }
+}
+
+fn push_bits(bits: &mut Vec<bool>, value: u32, count: usize) {
+    bits.extend((0..count).rev().map(|i| (value >> i) & 1 == 1));
+}
+
+fn numeric_pair_value(c: char) -> u32 {
+    if c == FNC1 { 10 } else { c as u32 - '0' as u32 }
+}
+
+/// Size in bits of the data field once padded: whole 12-bit characters,
+/// at least three of them.
+fn padded_size(bits: usize) -> usize {
+    bits.div_ceil(12).max(3) * 12
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encodation {
+    Numeric,
+    Alphanumeric,
+    Iso646,
+}
+
+/// Encodes an element string with the general-purpose method ("00"),
+/// returning the padded binary string including the header.
+fn expanded_bits(text: &[char]) -> ZplResult<Vec<bool>> {
+    if let Some(&c) = text.iter().find(|&&c| iso_value(c).is_none()) {
+        return Err(generation_error(format!(
+            "GS1 DataBar Expanded cannot encode character {:?}",
+            c
+        )));
+    }
+
+    let numeric_run = |from: usize| {
+        let run = text[from..].iter().take_while(|&&c| is_numeric(c)).count();
+        run >= 4 || (run >= 2 && from + run == text.len())
+    };
+    let alpha_run = |from: usize| {
+        text[from..]
+            .iter()
+            .take(10)
+            .all(|&c| alpha_value(c).is_some())
+    };
+
+    // Linkage flag (no composite), encodation method and the two
+    // variable-length bits, filled in once the size is known.
+    let mut bits = vec![false; 5];
+    let mut mode = Encodation::Numeric;
+    let mut i = 0;
+    while i < text.len() {
+        match mode {
+            Encodation::Numeric => {
+                if i + 1 < text.len()
+                    && is_numeric(text[i])
+                    && is_numeric(text[i + 1])
+                    && !(text[i] == FNC1 && text[i + 1] == FNC1)
+                {
+                    let value = 11 * numeric_pair_value(text[i]) + numeric_pair_value(text[i + 1]);
+                    push_bits(&mut bits, value + 8, 7);
+                    i += 2;
+                } else if i + 1 == text.len() && text[i].is_ascii_digit() {
+                    // A lone final digit fits in 4 bits when fewer than 7
+                    // bits remain in the symbol; otherwise pair it with FNC1.
+                    let digit = numeric_pair_value(text[i]);
+                    let room = padded_size(bits.len() + 4) - bits.len();
+                    if room < 7 {
+                        push_bits(&mut bits, digit + 1, 4);
+                    } else {
+                        push_bits(&mut bits, 11 * digit + 10 + 8, 7);
+                    }
+                    i += 1;
+                } else {
+                    push_bits(&mut bits, 0, 4);
+                    mode = Encodation::Alphanumeric;
+                }
+            }
+            Encodation::Alphanumeric => {
+                if numeric_run(i) {
+                    push_bits(&mut bits, 0, 3);
+                    mode = Encodation::Numeric;
+                } else if let Some((value, count)) = alpha_value(text[i]) {
+                    push_bits(&mut bits, value, count);
+                    i += 1;
+                } else {
+                    push_bits(&mut bits, 0b00100, 5);
+                    mode = Encodation::Iso646;
+                }
+            }
+            Encodation::Iso646 => {
+                if numeric_run(i) {
+                    push_bits(&mut bits, 0, 3);
+                    mode = Encodation::Numeric;
+                } else if alpha_run(i) {
+                    push_bits(&mut bits, 0b00100, 5);
+                    mode = Encodation::Alphanumeric;
+                } else if let Some((value, count)) = iso_value(text[i]) {
+                    push_bits(&mut bits, value, count);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    let size = padded_size(bits.len());
+    if size / 12 > MAX_DATA_CHARACTERS {
+        return Err(generation_error(format!(
+            "data too long for GS1 DataBar Expanded ({} bits, {} available)",
+            bits.len(),
+            MAX_DATA_CHARACTERS * 12
+        )));
+    }
+    if mode == Encodation::Numeric && bits.len() + 4 <= size {
+        push_bits(&mut bits, 0, 4);
+    }
+    while bits.len() < size {
+        push_bits(&mut bits, 0b00100, 5);
+    }
+    bits.truncate(size);
+
+    // Variable-length bits: odd number of symbol characters (data plus the
+    // check character), and more than 14 of them.
+    bits[3] = (size / 12 + 1) % 2 == 1;
+    bits[4] = size > 156;
+    Ok(bits)
+}
+
+fn expanded_character(value: u32) -> [u32; 8] {
+    let &(gsum, t_even, odd_modules, odd_widest) = EXPANDED_GROUPS
+        .iter()
+        .rev()
+        .find(|group| value >= group.0)
+        .unwrap_or(&EXPANDED_GROUPS[0]);
+    let v = value - gsum;
+    let odd = rss_widths((v / t_even) as i32, odd_modules, 4, odd_widest, true);
+    let even = rss_widths(
+        (v % t_even) as i32,
+        17 - odd_modules,
+        4,
+        9 - odd_widest,
+        false,
+    );
+    interleave(&odd, &even)
+}
+
+/// A symbol character pair: left character, finder and (except possibly
+/// in the last pair) right character, as laid out left to right.
+struct ExpandedPair {
+    elements: Vec<u32>,
+    /// Index of the first finder element within `elements`.
+    finder: usize,
+}
+
+/// Builds the character pairs of an Expanded symbol.
+fn expanded_pairs(data: &str) -> ZplResult<Vec<ExpandedPair>> {
+    let data = data.split('|').next().unwrap_or_default();
+    let text: Vec<char> = gs1::element_string(data)?.chars().collect();
+    if text.is_empty() {
+        return Err(generation_error("GS1 DataBar Expanded needs data"));
+    }
+    let bits = expanded_bits(&text)?;
+    let data_values: Vec<u32> = bits
+        .chunks(12)
+        .map(|chunk| chunk.iter().fold(0, |acc, &b| (acc << 1) | b as u32))
+        .collect();
+
+    let symbol_count = data_values.len() + 1;
+    let pair_count = symbol_count.div_ceil(2);
+    let sequence = FINDER_SEQUENCES[pair_count - 2];
+
+    let mut characters: Vec<[u32; 8]> = Vec::with_capacity(symbol_count);
+    characters.push([0; 8]);
+    characters.extend(data_values.iter().map(|&v| expanded_character(v)));
+
+    // The check character (left of the first pair) sums the weighted widths
+    // of every data character.
+    let mut checksum = 0;
+    for (index, widths) in characters.iter().enumerate().skip(1) {
+        let pair = index / 2;
+        let row = 4 * sequence[pair] + if pair % 2 == 0 { 0 } else { 2 } + index % 2 - 1;
+        checksum += (0..8)
+            .map(|i| widths[i] * EXPANDED_WEIGHTS[row][i])
+            .sum::<u32>();
+    }
+    let check_value = 211 * (symbol_count as u32 - 4) + checksum % 211;
+    characters[0] = expanded_character(check_value);
+
+    let mut pairs = Vec::with_capacity(pair_count);
+    for (pair, &finder) in sequence.iter().enumerate() {
+        let mut elements = characters[2 * pair].to_vec();
+        let finder_start = elements.len();
+        let mut pattern = EXPANDED_FINDERS[finder];
+        if pair % 2 == 1 {
+            pattern.reverse();
+        }
+        elements.extend(pattern);
+        if let Some(right) = characters.get(2 * pair + 1) {
+            elements.extend(right.iter().rev());
+        }
+        pairs.push(ExpandedPair {
+            elements,
+            finder: finder_start,
+        });
+    }
+    Ok(pairs)
+}
+
+/// Lays out pairs `pairs` (with their symbol indexes) as one row with its
+/// guards. Returns the modules and the module ranges of the finders.
+fn expanded_row(
+    pairs: &[(usize, &ExpandedPair)],
+    reversed: bool,
+) -> (Vec<bool>, Vec<std::ops::Range<usize>>) {
+    let ordered: Vec<&(usize, &ExpandedPair)> = if reversed {
+        pairs.iter().rev().collect()
+    } else {
+        pairs.iter().collect()
+    };
+    // Every pair has an odd element count, so its first and last elements
+    // share a colour: light for even pairs, dark for odd ones. The guard
+    // keeps the alternation going into the first pair.
+    let first_dark = ordered.first().is_some_and(|(index, _)| index % 2 == 1);
+
+    let mut modules = Vec::new();
+    let mut finders = Vec::new();
+    push_elements(&mut modules, &[1, 1], first_dark);
+    for (index, pair) in ordered {
+        let dark = index % 2 == 1;
+        let mut elements = pair.elements.clone();
+        let mut finder = pair.finder;
+        if reversed {
+            elements.reverse();
+            finder = elements.len() - finder - 5;
+        }
+        let start = modules.len() + elements[..finder].iter().sum::<u32>() as usize;
+        finders.push(start..start + 15);
+        push_elements(&mut modules, &elements, dark);
+    }
+    let last_dark = modules.last().copied().unwrap_or(false);
+    push_elements(&mut modules, &[1, 1], !last_dark);
+    (modules, finders)
+}
+
+/// Separator next to an Expanded Stacked row: the complement of the row,
+/// alternating over light finder modules.
+fn expanded_separator(
+    row: &[bool],
+    finders: &[std::ops::Range<usize>],
+    reversed: bool,
+) -> Vec<bool> {
+    let mut separator = vec![false; row.len()];
+    for i in 4..row.len().saturating_sub(4) {
+        separator[i] = !row[i];
+    }
+    for finder in finders {
+        let span: Vec<usize> = if reversed {
+            finder.clone().rev().collect()
+        } else {
+            finder.clone().collect()
+        };
+        for pair in span.windows(2) {
+            let (previous, i) = (pair[0], pair[1]);
+            if !row[i] && separator[previous] {
+                separator[i] = false;
+            }
+        }
+    }
+    separator
+}
+
+fn expanded_rows(data: &str, options: &DataBarOptions) -> ZplResult<Vec<DataBarRow>> {
+    let pairs = expanded_pairs(data)?;
+    let x = options.magnification.max(1);
+    let separator_height = options.separator_height.clamp(1, 2) * x;
+    let height = options.bar_height.max(1);
+
+    let symbol_count: usize = pairs
+        .iter()
+        .map(|pair| if pair.elements.len() > 13 { 2 } else { 1 })
+        .sum();
+    let segment_width = (options.segment_width.clamp(2, 22) & !1) as usize;
+    if symbol_count <= segment_width {
+        let indexed: Vec<(usize, &ExpandedPair)> = pairs.iter().enumerate().collect();
+        let (modules, _) = expanded_row(&indexed, false);
+        return Ok(vec![DataBarRow { modules, height }]);
+    }
+
+    // Expanded Stacked: `segment_width` characters per row. With an even
+    // number of pairs per row, even-numbered rows run right to left so the
+    // finder sequence reads the same way on every row, except a partial
+    // last row holding an odd number of pairs.
+    let per_row = segment_width / 2;
+    let indexed: Vec<(usize, &ExpandedPair)> = pairs.iter().enumerate().collect();
+    let chunks: Vec<&[(usize, &ExpandedPair)]> = indexed.chunks(per_row).collect();
+    let mut built = Vec::with_capacity(chunks.len());
+    for (row, chunk) in chunks.iter().enumerate() {
+        let even_numbered = row % 2 == 1;
+        let special = row + 1 == chunks.len() && chunk.len() < per_row && chunk.len() % 2 == 1;
+        let reversed = even_numbered && per_row.is_multiple_of(2) && !special;
+        let (modules, finders) = expanded_row(chunk, reversed);
+        built.push((modules, finders, reversed));
+    }
+
+    let mut rows = Vec::new();
+    for (row, (modules, finders, reversed)) in built.iter().enumerate() {
+        if row > 0 {
+            let width = modules.len();
+            let middle: Vec<bool> = (0..width)
+                .map(|i| i >= 5 && i < width.saturating_sub(4) && i % 2 == 1)
+                .collect();
+            rows.push(DataBarRow {
+                modules: middle,
+                height: separator_height,
+            });
+            rows.push(DataBarRow {
+                modules: expanded_separator(modules, finders, *reversed),
+                height: separator_height,
+            });
+        }
+        rows.push(DataBarRow {
+            modules: modules.clone(),
+            height,
+        });
+        if row + 1 < built.len() {
+            rows.push(DataBarRow {
+                modules: expanded_separator(modules, finders, *reversed),
+                height: separator_height,
+            });
+        }
+    }
+    Ok(rows)
+}
+
+// ─── Entry point ────────────────────────────────────────────────────────────
+
+/// Encodes `data` as GS1 DataBar symbology type `symbology` (the `^BR`
+/// type parameter, 1-12).
+///
+/// Types 1-5 take a GTIN (Limited only with indicator digit 0 or 1);
+/// Expanded (6) takes GS1 element strings in `(AI)value` notation or with
+/// the group separator as FNC1, and stacks into rows of `segment_width`
+/// characters when it does not fit one row. The composite types (7-12) are
+/// not supported.
+pub(crate) fn encode(
+    symbology: u32,
+    data: &str,
+    options: &DataBarOptions,
+) -> ZplResult<Vec<DataBarRow>> {
+    match symbology {
+        1..=4 => omni_rows(symbology, data, options),
+        5 => limited_rows(data, options),
+        6 => expanded_rows(data, options),
+        7..=12 => Err(generation_error(
+            "GS1 DataBar composite symbols are not supported",
+        )),
+        _ => Err(generation_error(format!(
+            "unknown GS1 DataBar type {}",
+            symbology
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: DataBarOptions = DataBarOptions {
+        magnification: 1,
+        separator_height: 1,
+        bar_height: 10,
+        segment_width: 22,
+    };
+
+    /// DataBar Omnidirectional elements of GTIN 20012345678909, which
+    /// `rxing`'s RSS-14 reader decodes back to the GTIN.
+    const OMNI: [u32; 46] = [
+        1, 1, 1, 1, 3, 3, 1, 1, 5, 1, 2, 7, 4, 1, 1, 1, 2, 2, 1, 2, 1, 5, 1, 2, 5, 1, 2, 1, 1, 1,
+        2, 1, 1, 2, 8, 3, 3, 2, 1, 2, 1, 2, 3, 2, 1, 1,
+    ];
+
+    /// DataBar Limited elements of GTIN 15012345678907.
+    const LIMITED: [u32; 46] = [
+        1, 1, 3, 2, 2, 2, 3, 2, 1, 2, 1, 1, 1, 1, 2, 3, 1, 1, 1, 2, 1, 1, 2, 1, 1, 2, 2, 1, 1, 1,
+        2, 1, 2, 1, 1, 2, 3, 2, 1, 3, 2, 2, 2, 2, 1, 1,
+    ];
+
+    /// DataBar Expanded elements of `(01)98898765432106(3202)012345(15)991231`,
+    /// guards included, which `rxing`'s reader decodes back to the data.
+    const EXPANDED: [u32; 130] = [
+        1, 1, 1, 1, 3, 4, 2, 2, 2, 2, 1, 8, 4, 1, 1, 1, 5, 2, 5, 1, 1, 1, 1, 2, 2, 1, 1, 2, 5, 1,
+        3, 1, 1, 5, 6, 2, 1, 2, 1, 2, 3, 3, 4, 1, 3, 3, 1, 3, 1, 4, 1, 1, 3, 6, 4, 1, 1, 1, 1, 2,
+        2, 4, 4, 2, 1, 1, 1, 5, 1, 2, 2, 2, 3, 1, 1, 8, 2, 3, 2, 1, 1, 5, 2, 1, 2, 3, 2, 1, 4, 1,
+        1, 2, 5, 1, 3, 2, 8, 1, 1, 1, 2, 1, 5, 4, 1, 1, 2, 2, 1, 2, 2, 1, 1, 7, 1, 1, 1, 9, 2, 2,
+        2, 2, 1, 2, 4, 1, 2, 3, 1, 1,
+    ];
+
+    /// Element widths of a row, starting with its first module.
+    fn widths(row: &DataBarRow) -> Vec<u32> {
+        let mut out: Vec<u32> = Vec::new();
+        for (i, &dark) in row.modules.iter().enumerate() {
+            match out.last_mut() {
+                Some(width) if row.modules[i - 1] == dark => *width += 1,
+                _ => out.push(1),
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn omnidirectional_and_truncated() {
+        for symbology in [1, 2] {
+            for data in ["(01)20012345678909", "2001234567890", "0120012345678909"] {
+                let rows = encode(symbology, data, &OPTIONS).unwrap();
+                assert_eq!(rows.len(), 1);
+                assert!(!rows[0].modules[0]);
+                assert_eq!(widths(&rows[0]), OMNI);
+            }
+        }
+    }
+
+    #[test]
+    fn stacked_types_split_after_the_left_finder() {
+        let top: Vec<u32> = OMNI[..23].iter().copied().chain([1, 1]).collect();
+        let bottom: Vec<u32> = [1, 1]
+            .into_iter()
+            .chain(OMNI[23..].iter().copied())
+            .collect();
+        for (symbology, row_count) in [(3, 3), (4, 5)] {
+            let rows = encode(symbology, "(01)20012345678909", &OPTIONS).unwrap();
+            assert_eq!(rows.len(), row_count);
+            let (first, last) = (&rows[0], &rows[row_count - 1]);
+            assert!(!first.modules[0] && last.modules[0]);
+            assert_eq!(widths(first), top);
+            assert_eq!(widths(last), bottom);
+            assert!(rows.iter().all(|row| row.modules.len() == 50));
+        }
+    }
+
+    #[test]
+    fn limited() {
+        let rows = encode(5, "(01)15012345678907", &OPTIONS).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].modules.len(), 74);
+        assert_eq!(widths(&rows[0]), LIMITED);
+        assert!(encode(5, "(01)25012345678904", &OPTIONS).is_err());
+    }
+
+    #[test]
+    fn expanded() {
+        let rows = encode(6, "(01)98898765432106(3202)012345(15)991231", &OPTIONS).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(widths(&rows[0]), EXPANDED);
+    }
+
+    #[test]
+    fn composite_types_are_rejected() {
+        assert!(encode(7, "(01)20012345678909", &OPTIONS).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Data Matrix (ECC 200) encoder for `^BX`.
+//!
+//! Plain field data goes through `rxing`'s high-level encoder, which picks
+//! the most compact encodation. Data containing `^BX` escape sequences is
+//! encoded natively in ASCII encodation instead, so FNC1, ECI and reader
+//! programming codewords end up exactly where they occur; GS1 data is too,
+//! with FNC1 first and between variable-length fields. In every case the
+//! symbol size is chosen here (fixed `^BX` columns/rows or the aspect
+//! ratio), and `rxing`'s symbol table, Reed-Solomon and module placement
+//! build the final matrix.
+
+use rxing::common::BitMatrix;
+use rxing::datamatrix::encoder::{
+    DefaultPlacement, SymbolInfo, SymbolInfoLookup, SymbolShapeHint, error_correction,
+    high_level_encoder,
+};
+
+use crate::gs1;
+use crate::{ZplError, ZplResult};
+
+/// ASCII encodation: pad codeword.
+const PAD: u8 = 129;
+/// ASCII encodation: first digit pair (`00`).
+const DIGIT_PAIR: u8 = 130;
+/// FNC1, also the GS1 start marker when it is the first codeword.
+const FNC1: u8 = 232;
+/// Reader programming.
+const READER_PROGRAMMING: u8 = 234;
+/// Upper shift to the extended ASCII range.
+const UPPER_SHIFT: u8 = 235;
+/// Extended Channel Interpretation designator.
+const ECI: u8 = 241;
+
+/// `^BX` options that affect encoding.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DataMatrixOptions {
+    /// Symbol columns including the finder pattern (0 = derive).
+    pub columns: u32,
+    /// Symbol rows including the finder pattern (0 = derive).
+    pub rows: u32,
+    /// Prefer rectangular symbols when the size is derived.
+    pub rectangular: bool,
+    /// Escape character introducing control sequences in the data.
+    pub escape: char,
+    /// Encode bracketed GS1 AI data (leading FNC1, FNC1 separators) instead
+    /// of escaped data.
+    pub gs1: bool,
+}
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+/// A piece of escaped field data.
+enum Item {
+    Byte(u8),
+    Fnc1,
+    ReaderProgramming,
+    Eci(u32),
+}
+
+/// Reads the three-digit argument of a `_5NNN` / `_dNNN` sequence.
+fn three_digits(chars: &[char]) -> Option<u32> {
+    if chars.len() < 3 || !chars[..3].iter().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    chars[..3].iter().collect::<String>().parse().ok()
+}
+
+fn byte(c: char) -> ZplResult<u8> {
+    u8::try_from(u32::from(c)).map_err(|_| {
+        generation_error(format!(
+            "character '{}' cannot be encoded in Data Matrix",
+            c
+        ))
+    })
+}
+
+/// Splits `data` at the `^BX` escape sequences, or returns `None` when it
+/// contains none and can go through the high-level encoder unchanged.
+///
+/// Supported sequences (shown with `_`): `__` the escape character itself,
+/// `_1` FNC1, `_3` reader programming, `_5NNN` ECI `NNN`, `_dNNN` the byte
+/// with decimal value `NNN` and `_@` to `_^` the control characters NUL to
+/// RS. Anything else is kept literally.
+fn parse_escapes(data: &str, escape: char) -> ZplResult<Option<Vec<Item>>> {
+    if !data.contains(escape) {
+        return Ok(None);
+    }
+    let chars: Vec<char> = data.chars().collect();
+    let mut items = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if c != escape || i == chars.len() {
+            items.push(Item::Byte(byte(c)?));
+            continue;
+        }
+        match chars[i] {
+            next if next == escape => {
+                items.push(Item::Byte(byte(escape)?));
+                i += 1;
+            }
+            '1' => {
+                items.push(Item::Fnc1);
+                i += 1;
+            }
+            '2' => {
+                return Err(generation_error(
+                    "Data Matrix structured append (escape 2) is not supported",
+                ));
+            }
+            '3' => {
+                items.push(Item::ReaderProgramming);
+                i += 1;
+            }
+            '5' => match three_digits(&chars[i + 1..]) {
+                Some(eci) => {
+                    items.push(Item::Eci(eci));
+                    i += 4;
+                }
+                None => items.push(Item::Byte(byte(escape)?)),
+            },
+            'd' => match three_digits(&chars[i + 1..]).filter(|v| *v <= 255) {
+                Some(value) => {
+                    items.push(Item::Byte(value as u8));
+                    i += 4;
+                }
+                None => items.push(Item::Byte(byte(escape)?)),
+            },
+            control @ '@'..='^' => {
+                items.push(Item::Byte(control as u8 - b'@'));
+                i += 1;
+            }
+            _ => items.push(Item::Byte(byte(escape)?)),
+        }
+    }
+    Ok(Some(items))
+}
+
+/// Encodes escaped data in ASCII encodation (unpadded). When the data
+/// starts with FNC1 (GS1), embedded GS characters become FNC1 separators.
+fn ascii_codewords(items: &[Item]) -> Vec<u8> {
+    let gs1 = matches!(items.first(), Some(Item::Fnc1));
+    let mut codewords = Vec::with_capacity(items.len());
+    let mut i = 0;
+    while i < items.len() {
+        match items[i] {
+            Item::Byte(a) if a.is_ascii_digit() => match items.get(i + 1) {
+                Some(Item::Byte(b)) if b.is_ascii_digit() => {
+                    codewords.push(DIGIT_PAIR + (a - b'0') * 10 + (b - b'0'));
+                    i += 1;
+                }
+                _ => codewords.push(a + 1),
+            },
+            Item::Byte(0x1D) if gs1 => codewords.push(FNC1),
+            Item::Byte(b) if b < 128 => codewords.push(b + 1),
+            Item::Byte(b) => codewords.extend([UPPER_SHIFT, b - 127]),
+            Item::Fnc1 => codewords.push(FNC1),
+            Item::ReaderProgramming => codewords.push(READER_PROGRAMMING),
+            Item::Eci(value) => {
+                codewords.push(ECI);
+                match value {
+                    0..=126 => codewords.push(value as u8 + 1),
+                    127..=16382 => codewords.extend([
+                        ((value - 127) / 254 + 128) as u8,
+                        ((value - 127) % 254 + 1) as u8,
+                    ]),
+                    _ => codewords.extend([
+                        ((value - 16383) / 64516 + 192) as u8,
+                        ((value - 16383) / 254 % 254 + 1) as u8,
+                        ((value - 16383) % 254 + 1) as u8,
+                    ]),
+                }
+            }
+        }
+        i += 1;
+    }
+    codewords
+}
+
+/// Pads `codewords` to `capacity` (ISO/IEC 16022 5.2.3, 253-state
+/// randomised pads after the first).
+fn pad(codewords: &mut Vec<u8>, capacity: usize) {
+    if codewords.len() < capacity {
+        codewords.push(PAD);
+    }
+    while codewords.len() < capacity {
+        let position = codewords.len() as u32 + 1;
+        let value = u32::from(PAD) + (149 * position) % 253 + 1;
+        codewords.push(if value <= 254 { value } else { value - 254 } as u8);
+    }
+}
+
+type SymbolRow = (bool, u32, u32, u32, u32, u32, i32, u32);
+
+/// ECC 200 symbol sizes in `rxing`'s lookup order (`SymbolInfo::with_details`
+/// arguments: rectangular, data and error codewords, data region width and
+/// height, region count and interleaved block sizes), followed by the
+/// 144x144 symbol. `rxing` keeps its table private; it is repeated here so
+/// the lookup can be restricted to the `^BX` size.
+const SYMBOLS: [SymbolRow; 29] = [
+    (false, 3, 5, 8, 8, 1, 3, 5),
+    (false, 5, 7, 10, 10, 1, 5, 7),
+    (true, 5, 7, 16, 6, 1, 5, 7),
+    (false, 8, 10, 12, 12, 1, 8, 10),
+    (true, 10, 11, 14, 6, 2, 10, 11),
+    (false, 12, 12, 14, 14, 1, 12, 12),
+    (true, 16, 14, 24, 10, 1, 16, 14),
+    (false, 18, 14, 16, 16, 1, 18, 14),
+    (false, 22, 18, 18, 18, 1, 22, 18),
+    (true, 22, 18, 16, 10, 2, 22, 18),
+    (false, 30, 20, 20, 20, 1, 30, 20),
+    (true, 32, 24, 16, 14, 2, 32, 24),
+    (false, 36, 24, 22, 22, 1, 36, 24),
+    (false, 44, 28, 24, 24, 1, 44, 28),
+    (true, 49, 28, 22, 14, 2, 49, 28),
+    (false, 62, 36, 14, 14, 4, 62, 36),
+    (false, 86, 42, 16, 16, 4, 86, 42),
+    (false, 114, 48, 18, 18, 4, 114, 48),
+    (false, 144, 56, 20, 20, 4, 144, 56),
+    (false, 174, 68, 22, 22, 4, 174, 68),
+    (false, 204, 84, 24, 24, 4, 102, 42),
+    (false, 280, 112, 14, 14, 16, 140, 56),
+    (false, 368, 144, 16, 16, 16, 92, 36),
+    (false, 456, 192, 18, 18, 16, 114, 48),
+    (false, 576, 224, 20, 20, 16, 144, 56),
+    (false, 696, 272, 22, 22, 16, 174, 68),
+    (false, 816, 336, 24, 24, 16, 136, 56),
+    (false, 1050, 408, 18, 18, 36, 175, 68),
+    (false, 1304, 496, 20, 20, 36, 163, 62),
+];
+
+/// Longest field data that can fit in the largest symbol (1558 codewords of
+/// digit pairs).
+const MAX_DATA_LEN: usize = 3116;
+
+/// Capacity of the catch-all entry appended to the candidate symbols, so
+/// `rxing`'s encoder (which panics when no symbol fits) always finds one.
+/// Being chosen means the data does not fit.
+const OVERFLOW_CAPACITY: u32 = 8192;
+
+/// Symbols allowed by the `^BX` options, smallest first, followed by the
+/// overflow entry.
+fn candidates(options: &DataMatrixOptions) -> Vec<SymbolInfo> {
+    let mut symbols: Vec<SymbolInfo> = SYMBOLS
+        .iter()
+        .map(
+            |&(rect, data, ec, width, height, regions, rs_data, rs_ec)| {
+                SymbolInfo::with_details(rect, data, ec, width, height, regions, rs_data, rs_ec)
+            },
+        )
+        .chain(std::iter::once(SymbolInfo::new_symbol_info_144()))
+        .zip(SYMBOLS.iter().map(|s| s.0).chain(std::iter::once(false)))
+        .filter(|(symbol, rectangular)| {
+            let (Ok(width), Ok(height)) = (symbol.getSymbolWidth(), symbol.getSymbolHeight())
+            else {
+                return false;
+            };
+            if options.columns > 0 || options.rows > 0 {
+                (options.columns == 0 || width == options.columns)
+                    && (options.rows == 0 || height == options.rows)
+            } else {
+                *rectangular == options.rectangular
+            }
+        })
+        .map(|(symbol, _)| symbol)
+        .collect();
+    symbols.push(SymbolInfo::new(false, OVERFLOW_CAPACITY, 0, 8, 8, 1));
+    symbols
+}
+
+/// Lays the placed codewords out with the finder and timing patterns of
+/// each data region.
+fn symbol_matrix(placement: &DefaultPlacement, symbol: &SymbolInfo) -> ZplResult<BitMatrix> {
+    let dims = |r: rxing::common::Result<u32>| r.map_err(generation_error);
+    let width = dims(symbol.getSymbolWidth())?;
+    let height = dims(symbol.getSymbolHeight())?;
+    let data_width = dims(symbol.getSymbolDataWidth())?;
+    let data_height = dims(symbol.getSymbolDataHeight())?;
+    let region_width = data_width / ((width - data_width) / 2);
+    let region_height = data_height / ((height - data_height) / 2);
+
+    let mut matrix = BitMatrix::new(width, height).map_err(generation_error)?;
+    let mut my = 0;
+    for y in 0..data_height {
+        if y % region_height == 0 {
+            for x in (0..width).step_by(2) {
+                matrix.set(x, my);
+            }
+            my += 1;
+        }
+        let mut mx = 0;
+        for x in 0..data_width {
+            if x % region_width == 0 {
+                matrix.set(mx, my);
+                mx += 1;
+            }
+            if placement.getBit(x as usize, y as usize) {
+                matrix.set(mx, my);
+            }
+            mx += 1;
+            if x % region_width == region_width - 1 {
+                if y % 2 == 0 {
+                    matrix.set(mx, my);
+                }
+                mx += 1;
+            }
+        }
+        my += 1;
+        if y % region_height == region_height - 1 {
+            for x in 0..width {
+                matrix.set(x, my);
+            }
+            my += 1;
+        }
+    }
+    Ok(matrix)
+}
+
+/// Encodes `data` as an ECC 200 symbol.
+pub(crate) fn encode(options: &DataMatrixOptions, data: &str) -> ZplResult<BitMatrix> {
+    if data.is_empty() {
+        return Err(generation_error("Data Matrix data is empty"));
+    }
+    if data.chars().count() > MAX_DATA_LEN {
+        return Err(generation_error("data is too long for Data Matrix"));
+    }
+    let symbols = candidates(options);
+    let mut lookup = SymbolInfoLookup::new();
+    lookup.overrideSymbolSet(&symbols);
+
+    let items = if options.gs1 {
+        let mut items = vec![Item::Fnc1];
+        for c in gs1::element_string(data)?.chars() {
+            items.push(Item::Byte(byte(c)?));
+        }
+        Some(items)
+    } else {
+        parse_escapes(data, options.escape)?
+    };
+    let mut codewords = match items {
+        Some(items) => ascii_codewords(&items),
+        None => high_level_encoder::encodeHighLevelWithDimensionForceC40WithSymbolInfoLookup(
+            data,
+            SymbolShapeHint::FORCE_NONE,
+            None,
+            None,
+            false,
+            Some(lookup),
+        )
+        .map_err(generation_error)?
+        .chars()
+        .map(|c| c as u8)
+        .collect(),
+    };
+
+    let symbol = symbols[..symbols.len() - 1]
+        .iter()
+        .find(|symbol| symbol.getDataCapacity() as usize >= codewords.len())
+        .ok_or_else(|| {
+            generation_error(if options.columns > 0 || options.rows > 0 {
+                format!(
+                    "data does not fit in a {}x{} Data Matrix",
+                    options.rows, options.columns
+                )
+            } else {
+                "data does not fit in Data Matrix".to_owned()
+            })
+        })?;
+    pad(&mut codewords, symbol.getDataCapacity() as usize);
+
+    let message: String = codewords.iter().map(|&c| c as char).collect();
+    let full = error_correction::encodeECC200(&message, symbol).map_err(generation_error)?;
+    let mut placement = DefaultPlacement::new(
+        full,
+        symbol.getSymbolDataWidth().map_err(generation_error)? as usize,
+        symbol.getSymbolDataHeight().map_err(generation_error)? as usize,
+    );
+    placement.place().map_err(generation_error)?;
+    symbol_matrix(&placement, symbol)
+}
@@ -0,0 +1,833 @@
+//! Native 1-D barcode support shared by both backends.
+//!
+//! `rxing` covers most linear symbologies, but not every one ZPL can print.
+//! This module holds the encoders it lacks, the data normalization Zebra
+//! printers apply before encoding (UPC-E compression, check digits), and the
+//! placement of human-readable text under or over the bars.
+
+use std::sync::Arc;
+
+use rxing::common::BitMatrix;
+
+use super::check_digit;
+use crate::engine::Barcode1DKind;
+use crate::{ZplError, ZplResult};
+
+/// Quiet zone added on each side of natively encoded symbols, in modules.
+/// Matches the default side margin `rxing` gives its 1-D symbols, so native
+/// and `rxing`-encoded barcodes share the same origin.
+pub(crate) const QUIET_ZONE: u32 = 5;
+
+/// An encoded 1-D symbol ready to draw.
+#[derive(Debug, Clone)]
+pub(crate) struct LinearSymbol {
+    /// One-row bar matrix.
+    pub matrix: Arc<BitMatrix>,
+    /// Width in dots of one matrix column: the module width for module-based
+    /// symbologies, `1` for two-width symbologies built at dot resolution.
+    pub column_width: u32,
+    /// Text printed in the interpretation line.
+    pub text: String,
+}
+
+/// Options a generic 1-D field carries from its command and `^BY`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LinearOptions {
+    /// Narrow bar width in dots.
+    pub module_width: u32,
+    /// Wide-to-narrow ratio.
+    pub ratio: f64,
+    /// Symbology-specific check digit selection.
+    pub check_digit: char,
+    /// Whether the check digit is printed in the interpretation line.
+    pub print_check_digit: bool,
+}
+
+/// A run of human-readable text centred over a span of matrix columns.
+#[derive(Debug, Clone)]
+pub(crate) struct HriGroup {
+    /// Text to draw.
+    pub text: String,
+    /// First matrix column of the span (may be negative: outside the symbol).
+    pub start: i32,
+    /// Column one past the end of the span.
+    pub end: i32,
+}
+
+// ─── Matrix construction ────────────────────────────────────────────────────
+
+/// Builds a one-row bit matrix from a module sequence (`true` = bar),
+/// surrounded by [`QUIET_ZONE`] modules on each side.
+pub(crate) fn modules_to_matrix(modules: &[bool]) -> ZplResult<BitMatrix> {
+    let width = modules.len() as u32 + QUIET_ZONE * 2;
+    let mut matrix = BitMatrix::new(width, 1)
+        .map_err(|e| ZplError::BackendError(format!("Barcode Generation Error: {}", e)))?;
+    for (i, &bar) in modules.iter().enumerate() {
+        if bar {
+            matrix.set(QUIET_ZONE + i as u32, 0);
+        }
+    }
+    Ok(matrix)
+}
+
+/// Wide-to-narrow ratio a printer uses for a `^BY` ratio: 2.0 to 3.0 in
+/// steps of 0.1.
+pub(crate) fn printer_ratio(ratio: f64) -> f64 {
+    (ratio.clamp(2.0, 3.0) * 10.0).round() / 10.0
+}
+
+/// Builds a one-row bit matrix at dot resolution from a wide/narrow element
+/// sequence (alternating bar and space, starting with a bar; `true` = wide).
+///
+/// Two-width symbologies cannot be drawn at module granularity because the
+/// wide element is a fractional multiple of the narrow one, so each element
+/// is laid out in dots instead, the wide one rounded to the nearest dot for
+/// the [`printer_ratio`] of `ratio`.
+pub(crate) fn elements_to_matrix(
+    elements: &[bool],
+    module_width: u32,
+    ratio: f64,
+) -> ZplResult<BitMatrix> {
+    let narrow = module_width.max(1);
+    let wide = ((narrow as f64 * printer_ratio(ratio)).round() as u32).max(narrow + 1);
+    let quiet = QUIET_ZONE * narrow;
+    let body: u32 = elements
+        .iter()
+        .map(|&w| if w { wide } else { narrow })
+        .sum();
+    let mut matrix = BitMatrix::new(body + quiet * 2, 1)
+        .map_err(|e| ZplError::BackendError(format!("Barcode Generation Error: {}", e)))?;
+    let mut x = quiet;
+    for (i, &w) in elements.iter().enumerate() {
+        let width = if w { wide } else { narrow };
+        if i % 2 == 0 {
+            for dx in 0..width {
+                matrix.set(x + dx, 0);
+            }
+        }
+        x += width;
+    }
+    Ok(matrix)
+}
+
+/// Appends the `count` low bits of `pattern`, most significant first, as
+/// wide (`1`) or narrow (`0`) elements.
+fn push_wide(elements: &mut Vec<bool>, pattern: u16, count: u32) {
+    elements.extend((0..count).rev().map(|i| pattern >> i & 1 == 1));
+}
+
+/// Appends a pattern given as a string of `'1'` (bar) and `'0'` (space) modules.
+fn push_bits(modules: &mut Vec<bool>, bits: &str) {
+    modules.extend(bits.bytes().map(|b| b == b'1'));
+}
+
+/// Column span `(first, last + 1)` of the bars in a one-row matrix.
+pub(crate) fn bar_span(matrix: &BitMatrix) -> (i32, i32) {
+    let width = matrix.getWidth();
+    let first = (0..width).find(|&x| matrix.get(x, 0)).unwrap_or(0);
+    let last = (0..width)
+        .rev()
+        .find(|&x| matrix.get(x, 0))
+        .unwrap_or(width);
+    (first as i32, last as i32 + 1)
+}
+
+// ─── UPC/EAN data normalization ─────────────────────────────────────────────
+
+/// Returns `data` when it is non-empty and purely numeric.
+fn digits<'a>(data: &'a str, symbology: &str) -> ZplResult<&'a str> {
+    let data = data.trim();
+    if data.is_empty() || !data.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ZplError::BackendError(format!(
+            "Barcode Generation Error: {} data must be numeric, got {:?}",
+            symbology, data
+        )));
+    }
+    Ok(data)
+}
+
+/// Expands a UPC-E body (number system + six digits) to the eleven-digit
+/// UPC-A body it stands for.
+fn upc_e_to_upc_a(body: &str) -> String {
+    let d: Vec<char> = body.chars().collect();
+    let (ns, m) = (d[0], &d[1..7]);
+    let expanded: String = match m[5] {
+        '0'..='2' => [m[0], m[1], m[5], '0', '0', '0', '0', m[2], m[3], m[4]]
+            .iter()
+            .collect(),
+        '3' => [m[0], m[1], m[2], '0', '0', '0', '0', '0', m[3], m[4]]
+            .iter()
+            .collect(),
+        '4' => [m[0], m[1], m[2], m[3], '0', '0', '0', '0', '0', m[4]]
+            .iter()
+            .collect(),
+        _ => [m[0], m[1], m[2], m[3], m[4], '0', '0', '0', '0', m[5]]
+            .iter()
+            .collect(),
+    };
+    format!("{}{}", ns, expanded)
+}
+
+/// Compresses an eleven-digit UPC-A body (number system, manufacturer,
+/// product) into its six-digit UPC-E form, when the zero-suppression rules
+/// allow it.
+fn upc_a_to_upc_e(body: &str) -> Option<String> {
+    let (mfr, product) = (&body[1..6], &body[6..11]);
+    let m: Vec<char> = mfr.chars().collect();
+    let p: Vec<char> = product.chars().collect();
+    if matches!(&mfr[2..], "000" | "100" | "200") && &product[..2] == "00" {
+        Some([m[0], m[1], p[2], p[3], p[4], m[2]].iter().collect())
+    } else if &mfr[3..] == "00" && &product[..3] == "000" {
+        Some([m[0], m[1], m[2], p[3], p[4], '3'].iter().collect())
+    } else if &mfr[4..] == "0" && &product[..4] == "0000" {
+        Some([m[0], m[1], m[2], m[3], p[4], '4'].iter().collect())
+    } else if &product[..4] == "0000" && p[4] >= '5' {
+        Some([m[0], m[1], m[2], m[3], m[4], p[4]].iter().collect())
+    } else {
+        None
+    }
+}
+
+/// EAN-13 and UPC-A: truncates or left-pads the data with zeros to
+/// `body_len` digits and appends the computed check digit, so a given check
+/// digit is replaced by the correct one.
+fn normalize_retail(data: &str, symbology: &str, body_len: usize) -> ZplResult<String> {
+    let data = digits(data, symbology)?;
+    let body = format!("{:0>1$}", &data[..data.len().min(body_len)], body_len);
+    let check = check_digit::mod10(&body);
+    Ok(format!("{}{}", body, check))
+}
+
+/// EAN-8: keeps the first seven digits and appends the computed check digit.
+fn normalize_ean8(data: &str) -> ZplResult<String> {
+    let data = digits(data, "EAN-8")?;
+    if data.len() < 7 {
+        return Err(ZplError::BackendError(format!(
+            "Barcode Generation Error: EAN-8 needs 7 digits, got {}",
+            data.len()
+        )));
+    }
+    let body = &data[..7];
+    Ok(format!("{}{}", body, check_digit::mod10(body)))
+}
+
+/// UPC-E: accepts the six-digit UPC-E body (optionally with number system
+/// and check digit) or a UPC-A number to be zero-suppressed, and returns the
+/// eight-digit form (number system, six digits, check digit).
+fn normalize_upc_e(data: &str) -> ZplResult<String> {
+    let data = digits(data, "UPC-E")?;
+    let body = match data.len() {
+        6 => format!("0{}", data),
+        7 | 8 => data[..7].to_string(),
+        10..=12 => {
+            let upc_a = if data.len() == 10 {
+                format!("0{}", data)
+            } else {
+                data[..11].to_string()
+            };
+            let compressed = upc_a_to_upc_e(&upc_a).ok_or_else(|| {
+                ZplError::BackendError(format!(
+                    "Barcode Generation Error: UPC-A {} cannot be zero-suppressed to UPC-E",
+                    upc_a
+                ))
+            })?;
+            format!("{}{}", &upc_a[..1], compressed)
+        }
+        n => {
+            return Err(ZplError::BackendError(format!(
+                "Barcode Generation Error: UPC-E needs 6-8 or 10-12 digits, got {}",
+                n
+            )));
+        }
+    };
+    if !body.starts_with(['0', '1']) {
+        return Err(ZplError::BackendError(
+            "Barcode Generation Error: UPC-E number system must be 0 or 1".into(),
+        ));
+    }
+    let check = check_digit::mod10(&upc_e_to_upc_a(&body));
+    Ok(format!("{}{}", body, check))
+}
+
+/// UPC/EAN add-on: exactly two or five digits.
+fn normalize_extension(data: &str) -> ZplResult<String> {
+    let data = digits(data, "UPC/EAN extension")?;
+    match data.len() {
+        2 | 5 => Ok(data.to_string()),
+        n => Err(ZplError::BackendError(format!(
+            "Barcode Generation Error: UPC/EAN extension needs 2 or 5 digits, got {}",
+            n
+        ))),
+    }
+}
+
+/// Applies the printer-side data rules for `kind`, returning the exact
+/// string to encode (and print in the interpretation line).
+pub(crate) fn normalize_data(kind: Barcode1DKind, data: &str) -> ZplResult<String> {
+    match kind {
+        Barcode1DKind::Ean13 => normalize_retail(data, "EAN-13", 12),
+        Barcode1DKind::UpcA => normalize_retail(data, "UPC-A", 11),
+        Barcode1DKind::Ean8 => normalize_ean8(data),
+        Barcode1DKind::UpcE => normalize_upc_e(data),
+        Barcode1DKind::UpcEanExtension => normalize_extension(data),
+        _ => Ok(data.to_string()),
+    }
+}
+
+// ─── UPC/EAN add-on (^BS) ───────────────────────────────────────────────────
+
+/// Odd-parity (set A) digit patterns.
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+
+/// Even-parity (set B) digit patterns.
+const EAN_G: [&str; 10] = [
+    "0100111", "0110011", "0011011", "0100001", "0011101", "0111001", "0000101", "0010001",
+    "0001001", "0010111",
+];
+
+/// Five-digit add-on parity per checksum value (`true` = set B).
+const EXT5_PARITY: [[bool; 5]; 10] = [
+    [true, true, false, false, false],
+    [true, false, true, false, false],
+    [true, false, false, true, false],
+    [true, false, false, false, true],
+    [false, true, true, false, false],
+    [false, false, true, true, false],
+    [false, false, false, true, true],
+    [false, true, false, true, false],
+    [false, true, false, false, true],
+    [false, false, true, false, true],
+];
+
+/// Encodes a two- or five-digit UPC/EAN add-on symbol.
+pub(crate) fn encode_extension(data: &str) -> ZplResult<BitMatrix> {
+    let data = normalize_extension(data)?;
+    let d: Vec<usize> = data.bytes().map(|b| (b - b'0') as usize).collect();
+    let parity: Vec<bool> = if d.len() == 2 {
+        let v = d[0] * 10 + d[1];
+        vec![v % 4 >= 2, v % 2 == 1]
+    } else {
+        let sum = 3 * (d[0] + d[2] + d[4]) + 9 * (d[1] + d[3]);
+        EXT5_PARITY[sum % 10].to_vec()
+    };
+
+    let mut modules = Vec::with_capacity(d.len() * 9 + 4);
+    push_bits(&mut modules, "1011");
+    for (i, (&digit, &even)) in d.iter().zip(&parity).enumerate() {
+        if i > 0 {
+            push_bits(&mut modules, "01");
+        }
+        push_bits(&mut modules, if even { EAN_G[digit] } else { EAN_L[digit] });
+    }
+    modules_to_matrix(&modules)
+}
+
+// ─── Two-width symbologies ──────────────────────────────────────────────────
+
+/// Builds the error returned for characters a symbology cannot encode.
+fn invalid_data(symbology: &str, data: &str) -> ZplError {
+    ZplError::BackendError(format!(
+        "Barcode Generation Error: {:?} contains characters {} cannot encode",
+        data, symbology
+    ))
+}
+
+/// Codabar character set, in table order.
+const CODABAR_CHARS: &str = "0123456789-$:/.+ABCD";
+
+/// Codabar patterns: 4 bars and 3 spaces, `1` = wide.
+const CODABAR_PATTERNS: [u16; 20] = [
+    0x003, 0x006, 0x009, 0x060, 0x012, 0x042, 0x021, 0x024, 0x030, 0x048, 0x00C, 0x018, 0x045,
+    0x051, 0x054, 0x015, 0x01A, 0x029, 0x00B, 0x00E,
+];
+
+/// Codabar: `data` includes the A-D start and stop characters.
+fn codabar(data: &str) -> ZplResult<(Vec<bool>, String)> {
+    let valid_guard = |c: Option<char>| matches!(c, Some('A'..='D'));
+    if data.len() < 2 || !valid_guard(data.chars().next()) || !valid_guard(data.chars().last()) {
+        return Err(invalid_data("Codabar", data));
+    }
+    let mut elements = Vec::with_capacity(data.len() * 8);
+    for (i, c) in data.chars().enumerate() {
+        let idx = CODABAR_CHARS
+            .find(c)
+            .ok_or_else(|| invalid_data("Codabar", data))?;
+        if i > 0 {
+            elements.push(false);
+        }
+        push_wide(&mut elements, CODABAR_PATTERNS[idx], 7);
+    }
+    Ok((elements, data.to_string()))
+}
+
+/// Code 11 patterns for `0-9` and `-`: 3 bars and 2 spaces, `1` = wide.
+const CODE11_PATTERNS: [u16; 11] = [
+    0x01, 0x11, 0x09, 0x18, 0x05, 0x14, 0x0C, 0x03, 0x12, 0x10, 0x04,
+];
+
+/// Code 11 start/stop pattern.
+const CODE11_GUARD: u16 = 0x06;
+
+fn code11_value(c: char) -> Option<usize> {
+    match c {
+        '0'..='9' => Some(c as usize - '0' as usize),
+        '-' => Some(10),
+        _ => None,
+    }
+}
+
+/// Code 11: `check_digit` `'Y'` appends one check character (C), anything
+/// else two (C and K), as Zebra printers do. The check characters are
+/// printed in the interpretation line too.
+fn code11(data: &str, check_digit: char) -> ZplResult<(Vec<bool>, String)> {
+    let mut values = data
+        .chars()
+        .map(code11_value)
+        .collect::<Option<Vec<_>>>()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| invalid_data("Code 11", data))?;
+    values.push(check_digit::mod11(&values, 10));
+    if check_digit != 'Y' {
+        values.push(check_digit::mod11(&values, 9));
+    }
+
+    let mut elements = Vec::with_capacity((values.len() + 2) * 6);
+    push_wide(&mut elements, CODE11_GUARD, 5);
+    for &v in &values {
+        elements.push(false);
+        push_wide(&mut elements, CODE11_PATTERNS[v], 5);
+    }
+    elements.push(false);
+    push_wide(&mut elements, CODE11_GUARD, 5);
+
+    let text = values
+        .iter()
+        .map(|&v| {
+            if v == 10 {
+                '-'
+            } else {
+                char::from(b'0' + v as u8)
+            }
+        })
+        .collect();
+    Ok((elements, text))
+}
+
+/// MSI: `check_digit` selects `A` (none), `B` (Mod 10), `C` (Mod 10 twice) or
+/// `D` (Mod 11 then Mod 10).
+fn msi(data: &str, check_digit: char, print_check_digit: bool) -> ZplResult<(Vec<bool>, String)> {
+    let data = digits(data, "MSI")?;
+    let mut full = data.to_string();
+    match check_digit {
+        'A' => {}
+        'C' => {
+            full.push(check_digit::msi_mod10(&full));
+            full.push(check_digit::msi_mod10(&full));
+        }
+        'D' => {
+            full.push_str(&check_digit::msi_mod11(&full));
+            full.push(check_digit::msi_mod10(&full));
+        }
+        _ => full.push(check_digit::msi_mod10(&full)),
+    }
+
+    let mut elements = Vec::with_capacity(full.len() * 8 + 5);
+    elements.extend([true, false]);
+    for b in full.bytes() {
+        let v = (b - b'0') as u16;
+        for bit in (0..4).rev() {
+            let one = v >> bit & 1 == 1;
+            elements.extend([one, !one]);
+        }
+    }
+    elements.extend([false, true, false]);
+    let text = if print_check_digit {
+        full
+    } else {
+        data.to_string()
+    };
+    Ok((elements, text))
+}
+
+/// Plessey: hexadecimal data, four bits per character least significant
+/// first, followed by an 8-bit CRC.
+fn plessey(data: &str, print_check_digit: bool) -> ZplResult<(Vec<bool>, String)> {
+    let data = data.trim().to_ascii_uppercase();
+    let values = data
+        .chars()
+        .map(|c| c.to_digit(16))
+        .collect::<Option<Vec<_>>>()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| invalid_data("Plessey", &data))?;
+    let mut bits: Vec<bool> = values
+        .iter()
+        .flat_map(|v| (0..4).map(move |i| v >> i & 1 == 1))
+        .collect();
+    let crc = check_digit::plessey_crc(&bits);
+    bits.extend_from_slice(&crc);
+
+    let mut elements = Vec::with_capacity(bits.len() * 2 + 17);
+    for bit in [true, true, false, true]
+        .into_iter()
+        .chain(bits.iter().copied())
+    {
+        elements.extend([bit, !bit]);
+    }
+    elements.extend([true, true, false, true, false, false, true, false, true]);
+
+    let mut text = data.clone();
+    if print_check_digit {
+        for nibble in crc.chunks(4) {
+            let v = nibble
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (i, &b)| acc | (b as u32) << i);
+            text.push(char::from_digit(v, 16).unwrap_or('0').to_ascii_uppercase());
+        }
+    }
+    Ok((elements, text))
+}
+
+/// 2 of 5 digit patterns (weights 1, 2, 4, 7, parity), `1` = wide.
+const TWO_OF_FIVE_PATTERNS: [u16; 10] = [
+    0b00110, 0b10001, 0b01001, 0b11000, 0b00101, 0b10100, 0b01100, 0b00011, 0b10010, 0b01010,
+];
+
+/// Standard and Industrial 2 of 5: every digit is five bars, two of them
+/// wide, separated by narrow spaces; the spaces carry no data.
+/// `check_digit` `'Y'` appends a Mod 10 check digit.
+fn two_of_five(data: &str, symbology: &str, check_digit: char) -> ZplResult<(Vec<bool>, String)> {
+    let mut data = digits(data, symbology)?.to_string();
+    if check_digit == 'Y' {
+        data.push(check_digit::mod10(&data));
+    }
+    let mut bars = vec![true, true, false];
+    for b in data.bytes() {
+        let pattern = TWO_OF_FIVE_PATTERNS[(b - b'0') as usize];
+        bars.extend((0..5).rev().map(|i| pattern >> i & 1 == 1));
+    }
+    bars.extend([true, false, true]);
+
+    let mut elements = Vec::with_capacity(bars.len() * 2);
+    for (i, &bar) in bars.iter().enumerate() {
+        if i > 0 {
+            elements.push(false);
+        }
+        elements.push(bar);
+    }
+    Ok((elements, data))
+}
+
+/// Interleaved 2 of 5: digits are paired, the first of each pair in the
+/// bars and the second in the spaces. `check_digit` `'Y'` appends a Mod 10
+/// check digit; data of odd length (check digit included) gets a leading
+/// zero, as on printers.
+fn interleaved_two_of_five(data: &str, check_digit: char) -> ZplResult<(Vec<bool>, String)> {
+    let mut data = digits(data, "Interleaved 2 of 5")?.to_string();
+    if check_digit == 'Y' {
+        data.push(check_digit::mod10(&data));
+    }
+    if data.len() % 2 == 1 {
+        data.insert(0, '0');
+    }
+
+    let mut elements = vec![false; 4];
+    for pair in data.as_bytes().chunks(2) {
+        let bars = TWO_OF_FIVE_PATTERNS[(pair[0] - b'0') as usize];
+        let spaces = TWO_OF_FIVE_PATTERNS[(pair[1] - b'0') as usize];
+        for i in (0..5).rev() {
+            elements.push(bars >> i & 1 == 1);
+            elements.push(spaces >> i & 1 == 1);
+        }
+    }
+    elements.extend([true, false, false]);
+    Ok((elements, data))
+}
+
+/// Code 39 character set, in Mod 43 value order.
+const CODE39_CHARS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+/// Code 39 patterns: 5 bars and 4 spaces, `1` = wide.
+const CODE39_PATTERNS: [u16; 43] = [
+    0x034, 0x121, 0x061, 0x160, 0x031, 0x130, 0x070, 0x025, 0x124, 0x064, 0x109, 0x049, 0x148,
+    0x019, 0x118, 0x058, 0x00D, 0x10C, 0x04C, 0x01C, 0x103, 0x043, 0x142, 0x013, 0x112, 0x052,
+    0x007, 0x106, 0x046, 0x016, 0x181, 0x0C1, 0x1C0, 0x091, 0x190, 0x0D0, 0x085, 0x184, 0x0C4,
+    0x0A8, 0x0A2, 0x08A, 0x02A,
+];
+
+/// Code 39 start/stop character (`*`).
+const CODE39_GUARD: u16 = 0x094;
+
+/// Code 39 elements for character values, between start and stop
+/// characters, with a narrow gap after each character.
+fn code39_elements(values: &[usize]) -> Vec<bool> {
+    let mut elements = Vec::with_capacity((values.len() + 2) * 10);
+    push_wide(&mut elements, CODE39_GUARD, 9);
+    for &v in values {
+        elements.push(false);
+        push_wide(&mut elements, CODE39_PATTERNS[v], 9);
+    }
+    elements.push(false);
+    push_wide(&mut elements, CODE39_GUARD, 9);
+    elements
+}
+
+/// Full ASCII Code 39 pair for a character outside the Code 39 set.
+fn code39_full_ascii(c: char) -> Option<[char; 2]> {
+    let shifted = |shift: char, base: u8, offset: u8| Some([shift, (base + offset) as char]);
+    match c as u32 {
+        0 => Some(['%', 'U']),
+        c @ 1..=26 => shifted('$', b'A', c as u8 - 1),
+        c @ 27..=31 => shifted('%', b'A', c as u8 - 27),
+        c @ 33..=44 => shifted('/', b'A', c as u8 - 33),
+        58 => Some(['/', 'Z']),
+        c @ 59..=63 => shifted('%', b'F', c as u8 - 59),
+        64 => Some(['%', 'V']),
+        c @ 91..=95 => shifted('%', b'K', c as u8 - 91),
+        96 => Some(['%', 'W']),
+        c @ 97..=122 => shifted('+', b'A', c as u8 - 97),
+        c @ 123..=127 => shifted('%', b'P', c as u8 - 123),
+        _ => None,
+    }
+}
+
+/// Code 39 character values of `data`, writing characters outside the
+/// Code 39 set as Full ASCII pairs whose shift character is mapped by
+/// `shift` (Code 39 uses the `$`, `%`, `/` and `+` characters themselves).
+fn full_ascii_values(
+    data: &str,
+    symbology: &str,
+    shift: impl Fn(char) -> Option<usize>,
+) -> ZplResult<Vec<usize>> {
+    let mut values = Vec::with_capacity(data.len());
+    for c in data.chars() {
+        match CODE39_CHARS.find(c) {
+            Some(v) => values.push(v),
+            None => {
+                let [s, c] = code39_full_ascii(c).ok_or_else(|| invalid_data(symbology, data))?;
+                values.extend(shift(s).into_iter().chain(CODE39_CHARS.find(c)));
+            }
+        }
+    }
+    if values.is_empty() {
+        return Err(invalid_data(symbology, data));
+    }
+    Ok(values)
+}
+
+/// Code 39 (`^B3`): characters outside the Code 39 set are written as Full
+/// ASCII pairs. `check_digit` appends the Mod 43 check character, which is
+/// printed in the interpretation line too.
+pub(crate) fn code39(data: &str, check_digit: bool) -> ZplResult<(Vec<bool>, String)> {
+    let mut values = full_ascii_values(data, "Code 39", |s| CODE39_CHARS.find(s))?;
+    let mut text = data.to_string();
+    if check_digit {
+        let check = check_digit::mod43(&values);
+        values.push(check);
+        text.push_str(&CODE39_CHARS[check..=check]);
+    }
+    Ok((code39_elements(&values), text))
+}
+
+/// LOGMARS: Code 39 with a mandatory Mod 43 check character.
+fn logmars(data: &str) -> ZplResult<(Vec<bool>, String)> {
+    let data = data.trim().to_ascii_uppercase();
+    let mut values = data
+        .chars()
+        .map(|c| CODE39_CHARS.find(c))
+        .collect::<Option<Vec<_>>>()
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| invalid_data("LOGMARS", &data))?;
+    let check = check_digit::mod43(&values);
+    values.push(check);
+
+    let text = format!("{}{}", data, &CODE39_CHARS[check..=check]);
+    Ok((code39_elements(&values), text))
+}
+
+/// Code 93 patterns of values 0-46 (the Code 39 character set, then the
+/// `($)`, `(%)`, `(/)` and `(+)` shift characters), 9 modules each.
+const CODE93_PATTERNS: [u16; 47] = [
+    0x114, 0x148, 0x144, 0x142, 0x128, 0x124, 0x122, 0x150, 0x112, 0x10A, 0x1A8, 0x1A4, 0x1A2,
+    0x194, 0x192, 0x18A, 0x168, 0x164, 0x162, 0x134, 0x11A, 0x158, 0x14C, 0x146, 0x12C, 0x116,
+    0x1B4, 0x1B2, 0x1AC, 0x1A6, 0x196, 0x19A, 0x16C, 0x166, 0x136, 0x13A, 0x12E, 0x1D4, 0x1D2,
+    0x1CA, 0x16E, 0x176, 0x1AE, 0x126, 0x1DA, 0x1D6, 0x132,
+];
+
+/// Code 93 start/stop character.
+const CODE93_GUARD: u16 = 0x15E;
+
+/// How the printer shows the Code 93 shift characters ($), (%), (/) and (+)
+/// (values 43-46) in the interpretation line.
+const CODE93_SHIFT_TEXT: &str = "&'()";
+
+/// Code 93 (`^BA`): Full ASCII data followed by the Mod 47 check characters
+/// C and K, which `print_check` also prints in the interpretation line.
+fn code93(data: &str, print_check: bool) -> ZplResult<(Vec<bool>, String)> {
+    let mut values = full_ascii_values(data, "Code 93", |s| {
+        "$%/+".find(s).map(|i| CODE39_CHARS.len() + i)
+    })?;
+    let mut text = data.to_string();
+    for max_weight in [20, 15] {
+        let check = check_digit::mod47(&values, max_weight);
+        values.push(check);
+        if print_check {
+            let i = check % CODE39_CHARS.len();
+            text.push_str(if check < CODE39_CHARS.len() {
+                &CODE39_CHARS[i..=i]
+            } else {
+                &CODE93_SHIFT_TEXT[i..=i]
+            });
+        }
+    }
+
+    let mut modules = Vec::with_capacity((values.len() + 2) * 9 + 1);
+    let mut push = |pattern: u16| modules.extend((0..9).rev().map(|i| pattern >> i & 1 == 1));
+    push(CODE93_GUARD);
+    for &v in &values {
+        push(CODE93_PATTERNS[v]);
+    }
+    push(CODE93_GUARD);
+    modules.push(true);
+    Ok((modules, text))
+}
+
+/// Column width of the matrix [`encode_native`] builds for `kind`, or `None`
+/// when `kind` is not encoded natively. Module-based symbologies draw each
+/// module `module_width` dots wide; two-width ones are laid out in dots.
+pub(crate) fn native_column_width(kind: Barcode1DKind, options: &LinearOptions) -> Option<u32> {
+    match kind {
+        Barcode1DKind::Code93 => Some(options.module_width.max(1)),
+        Barcode1DKind::Codabar
+        | Barcode1DKind::Msi
+        | Barcode1DKind::Plessey
+        | Barcode1DKind::Code11
+        | Barcode1DKind::Standard2of5
+        | Barcode1DKind::Industrial2of5
+        | Barcode1DKind::Interleaved2of5
+        | Barcode1DKind::Logmars => Some(1),
+        _ => None,
+    }
+}
+
+/// Encodes a natively supported symbology (see [`native_column_width`])
+/// into its matrix and interpretation text.
+pub(crate) fn encode_native(
+    kind: Barcode1DKind,
+    data: &str,
+    options: &LinearOptions,
+) -> ZplResult<(BitMatrix, String)> {
+    let two_width = match kind {
+        Barcode1DKind::Code93 => {
+            let (modules, text) = code93(data, options.check_digit == 'Y')?;
+            return Ok((modules_to_matrix(&modules)?, text));
+        }
+        Barcode1DKind::Codabar => codabar(data),
+        Barcode1DKind::Msi => msi(data, options.check_digit, options.print_check_digit),
+        Barcode1DKind::Plessey => plessey(data, options.print_check_digit),
+        Barcode1DKind::Code11 => code11(data, options.check_digit),
+        Barcode1DKind::Standard2of5 => two_of_five(data, "Standard 2 of 5", options.check_digit),
+        Barcode1DKind::Industrial2of5 => {
+            two_of_five(data, "Industrial 2 of 5", options.check_digit)
+        }
+        Barcode1DKind::Interleaved2of5 => interleaved_two_of_five(data, options.check_digit),
+        Barcode1DKind::Logmars => logmars(data),
+        _ => {
+            return Err(ZplError::BackendError(format!(
+                "Barcode Generation Error: {:?} is not encoded natively",
+                kind
+            )));
+        }
+    };
+    let (elements, text) = two_width?;
+    let matrix = elements_to_matrix(&elements, options.module_width, options.ratio)?;
+    Ok((matrix, text))
+}
+
+// ─── Human-readable placement ───────────────────────────────────────────────
+
+/// Splits the interpretation line into the groups a printer draws.
+///
+/// Retail symbologies print their digits in groups aligned with the bar
+/// halves, with the leading digit of EAN-13 and the number-system and check
+/// digits of UPC-A and UPC-E outside the guards; every other symbology
+/// centres `text` under the whole matrix.
+pub(crate) fn hri_groups(
+    kind: Option<Barcode1DKind>,
+    text: &str,
+    matrix: &BitMatrix,
+) -> Vec<HriGroup> {
+    let (first, _) = bar_span(matrix);
+    let group = |text: &str, start: i32, end: i32| HriGroup {
+        text: text.to_string(),
+        start: first + start,
+        end: first + end,
+    };
+
+    match kind {
+        // 3 guard + 6×7 + 5 centre + 6×7 + 3 guard = 95 modules; the first
+        // digit is implied by the parity pattern and printed outside.
+        Some(Barcode1DKind::Ean13) if text.len() == 13 => vec![
+            group(&text[..1], -8, -1),
+            group(&text[1..7], 3, 45),
+            group(&text[7..], 50, 92),
+        ],
+        // Same 95 modules; the number-system and check digits are printed
+        // outside, next to their extended bars.
+        Some(Barcode1DKind::UpcA) if text.len() == 12 => vec![
+            group(&text[..1], -8, -1),
+            group(&text[1..6], 10, 45),
+            group(&text[6..11], 50, 85),
+            group(&text[11..], 96, 103),
+        ],
+        // 3 guard + 4×7 + 5 centre + 4×7 + 3 guard = 67 modules.
+        Some(Barcode1DKind::Ean8) if text.len() == 8 => {
+            vec![group(&text[..4], 3, 31), group(&text[4..], 36, 64)]
+        }
+        // 3 guard + 6×7 + 6 end guard = 51 modules.
+        Some(Barcode1DKind::UpcE) if text.len() == 8 => vec![
+            group(&text[..1], -8, -1),
+            group(&text[1..7], 3, 45),
+            group(&text[7..], 52, 59),
+        ],
+        _ => {
+            // Add-ons centre their digits over the bars alone, so the text
+            // lines up with the parent symbol's guard bars.
+            let (start, end) = if matches!(kind, Some(Barcode1DKind::UpcEanExtension)) {
+                bar_span(matrix)
+            } else {
+                (0, matrix.getWidth() as i32)
+            };
+            vec![HriGroup {
+                text: text.to_string(),
+                start,
+                end,
+            }]
+        }
+    }
+}
+
+/// Matrix column spans `(start, end)` of the retail guard bars (and the
+/// UPC-A number-system and check characters) that a printer extends down
+/// between the interpretation-line groups; empty for other symbologies.
+pub(crate) fn extended_guards(
+    kind: Option<Barcode1DKind>,
+    text: &str,
+    matrix: &BitMatrix,
+) -> Vec<(i32, i32)> {
+    let spans: &[(i32, i32)] = match kind {
+        Some(Barcode1DKind::Ean13) if text.len() == 13 => &[(0, 3), (45, 50), (92, 95)],
+        Some(Barcode1DKind::UpcA) if text.len() == 12 => &[(0, 10), (45, 50), (85, 95)],
+        Some(Barcode1DKind::Ean8) if text.len() == 8 => &[(0, 3), (31, 36), (64, 67)],
+        Some(Barcode1DKind::UpcE) if text.len() == 8 => &[(0, 3), (45, 51)],
+        _ => &[],
+    };
+    let (first, _) = bar_span(matrix);
+    spans
+        .iter()
+        .map(|&(start, end)| (first + start, first + end))
+        .collect()
+}
@@ -0,0 +1,529 @@
+//! MaxiCode (`^BD`) encoder and symbol geometry shared by both backends.
+//!
+//! `rxing` can read MaxiCode but not write it, so symbols are built here:
+//! the message is compacted into 6-bit codewords (code sets A–E), protected
+//! with Reed-Solomon over GF(64) and laid out on the fixed 33-row grid of
+//! offset hexagons. The resulting [`BitMatrix`] holds one bit per module;
+//! odd rows are shifted half a module to the right when drawn, and the
+//! bullseye finder is drawn separately from [`Geometry`].
+
+use rxing::common::BitMatrix;
+
+use crate::{ZplError, ZplResult};
+
+/// Module columns per row (odd rows use only the first 29).
+pub(crate) const COLUMNS: u32 = 30;
+/// Module rows per symbol.
+pub(crate) const ROWS: u32 = 33;
+
+/// Nominal symbol width in inches; MaxiCode is printed at a fixed size.
+const SYMBOL_WIDTH_IN: f64 = 1.11;
+
+/// Radii of the bullseye circles in module widths, outermost first. Dark
+/// and light bands alternate starting with the dark outer ring, leaving a
+/// light centre.
+const BULLSEYE_RADII: [f64; 6] = [4.571, 3.779, 2.988, 2.196, 1.394, 0.602];
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+// ─── Geometry ───────────────────────────────────────────────────────────────
+
+/// Physical layout of a MaxiCode symbol at a given resolution, in dots
+/// relative to the symbol's top-left corner.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Geometry {
+    /// Horizontal distance between module centres.
+    pub pitch: f64,
+    /// Vertical distance between row centres.
+    pub row_pitch: f64,
+    /// Overall symbol width.
+    pub width: f64,
+    /// Overall symbol height.
+    pub height: f64,
+}
+
+impl Geometry {
+    /// Geometry of the nominal 1.11 in symbol at `dpi` dots per inch.
+    pub(crate) fn new(dpi: f64) -> Self {
+        let pitch = SYMBOL_WIDTH_IN * dpi / COLUMNS as f64;
+        let row_pitch = pitch * 3f64.sqrt() / 2.0;
+        Self {
+            pitch,
+            row_pitch,
+            width: pitch * COLUMNS as f64,
+            height: row_pitch * (ROWS - 1) as f64 + pitch * 2.0 / 3f64.sqrt(),
+        }
+    }
+
+    /// Centre of the module at (`col`, `row`).
+    fn module_center(&self, col: u32, row: u32) -> (f64, f64) {
+        let offset = if row % 2 == 1 { 0.5 } else { 0.0 };
+        (
+            (col as f64 + 0.5 + offset) * self.pitch,
+            row as f64 * self.row_pitch + self.pitch / 3f64.sqrt(),
+        )
+    }
+
+    /// Corners of the pointy-top hexagon for the module at (`col`, `row`),
+    /// clockwise from the top.
+    pub(crate) fn hexagon(&self, col: u32, row: u32) -> [(f64, f64); 6] {
+        let (cx, cy) = self.module_center(col, row);
+        let half_w = self.pitch / 2.0;
+        let r = self.pitch / 3f64.sqrt();
+        [
+            (cx, cy - r),
+            (cx + half_w, cy - r / 2.0),
+            (cx + half_w, cy + r / 2.0),
+            (cx, cy + r),
+            (cx - half_w, cy + r / 2.0),
+            (cx - half_w, cy - r / 2.0),
+        ]
+    }
+
+    /// Centre of the bullseye finder.
+    pub(crate) fn bullseye_center(&self) -> (f64, f64) {
+        self.module_center(14, 16)
+    }
+
+    /// Bullseye circle radii, outermost first; even indices start a dark band.
+    pub(crate) fn bullseye_radii(&self) -> [f64; 6] {
+        BULLSEYE_RADII.map(|r| r * self.pitch)
+    }
+}
+
+// ─── Module placement ───────────────────────────────────────────────────────
+
+/// Bit number (codeword * 6 + bit, MSB first) carried by each module, from
+/// ISO/IEC 16023 Figure 5. `-1` marks dark orientation modules, `-2` light
+/// orientation modules and `-3` modules that are not part of the grid (the
+/// bullseye area and the missing last column of odd rows).
+#[rustfmt::skip]
+const BIT_NUMBERS: [[i16; 30]; 33] = [
+    [121, 120, 127, 126, 133, 132, 139, 138, 145, 144, 151, 150, 157, 156, 163, 162, 169, 168, 175, 174, 181, 180, 187, 186, 193, 192, 199, 198, -2, -2],
+    [123, 122, 129, 128, 135, 134, 141, 140, 147, 146, 153, 152, 159, 158, 165, 164, 171, 170, 177, 176, 183, 182, 189, 188, 195, 194, 201, 200, 816, -3],
+    [125, 124, 131, 130, 137, 136, 143, 142, 149, 148, 155, 154, 161, 160, 167, 166, 173, 172, 179, 178, 185, 184, 191, 190, 197, 196, 203, 202, 818, 817],
+    [283, 282, 277, 276, 271, 270, 265, 264, 259, 258, 253, 252, 247, 246, 241, 240, 235, 234, 229, 228, 223, 222, 217, 216, 211, 210, 205, 204, 819, -3],
+    [285, 284, 279, 278, 273, 272, 267, 266, 261, 260, 255, 254, 249, 248, 243, 242, 237, 236, 231, 230, 225, 224, 219, 218, 213, 212, 207, 206, 821, 820],
+    [287, 286, 281, 280, 275, 274, 269, 268, 263, 262, 257, 256, 251, 250, 245, 244, 239, 238, 233, 232, 227, 226, 221, 220, 215, 214, 209, 208, 822, -3],
+    [289, 288, 295, 294, 301, 300, 307, 306, 313, 312, 319, 318, 325, 324, 331, 330, 337, 336, 343, 342, 349, 348, 355, 354, 361, 360, 367, 366, 824, 823],
+    [291, 290, 297, 296, 303, 302, 309, 308, 315, 314, 321, 320, 327, 326, 333, 332, 339, 338, 345, 344, 351, 350, 357, 356, 363, 362, 369, 368, 825, -3],
+    [293, 292, 299, 298, 305, 304, 311, 310, 317, 316, 323, 322, 329, 328, 335, 334, 341, 340, 347, 346, 353, 352, 359, 358, 365, 364, 371, 370, 827, 826],
+    [409, 408, 403, 402, 397, 396, 391, 390, 79, 78, -2, -2, 13, 12, 37, 36, 2, -1, 44, 43, 109, 108, 385, 384, 379, 378, 373, 372, 828, -3],
+    [411, 410, 405, 404, 399, 398, 393, 392, 81, 80, 40, -2, 15, 14, 39, 38, 3, -1, -1, 45, 111, 110, 387, 386, 381, 380, 375, 374, 830, 829],
+    [413, 412, 407, 406, 401, 400, 395, 394, 83, 82, 41, -3, -3, -3, -3, -3, 5, 4, 47, 46, 113, 112, 389, 388, 383, 382, 377, 376, 831, -3],
+    [415, 414, 421, 420, 427, 426, 103, 102, 55, 54, 16, -3, -3, -3, -3, -3, -3, -3, 20, 19, 85, 84, 433, 432, 439, 438, 445, 444, 833, 832],
+    [417, 416, 423, 422, 429, 428, 105, 104, 57, 56, -3, -3, -3, -3, -3, -3, -3, -3, 22, 21, 87, 86, 435, 434, 441, 440, 447, 446, 834, -3],
+    [419, 418, 425, 424, 431, 430, 107, 106, 59, 58, -3, -3, -3, -3, -3, -3, -3, -3, -3, 23, 89, 88, 437, 436, 443, 442, 449, 448, 836, 835],
+    [481, 480, 475, 474, 469, 468, 48, -2, 30, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, 0, 53, 52, 463, 462, 457, 456, 451, 450, 837, -3],
+    [483, 482, 477, 476, 471, 470, 49, -1, -2, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, -2, -1, 465, 464, 459, 458, 453, 452, 839, 838],
+    [485, 484, 479, 478, 473, 472, 51, 50, 31, -3, -3, -3, -3, -3, -3, -3, -3, -3, -3, 1, -2, 42, 467, 466, 461, 460, 455, 454, 840, -3],
+    [487, 486, 493, 492, 499, 498, 97, 96, 61, 60, -3, -3, -3, -3, -3, -3, -3, -3, -3, 26, 91, 90, 505, 504, 511, 510, 517, 516, 842, 841],
+    [489, 488, 495, 494, 501, 500, 99, 98, 63, 62, -3, -3, -3, -3, -3, -3, -3, -3, 28, 27, 93, 92, 507, 506, 513, 512, 519, 518, 843, -3],
+    [491, 490, 497, 496, 503, 502, 101, 100, 65, 64, 17, -3, -3, -3, -3, -3, -3, -3, 18, 29, 95, 94, 509, 508, 515, 514, 521, 520, 845, 844],
+    [559, 558, 553, 552, 547, 546, 541, 540, 73, 72, 32, -3, -3, -3, -3, -3, -3, 10, 67, 66, 115, 114, 535, 534, 529, 528, 523, 522, 846, -3],
+    [561, 560, 555, 554, 549, 548, 543, 542, 75, 74, -2, -1, 7, 6, 35, 34, 11, -2, 69, 68, 117, 116, 537, 536, 531, 530, 525, 524, 848, 847],
+    [563, 562, 557, 556, 551, 550, 545, 544, 77, 76, -2, 33, 9, 8, 25, 24, -1, -2, 71, 70, 119, 118, 539, 538, 533, 532, 527, 526, 849, -3],
+    [565, 564, 571, 570, 577, 576, 583, 582, 589, 588, 595, 594, 601, 600, 607, 606, 613, 612, 619, 618, 625, 624, 631, 630, 637, 636, 643, 642, 851, 850],
+    [567, 566, 573, 572, 579, 578, 585, 584, 591, 590, 597, 596, 603, 602, 609, 608, 615, 614, 621, 620, 627, 626, 633, 632, 639, 638, 645, 644, 852, -3],
+    [569, 568, 575, 574, 581, 580, 587, 586, 593, 592, 599, 598, 605, 604, 611, 610, 617, 616, 623, 622, 629, 628, 635, 634, 641, 640, 647, 646, 854, 853],
+    [727, 726, 721, 720, 715, 714, 709, 708, 703, 702, 697, 696, 691, 690, 685, 684, 679, 678, 673, 672, 667, 666, 661, 660, 655, 654, 649, 648, 855, -3],
+    [729, 728, 723, 722, 717, 716, 711, 710, 705, 704, 699, 698, 693, 692, 687, 686, 681, 680, 675, 674, 669, 668, 663, 662, 657, 656, 651, 650, 857, 856],
+    [731, 730, 725, 724, 719, 718, 713, 712, 707, 706, 701, 700, 695, 694, 689, 688, 683, 682, 677, 676, 671, 670, 665, 664, 659, 658, 653, 652, 858, -3],
+    [733, 732, 739, 738, 745, 744, 751, 750, 757, 756, 763, 762, 769, 768, 775, 774, 781, 780, 787, 786, 793, 792, 799, 798, 805, 804, 811, 810, 860, 859],
+    [735, 734, 741, 740, 747, 746, 753, 752, 759, 758, 765, 764, 771, 770, 777, 776, 783, 782, 789, 788, 795, 794, 801, 800, 807, 806, 813, 812, 861, -3],
+    [737, 736, 743, 742, 749, 748, 755, 754, 761, 760, 767, 766, 773, 772, 779, 778, 785, 784, 791, 790, 797, 796, 803, 802, 809, 808, 815, 814, 863, 862],
+];
+
+// ─── Code sets ──────────────────────────────────────────────────────────────
+
+/// Placeholder for code set positions that are not plain characters.
+const NONE: char = '\u{FFFF}';
+
+/// Code sets A–E in codeword order (ISO/IEC 16023 Table 3). Positions 27–31
+/// (ECI, FS/GS/RS, NS) and the shift/latch codes are special in every set;
+/// FS, GS and RS are listed where each set encodes them.
+const CODE_SETS: [&str; 5] = [
+    "\rABCDEFGHIJKLMNOPQRSTUVWXYZ\u{FFFF}\x1C\x1D\x1E\u{FFFF} \u{FFFF}\"#$%&'()*+,-./0123456789:\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}",
+    "`abcdefghijklmnopqrstuvwxyz\u{FFFF}\x1C\x1D\x1E\u{FFFF}{\u{FFFF}}~\x7F;<=>?[\\]^_ ,./:@!|\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}",
+    "ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚ\u{FFFF}\x1C\x1D\x1E\u{FFFF}ÛÜÝÞßª¬±²³µ¹º¼½¾\u{80}\u{81}\u{82}\u{83}\u{84}\u{85}\u{86}\u{87}\u{88}\u{89}\u{FFFF} \u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}",
+    "àáâãäåæçèéêëìíîïðñòóôõö÷øùú\u{FFFF}\x1C\x1D\x1E\u{FFFF}ûüýþÿ¡¨«¯°´·¸»¿\u{8A}\u{8B}\u{8C}\u{8D}\u{8E}\u{8F}\u{90}\u{91}\u{92}\u{93}\u{94}\u{FFFF} \u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}",
+    "\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\n\x0B\x0C\r\x0E\x0F\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1A\u{FFFF}\u{FFFF}\u{FFFF}\x1B\u{FFFF}\x1C\x1D\x1E\x1F\u{9F}\u{A0}\u{A2}\u{A3}\u{A4}\u{A5}\u{A6}\u{A7}\u{A9}\u{AD}\u{AE}\u{B6}\u{95}\u{96}\u{97}\u{98}\u{99}\u{9A}\u{9B}\u{9C}\u{9D}\u{9E}\u{FFFF} \u{FFFF}\u{FFFF}\u{FFFF}\u{FFFF}",
+];
+
+/// Index of code set A; set B is 1.
+const SET_A: usize = 0;
+
+/// Numeric shift: the next five codewords hold nine digits (A and B).
+const NS: u8 = 31;
+/// Padding (A and B); also introduces a structured append header.
+const PAD: u8 = 33;
+/// In set B: shift two or three characters to set A.
+const TWO_SHIFT_A: u8 = 56;
+const THREE_SHIFT_A: u8 = 57;
+/// In sets A and B: shift one character to the other of A/B.
+const SHIFT_AB: u8 = 59;
+/// In sets A and B: latch to the other of A/B.
+const LATCH_AB: u8 = 63;
+/// In sets A and B: shift one character to set C, D or E (`SHIFT_C + n`).
+const SHIFT_C: u8 = 60;
+
+/// Codeword for `c` in code set `set`.
+fn codeword(set: usize, c: char) -> Option<u8> {
+    if c == NONE {
+        return None;
+    }
+    CODE_SETS[set].chars().position(|x| x == c).map(|p| p as u8)
+}
+
+/// Compacts `text` into codewords, starting latched to set A.
+fn encode_message(text: &str) -> ZplResult<Vec<u8>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::with_capacity(chars.len());
+    let mut latched = SET_A;
+    let mut i = 0;
+    while i < chars.len() {
+        let digits = chars[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if digits >= 9 {
+            let value = chars[i..i + 9]
+                .iter()
+                .fold(0u32, |acc, c| acc * 10 + c.to_digit(10).unwrap_or(0));
+            out.push(NS);
+            out.extend([24, 18, 12, 6, 0].map(|shift| (value >> shift & 0x3F) as u8));
+            i += 9;
+            continue;
+        }
+
+        let c = chars[i];
+        if let Some(cw) = codeword(latched, c) {
+            out.push(cw);
+            i += 1;
+            continue;
+        }
+
+        let other = 1 - latched;
+        if codeword(other, c).is_some() {
+            let run = chars[i..]
+                .iter()
+                .take_while(|&&c| codeword(latched, c).is_none() && codeword(other, c).is_some())
+                .count();
+            if other == SET_A && (2..=3).contains(&run) {
+                out.push(if run == 2 { TWO_SHIFT_A } else { THREE_SHIFT_A });
+                out.extend(chars[i..i + run].iter().filter_map(|&c| codeword(SET_A, c)));
+                i += run;
+                continue;
+            }
+            if run > 1 {
+                out.push(LATCH_AB);
+                latched = other;
+            } else {
+                out.push(SHIFT_AB);
+            }
+            out.extend(codeword(other, c));
+            i += 1;
+            continue;
+        }
+
+        match (2..5).find_map(|set| codeword(set, c).map(|cw| (set, cw))) {
+            Some((set, cw)) => {
+                out.push(SHIFT_C + (set - 2) as u8);
+                out.push(cw);
+                i += 1;
+            }
+            None => {
+                return Err(generation_error(format!(
+                    "MaxiCode cannot encode character {:?}",
+                    c
+                )));
+            }
+        }
+    }
+    Ok(out)
+}
+
+// ─── Primary message (modes 2 and 3) ────────────────────────────────────────
+
+/// Message header of ANSI MH10.8.3 transportation data (`[)>` RS `01` GS).
+const UPS_HEADER: &str = "[)>\x1E01\x1D";
+
+/// Bit positions (1-based, MSB first) of the structured carrier fields.
+const COUNTRY_BITS: [u8; 10] = [53, 54, 43, 44, 45, 46, 47, 48, 37, 38];
+const SERVICE_CLASS_BITS: [u8; 10] = [55, 56, 57, 58, 59, 60, 49, 50, 51, 52];
+const POSTCODE_2_LENGTH_BITS: [u8; 6] = [39, 40, 41, 42, 31, 32];
+const POSTCODE_2_BITS: [u8; 30] = [
+    33, 34, 35, 36, 25, 26, 27, 28, 29, 30, 19, 20, 21, 22, 23, 24, 13, 14, 15, 16, 17, 18, 7, 8,
+    9, 10, 11, 12, 1, 2,
+];
+const POSTCODE_3_BITS: [[u8; 6]; 6] = [
+    [39, 40, 41, 42, 31, 32],
+    [33, 34, 35, 36, 25, 26],
+    [27, 28, 29, 30, 19, 20],
+    [21, 22, 23, 24, 13, 14],
+    [15, 16, 17, 18, 7, 8],
+    [9, 10, 11, 12, 1, 2],
+];
+
+/// The structured carrier message of modes 2 and 3.
+struct Primary<'a> {
+    postal_code: &'a str,
+    country: &'a str,
+    service_class: &'a str,
+}
+
+/// Splits mode 2/3 data into the primary message and the secondary text.
+///
+/// Data starting with the `[)>` RS `01` GS `yy` header carries postal code,
+/// country and class of service as its first three GS-separated fields,
+/// which move to the primary message; the header stays in the secondary.
+/// Otherwise the data starts with Zebra's high-priority message: 3-digit
+/// class of service, 3-digit country code and the postal code (up to 9
+/// digits in mode 2, 6 characters in mode 3).
+fn split_primary(mode: u32, data: &str) -> ZplResult<(Primary<'_>, String)> {
+    if data.starts_with(UPS_HEADER)
+        && data.len() >= 9
+        && data.as_bytes()[7..9].iter().all(u8::is_ascii_digit)
+    {
+        let (header, rest) = data.split_at(9);
+        let mut fields = rest.splitn(4, '\x1D');
+        if let (Some(postal_code), Some(country), Some(service_class)) =
+            (fields.next(), fields.next(), fields.next())
+        {
+            let secondary = format!("{}{}", header, fields.next().unwrap_or(""));
+            return Ok((
+                Primary {
+                    postal_code,
+                    country,
+                    service_class,
+                },
+                secondary,
+            ));
+        }
+    }
+
+    let postal_len = if mode == 2 {
+        data.get(6..)
+            .map(|s| s.bytes().take(9).take_while(u8::is_ascii_digit).count())
+            .unwrap_or(0)
+    } else {
+        6
+    };
+    match (data.get(..3), data.get(3..6), data.get(6..6 + postal_len)) {
+        (Some(service_class), Some(country), Some(postal_code)) => Ok((
+            Primary {
+                postal_code,
+                country,
+                service_class,
+            },
+            data[6 + postal_len..].to_string(),
+        )),
+        _ => Err(generation_error(format!(
+            "MaxiCode mode {} needs class of service, country and postal code, got {:?}",
+            mode, data
+        ))),
+    }
+}
+
+/// Writes `value` MSB first into the 1-based message bit `positions`.
+fn set_bits(codewords: &mut [u8], positions: &[u8], value: u32) {
+    for (i, &position) in positions.iter().enumerate() {
+        if value >> (positions.len() - 1 - i) & 1 == 1 {
+            let bit = (position - 1) as usize;
+            codewords[bit / 6] |= 1 << (5 - bit % 6);
+        }
+    }
+}
+
+/// Parses a 3-digit primary message field.
+fn three_digits(field: &str, name: &str) -> ZplResult<u32> {
+    match field.trim().parse::<u32>() {
+        Ok(value) if value <= 999 && field.trim().len() <= 3 => Ok(value),
+        _ => Err(generation_error(format!(
+            "MaxiCode {} must be 3 digits, got {:?}",
+            name, field
+        ))),
+    }
+}
+
+/// Encodes the primary message into the first ten codewords.
+fn encode_primary(mode: u32, primary: &Primary, codewords: &mut [u8]) -> ZplResult<()> {
+    codewords[0] = mode as u8;
+    let country = three_digits(primary.country, "country code")?;
+    let service_class = three_digits(primary.service_class, "class of service")?;
+    set_bits(codewords, &COUNTRY_BITS, country);
+    set_bits(codewords, &SERVICE_CLASS_BITS, service_class);
+
+    let postal_code = primary.postal_code.trim();
+    if mode == 2 {
+        if postal_code.is_empty()
+            || postal_code.len() > 9
+            || !postal_code.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(generation_error(format!(
+                "MaxiCode mode 2 needs a numeric postal code of up to 9 digits, got {:?}",
+                postal_code
+            )));
+        }
+        set_bits(
+            codewords,
+            &POSTCODE_2_BITS,
+            postal_code.parse().unwrap_or(0),
+        );
+        set_bits(codewords, &POSTCODE_2_LENGTH_BITS, postal_code.len() as u32);
+    } else {
+        let padded = postal_code
+            .chars()
+            .map(|c| c.to_ascii_uppercase())
+            .chain(std::iter::repeat(' '))
+            .take(6);
+        for (positions, c) in POSTCODE_3_BITS.iter().zip(padded) {
+            let value = codeword(SET_A, c).ok_or_else(|| {
+                generation_error(format!(
+                    "MaxiCode mode 3 postal code cannot contain {:?}",
+                    c
+                ))
+            })?;
+            set_bits(codewords, positions, value as u32);
+        }
+    }
+    Ok(())
+}
+
+// ─── Symbol assembly ────────────────────────────────────────────────────────
+
+/// Primitive polynomial of GF(64), x⁶ + x + 1.
+const GF64_PRIMITIVE: u8 = 0x43;
+
+/// Product of `a` and `b` in GF(64).
+fn gf64_multiply(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x40 != 0 {
+            a ^= GF64_PRIMITIVE;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Reed-Solomon check codewords for `data` over GF(64), with generator
+/// roots α, α², … α^`ec_len` (α = 2).
+fn reed_solomon(data: &[u8], ec_len: usize) -> Vec<u8> {
+    let mut generator = vec![1u8];
+    let mut root = 1;
+    for _ in 0..ec_len {
+        root = gf64_multiply(root, 2);
+        let mut next = vec![0; generator.len() + 1];
+        for (i, &c) in generator.iter().enumerate() {
+            next[i] ^= c;
+            next[i + 1] ^= gf64_multiply(c, root);
+        }
+        generator = next;
+    }
+
+    let mut remainder = vec![0u8; ec_len];
+    for &codeword in data {
+        let factor = codeword ^ remainder[0];
+        for i in 0..ec_len {
+            let carry = remainder.get(i + 1).copied().unwrap_or(0);
+            remainder[i] = carry ^ gf64_multiply(factor, generator[i + 1]);
+        }
+    }
+    remainder
+}
+
+/// Encodes `data` as a MaxiCode symbol in `mode` (2–6), as symbol
+/// `symbol_number` of `symbol_count` for structured append.
+pub(crate) fn encode(
+    mode: u32,
+    symbol_number: u32,
+    symbol_count: u32,
+    data: &str,
+) -> ZplResult<BitMatrix> {
+    let mut codewords = [0u8; 144];
+    let secondary = match mode {
+        2 | 3 => {
+            let (primary, secondary) = split_primary(mode, data)?;
+            encode_primary(mode, &primary, &mut codewords)?;
+            secondary
+        }
+        4..=6 => {
+            codewords[0] = mode as u8;
+            data.to_string()
+        }
+        _ => {
+            return Err(generation_error(format!(
+                "MaxiCode mode must be 2-6, got {}",
+                mode
+            )));
+        }
+    };
+
+    let mut message = Vec::new();
+    if (2..=8).contains(&symbol_count) && (1..=symbol_count).contains(&symbol_number) {
+        message.push(PAD);
+        message.push(((symbol_number - 1) << 3 | (symbol_count - 1)) as u8);
+    }
+    message.extend(encode_message(&secondary)?);
+
+    // Enhanced error correction (mode 5) trades 16 data codewords for checks.
+    let (secondary_data, secondary_ec) = if mode == 5 { (68, 56) } else { (84, 40) };
+    let capacity = if mode <= 3 {
+        secondary_data
+    } else {
+        secondary_data + 9
+    };
+    if message.len() > capacity {
+        return Err(generation_error(format!(
+            "data too long for MaxiCode mode {} ({} codewords, {} available)",
+            mode,
+            message.len(),
+            capacity
+        )));
+    }
+    message.resize(capacity, PAD);
+
+    let secondary_words = if mode <= 3 {
+        &message[..]
+    } else {
+        codewords[1..10].copy_from_slice(&message[..9]);
+        &message[9..]
+    };
+    codewords[20..20 + secondary_data].copy_from_slice(secondary_words);
+
+    let primary_ec = reed_solomon(&codewords[..10], 10);
+    codewords[10..20].copy_from_slice(&primary_ec);
+
+    // The secondary message is split into two interleaved blocks.
+    for parity in 0..2 {
+        let block: Vec<u8> = codewords[20..20 + secondary_data]
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .copied()
+            .collect();
+        let ec = reed_solomon(&block, secondary_ec / 2);
+        for (i, &check) in ec.iter().enumerate() {
+            codewords[20 + secondary_data + 2 * i + parity] = check;
+        }
+    }
+
+    let mut matrix = BitMatrix::new(COLUMNS, ROWS).map_err(generation_error)?;
+    for (row, numbers) in BIT_NUMBERS.iter().enumerate() {
+        for (col, &number) in numbers.iter().enumerate() {
+            let dark = match number {
+                -1 => true,
+                n if n >= 0 => codewords[n as usize / 6] >> (5 - n % 6) & 1 == 1,
+                _ => false,
+            };
+            if dark {
+                matrix.set(col as u32, row as u32);
+            }
+        }
+    }
+    Ok(matrix)
+}
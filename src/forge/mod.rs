@@ -4,22 +4,423 @@
 //! It translates the intermediate representation (`ZplInstruction`) into
 //! specific output formats like images or documents.
 
+#[cfg_attr(not(any(feature = "png", feature = "pdf")), allow(dead_code))]
+pub(crate) mod check_digit;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod code128;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod databar;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod datamatrix;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod linear;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod maxicode;
 #[cfg(feature = "pdf")]
 pub mod pdf_native;
 #[cfg(feature = "png")]
 pub mod png;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod postal;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod qr;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod stacked;
+#[cfg(feature = "pdf")]
+pub(crate) mod subset;
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) mod tlc39;
 
-/// Maps a generic 1-D symbology to its `rxing` barcode format.
+/// Maps a generic 1-D symbology to its `rxing` barcode format, or `None`
+/// when it is encoded natively by [`linear`].
 #[cfg(any(feature = "png", feature = "pdf"))]
-pub(crate) fn barcode_1d_format(kind: crate::engine::Barcode1DKind) -> rxing::BarcodeFormat {
+pub(crate) fn barcode_1d_format(
+    kind: crate::engine::Barcode1DKind,
+) -> Option<rxing::BarcodeFormat> {
     match kind {
-        crate::engine::Barcode1DKind::Ean13 => rxing::BarcodeFormat::EAN_13,
-        crate::engine::Barcode1DKind::UpcA => rxing::BarcodeFormat::UPC_A,
-        crate::engine::Barcode1DKind::Interleaved2of5 => rxing::BarcodeFormat::ITF,
-        crate::engine::Barcode1DKind::Code93 => rxing::BarcodeFormat::CODE_93,
+        crate::engine::Barcode1DKind::Ean13 => Some(rxing::BarcodeFormat::EAN_13),
+        crate::engine::Barcode1DKind::UpcA => Some(rxing::BarcodeFormat::UPC_A),
+        crate::engine::Barcode1DKind::Ean8 => Some(rxing::BarcodeFormat::EAN_8),
+        crate::engine::Barcode1DKind::UpcE => Some(rxing::BarcodeFormat::UPC_E),
+        _ => None,
     }
 }
 
+/// Cache key prefix for symbologies encoded natively by [`linear`].
+#[cfg(any(feature = "png", feature = "pdf"))]
+fn native_format_key(kind: crate::engine::Barcode1DKind) -> &'static str {
+    match kind {
+        crate::engine::Barcode1DKind::UpcEanExtension => "ext",
+        crate::engine::Barcode1DKind::Codabar => "cbar",
+        crate::engine::Barcode1DKind::Msi => "msi",
+        crate::engine::Barcode1DKind::Plessey => "pls",
+        crate::engine::Barcode1DKind::Code11 => "c11",
+        crate::engine::Barcode1DKind::Standard2of5 => "s25",
+        crate::engine::Barcode1DKind::Industrial2of5 => "i25",
+        crate::engine::Barcode1DKind::Interleaved2of5 => "itf25",
+        crate::engine::Barcode1DKind::Code93 => "c93n",
+        crate::engine::Barcode1DKind::Logmars => "lgm",
+        _ => "other",
+    }
+}
+
+/// Encodes a generic 1-D symbol through `rxing` or the native encoders.
+///
+/// The matrix is cached; the returned text is what the interpretation line
+/// prints (e.g. with the computed check digit).
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_barcode_1d(
+    kind: crate::engine::Barcode1DKind,
+    data: &str,
+    options: &linear::LinearOptions,
+) -> crate::ZplResult<linear::LinearSymbol> {
+    let hints_key = format!(
+        "{}:{}:{}:{}",
+        options.module_width, options.ratio, options.check_digit, options.print_check_digit
+    );
+    if let Some(column_width) = linear::native_column_width(kind, options) {
+        let (matrix, text) = barcode_cache::encode_native_cached_with_text(
+            native_format_key(kind),
+            data,
+            &hints_key,
+            |data| linear::encode_native(kind, data, options),
+        )?;
+        return Ok(linear::LinearSymbol {
+            matrix,
+            column_width,
+            text,
+        });
+    }
+
+    let text = linear::normalize_data(kind, data)?;
+    let matrix = match barcode_1d_format(kind) {
+        Some(format) => barcode_cache::encode_cached(format, &text, "", None)?,
+        None => barcode_cache::encode_native_cached(
+            native_format_key(kind),
+            &text,
+            "",
+            linear::encode_extension,
+        )?,
+    };
+    Ok(linear::LinearSymbol {
+        matrix,
+        column_width: options.module_width.max(1),
+        text,
+    })
+}
+
+/// Encodes a Code 39 symbol at the `^BY` wide-to-narrow `ratio`, with a
+/// Mod 43 check character when `check_digit` is `'Y'` (see
+/// [`linear::code39`]).
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_code39(
+    data: &str,
+    check_digit: char,
+    module_width: u32,
+    ratio: f64,
+) -> crate::ZplResult<linear::LinearSymbol> {
+    let (matrix, text) = barcode_cache::encode_native_cached_with_text(
+        "c39n",
+        data,
+        &format!(
+            "{}:{}:{}",
+            module_width,
+            linear::printer_ratio(ratio),
+            check_digit == 'Y'
+        ),
+        |data| {
+            let (elements, text) = linear::code39(data, check_digit == 'Y')?;
+            Ok((
+                linear::elements_to_matrix(&elements, module_width, ratio)?,
+                text,
+            ))
+        },
+    )?;
+    Ok(linear::LinearSymbol {
+        matrix,
+        column_width: 1,
+        text,
+    })
+}
+
+/// The encoded components of a TLC39 (`^BT`) symbol and their layout.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) struct Tlc39Symbol {
+    /// The Code 39 symbol of the ECI number.
+    pub linear: linear::LinearSymbol,
+    /// The MicroPDF417 of the serial data, absent when the field only holds
+    /// the ECI number.
+    pub micro: Option<std::sync::Arc<rxing::common::BitMatrix>>,
+    /// Placement of the two components.
+    pub layout: tlc39::Tlc39Layout,
+}
+
+/// Encodes a TLC39 symbol: the ECI number as Code 39 with `module_width`,
+/// `ratio` and `height`, and the serial data as the smallest four-column
+/// MicroPDF417 it fits, drawn `micro_module_width` wide and `row_height`
+/// tall per row.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_tlc39(
+    data: &str,
+    module_width: u32,
+    ratio: f64,
+    height: u32,
+    micro_module_width: u32,
+    row_height: u32,
+) -> crate::ZplResult<Tlc39Symbol> {
+    let (eci, serial) = tlc39::split(data)?;
+    let linear = encode_code39(eci, 'N', module_width, ratio)?;
+
+    let micro = match serial {
+        Some(serial) => {
+            let mut result = None;
+            for mode in tlc39::MICRO_MODES {
+                result = Some(barcode_cache::encode_native_cached(
+                    "updf",
+                    serial,
+                    &format!("m:{}", mode),
+                    |data| stacked::encode_micro_pdf417(mode, data),
+                ));
+                if let Some(Ok(_)) = result {
+                    break;
+                }
+            }
+            result.transpose()?
+        }
+        None => None,
+    };
+
+    let row_height = row_height.max(1);
+    let micro_module_width = micro_module_width.max(1);
+    let layout = tlc39::Tlc39Layout {
+        linear_width: linear.matrix.getWidth() * linear.column_width,
+        linear_height: height,
+        micro_size: micro.as_ref().map(|m| {
+            (
+                m.getWidth() * micro_module_width,
+                m.getHeight() * row_height,
+            )
+        }),
+        micro_indent: linear::bar_span(&linear.matrix).0 as u32 * linear.column_width,
+        gap: row_height,
+    };
+    Ok(Tlc39Symbol {
+        linear,
+        micro,
+        layout,
+    })
+}
+
+/// Encodes a Code 128 symbol for `^BC` mode `mode` (see
+/// [`code128::encode`]). The matrix is cached; the returned text is the
+/// interpretation line, with AI parentheses in modes `U` and `D`.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_code128(
+    mode: char,
+    check_digit: char,
+    module_width: u32,
+    data: &str,
+) -> crate::ZplResult<linear::LinearSymbol> {
+    let ucc_check_digit = check_digit == 'Y';
+    let (matrix, text) = barcode_cache::encode_native_cached_with_text(
+        "c128n",
+        data,
+        &format!("m:{}:e:{}", mode, ucc_check_digit),
+        |data| {
+            let (modules, text) = code128::encode(mode, ucc_check_digit, data)?;
+            Ok((linear::modules_to_matrix(&modules)?, text))
+        },
+    )?;
+    Ok(linear::LinearSymbol {
+        matrix,
+        column_width: module_width.max(1),
+        text,
+    })
+}
+
+/// Encodes an Aztec symbol with the `^B0` options that `rxing` supports.
+///
+/// `extended_channel` `'Y'` encodes the data as UTF-8 behind an ECI header;
+/// `error_control` is the raw `^B0` value (see
+/// [`ZplForgeBackend::draw_aztec`](crate::engine::ZplForgeBackend::draw_aztec)).
+/// Aztec runes (300) are not supported by the encoder and are rejected
+/// rather than printed as a plain symbol; `^B0` never passes them.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_aztec(
+    data: &str,
+    extended_channel: char,
+    error_control: u32,
+) -> crate::ZplResult<std::sync::Arc<rxing::common::BitMatrix>> {
+    use rxing::{EncodeHintType, EncodeHintValue, EncodeHints};
+
+    let (ecc_percent, layers) = match error_control {
+        1..=99 => (error_control, 0),
+        101..=104 => (23, -(error_control as i32 - 100)),
+        201..=232 => (23, error_control as i32 - 200),
+        300 => {
+            return Err(crate::ZplError::BackendError(
+                "Barcode Generation Error: Aztec runes are not supported".into(),
+            ));
+        }
+        _ => (23, 0),
+    };
+
+    let mut hints = std::collections::HashMap::new();
+    hints.insert(
+        EncodeHintType::ERROR_CORRECTION,
+        EncodeHintValue::ErrorCorrection(ecc_percent.to_string()),
+    );
+    if layers != 0 {
+        hints.insert(
+            EncodeHintType::AZTEC_LAYERS,
+            EncodeHintValue::AztecLayers(layers),
+        );
+    }
+    if extended_channel == 'Y' {
+        hints.insert(
+            EncodeHintType::CHARACTER_SET,
+            EncodeHintValue::CharacterSet("UTF-8".to_owned()),
+        );
+    }
+    let hints: EncodeHints = hints.into();
+
+    barcode_cache::encode_cached(
+        rxing::BarcodeFormat::AZTEC,
+        data,
+        &format!("ec:{}:l:{}:eci:{}", ecc_percent, layers, extended_channel),
+        Some(&hints),
+    )
+}
+
+/// Encodes a QR Code symbol with its `^FD` prefix already split off.
+///
+/// Automatic-mode Model 2 symbols are encoded by `rxing`; manual segments,
+/// structured append and Model 1 go through [`qr::encode`].
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_qr(
+    options: &qr::QrOptions,
+    data: &str,
+    segments: &[crate::engine::QrSegment],
+) -> crate::ZplResult<std::sync::Arc<rxing::common::BitMatrix>> {
+    use rxing::{EncodeHintType, EncodeHintValue, EncodeHints};
+
+    let level = format!("{:?}", qr::ec_level(options.error_correction));
+    if options.model != 1
+        && segments.is_empty()
+        && options.structured_append.is_none()
+        && !options.gs1
+    {
+        let mut hints = std::collections::HashMap::new();
+        hints.insert(
+            EncodeHintType::ERROR_CORRECTION,
+            EncodeHintValue::ErrorCorrection(level.clone()),
+        );
+        hints.insert(
+            EncodeHintType::MARGIN,
+            EncodeHintValue::Margin("0".to_owned()),
+        );
+        let hints: EncodeHints = hints.into();
+        return barcode_cache::encode_cached(
+            rxing::BarcodeFormat::QR_CODE,
+            data,
+            &format!("ec:{}", level),
+            Some(&hints),
+        );
+    }
+
+    let key: String = segments
+        .iter()
+        .map(|s| format!("{}{}:{}", s.mode, s.data.len(), s.data))
+        .collect();
+    barcode_cache::encode_native_cached(
+        "qrm",
+        &format!("{}|{}", data, key),
+        &format!(
+            "m:{}:ec:{}:sa:{:?}:gs1:{}",
+            options.model, level, options.structured_append, options.gs1
+        ),
+        |_| qr::encode(options, data, segments),
+    )
+}
+
+/// Encodes a Data Matrix symbol (see [`datamatrix::encode`]) through the
+/// barcode cache. Symbols are always ECC 200; the legacy `^BX` quality
+/// levels 000-140 fall back to it.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_datamatrix(
+    options: &datamatrix::DataMatrixOptions,
+    data: &str,
+) -> crate::ZplResult<std::sync::Arc<rxing::common::BitMatrix>> {
+    barcode_cache::encode_native_cached(
+        "dmtx",
+        data,
+        &format!(
+            "c:{}:r:{}:rect:{}:esc:{}:gs1:{}",
+            options.columns, options.rows, options.rectangular, options.escape, options.gs1
+        ),
+        |data| datamatrix::encode(options, data),
+    )
+}
+
+/// Encodes a MaxiCode symbol (see [`maxicode::encode`]) through the barcode
+/// cache.
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_maxicode(
+    mode: u32,
+    symbol_number: u32,
+    symbol_count: u32,
+    data: &str,
+) -> crate::ZplResult<std::sync::Arc<rxing::common::BitMatrix>> {
+    barcode_cache::encode_native_cached(
+        "maxi",
+        data,
+        &format!("m:{}:n:{}:t:{}", mode, symbol_number, symbol_count),
+        |data| maxicode::encode(mode, symbol_number, symbol_count, data),
+    )
+}
+
+/// Encodes a PDF417 or MicroPDF417 field and pairs each symbol with the
+/// origin it is drawn at: `(x, y)` for a single symbol, or the `^FM` origins
+/// for Macro segments (excluded segments are encoded but dropped here).
+#[cfg(any(feature = "png", feature = "pdf"))]
+pub(crate) fn encode_stacked(
+    symbology: stacked::Stacked,
+    x: u32,
+    y: u32,
+    macro_origins: &[Option<(u32, u32)>],
+    data: &str,
+) -> crate::ZplResult<Vec<(u32, u32, std::sync::Arc<rxing::common::BitMatrix>)>> {
+    if !macro_origins.is_empty() {
+        let symbols = stacked::encode_segments(symbology, data, macro_origins.len())?;
+        return Ok(symbols
+            .into_iter()
+            .zip(macro_origins)
+            .filter_map(|(matrix, origin)| origin.map(|(x, y)| (x, y, std::sync::Arc::new(matrix))))
+            .collect());
+    }
+
+    let matrix = match symbology {
+        stacked::Stacked::Pdf417(options) => barcode_cache::encode_native_cached(
+            "pdf417",
+            data,
+            &format!(
+                "ec:{}:c:{}:r:{}:t:{}",
+                options.security_level.min(8),
+                options.columns,
+                options.rows,
+                options.truncate
+            ),
+            |data| stacked::encode_pdf417(&options, data),
+        )?,
+        stacked::Stacked::MicroPdf417 { mode } => {
+            barcode_cache::encode_native_cached("updf", data, &format!("m:{}", mode), |data| {
+                stacked::encode_micro_pdf417(mode, data)
+            })?
+        }
+    };
+    Ok(vec![(x, y, matrix)])
+}
+
 /// Process-wide, bounded cache of encoded barcode bit matrices.
 ///
 /// Encoding is pure (same format + data + hints → same matrix), so results
@@ -40,9 +441,12 @@ pub(crate) mod barcode_cache {
     const MAX_ENTRIES: usize = 512;
 
     type Key = (&'static str, String, String);
+    /// An encoded matrix and the interpretation line text that came with it
+    /// (empty for symbologies without one).
+    type Entry = (Arc<BitMatrix>, String);
 
-    fn cache() -> &'static Mutex<HashMap<Key, Arc<BitMatrix>>> {
-        static CACHE: OnceLock<Mutex<HashMap<Key, Arc<BitMatrix>>>> = OnceLock::new();
+    fn cache() -> &'static Mutex<HashMap<Key, Entry>> {
+        static CACHE: OnceLock<Mutex<HashMap<Key, Entry>>> = OnceLock::new();
         CACHE.get_or_init(|| Mutex::new(HashMap::new()))
     }
 
@@ -54,8 +458,11 @@ pub(crate) mod barcode_cache {
             BarcodeFormat::QR_CODE => "qr",
             BarcodeFormat::DATA_MATRIX => "dm",
             BarcodeFormat::PDF_417 => "p417",
+            BarcodeFormat::AZTEC => "az",
             BarcodeFormat::EAN_13 => "e13",
+            BarcodeFormat::EAN_8 => "e8",
             BarcodeFormat::UPC_A => "upca",
+            BarcodeFormat::UPC_E => "upce",
             BarcodeFormat::ITF => "itf",
             _ => "other",
         }
@@ -64,14 +471,68 @@ pub(crate) mod barcode_cache {
     /// Encodes `data` in the given format, reusing a cached matrix when the
     /// same (format, data, hints) triple was encoded before. `hints_key` must
     /// uniquely fingerprint the contents of `hints`.
-    #[allow(clippy::collapsible_if)]
     pub fn encode_cached(
         format: BarcodeFormat,
         data: &str,
         hints_key: &str,
         hints: Option<&EncodeHints>,
     ) -> ZplResult<Arc<BitMatrix>> {
-        let key: Key = (format_key(&format), data.to_string(), hints_key.to_string());
+        get_or_encode(format_key(&format), data, hints_key, || {
+            let writer = MultiFormatWriter;
+            match hints {
+                Some(h) => writer.encode_with_hints(data, &format, 0, 0, h),
+                None => writer.encode(data, &format, 0, 0),
+            }
+            .map(|matrix| (matrix, String::new()))
+            .map_err(|e| ZplError::BackendError(format!("Barcode Generation Error: {}", e)))
+        })
+        .map(|(matrix, _)| matrix)
+    }
+
+    /// Same as [`encode_cached`] for symbologies encoded natively by
+    /// zpl-forge. `format_key` names the symbology and must not collide with
+    /// the keys used for `rxing` formats.
+    pub fn encode_native_cached<F>(
+        format_key: &'static str,
+        data: &str,
+        hints_key: &str,
+        encode: F,
+    ) -> ZplResult<Arc<BitMatrix>>
+    where
+        F: FnOnce(&str) -> ZplResult<BitMatrix>,
+    {
+        get_or_encode(format_key, data, hints_key, || {
+            encode(data).map(|matrix| (matrix, String::new()))
+        })
+        .map(|(matrix, _)| matrix)
+    }
+
+    /// Same as [`encode_native_cached`] for 1-D symbologies whose encoder
+    /// also yields the interpretation line text, which is cached with the
+    /// matrix so that a hit skips the encoder entirely.
+    pub fn encode_native_cached_with_text<F>(
+        format_key: &'static str,
+        data: &str,
+        hints_key: &str,
+        encode: F,
+    ) -> ZplResult<(Arc<BitMatrix>, String)>
+    where
+        F: FnOnce(&str) -> ZplResult<(BitMatrix, String)>,
+    {
+        get_or_encode(format_key, data, hints_key, || encode(data))
+    }
+
+    #[allow(clippy::collapsible_if)]
+    fn get_or_encode<F>(
+        format_key: &'static str,
+        data: &str,
+        hints_key: &str,
+        encode: F,
+    ) -> ZplResult<Entry>
+    where
+        F: FnOnce() -> ZplResult<(BitMatrix, String)>,
+    {
+        let key: Key = (format_key, data.to_string(), hints_key.to_string());
 
         if let Ok(guard) = cache().lock() {
             if let Some(hit) = guard.get(&key) {
@@ -79,20 +540,14 @@ pub(crate) mod barcode_cache {
             }
         }
 
-        let writer = MultiFormatWriter;
-        let matrix = match hints {
-            Some(h) => writer.encode_with_hints(data, &format, 0, 0, h),
-            None => writer.encode(data, &format, 0, 0),
-        }
-        .map_err(|e| ZplError::BackendError(format!("Barcode Generation Error: {}", e)))?;
-
-        let matrix = Arc::new(matrix);
+        let (matrix, text) = encode()?;
+        let entry = (Arc::new(matrix), text);
         if let Ok(mut guard) = cache().lock() {
             if guard.len() >= MAX_ENTRIES {
                 guard.clear();
             }
-            guard.insert(key, matrix.clone());
+            guard.insert(key, entry.clone());
         }
-        Ok(matrix)
+        Ok(entry)
     }
 }
@@ -4,82 +4,68 @@
 //! vector operations for maximum quality and minimal file size.
 
 use std::cmp::max;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
 use std::sync::Arc;
 
-use ab_glyph::{Font, FontArc};
+use ab_glyph::{Font, GlyphId};
 use base64::{Engine as _, engine::general_purpose};
 use flate2::Compression;
 use flate2::write::ZlibEncoder;
 use lopdf::{Document, FontData, Object, Stream, dictionary};
 use rxing::common::BitMatrix;
-use rxing::{BarcodeFormat, EncodeHintType, EncodeHintValue, EncodeHints};
 
-use super::{barcode_1d_format, barcode_cache};
-use crate::engine::{Barcode1DKind, FontManager, ZplForgeBackend};
+use super::databar::{self, DataBarOptions, DataBarRow};
+use super::datamatrix::DataMatrixOptions;
+use super::linear::{self, HriGroup, LinearOptions};
+use super::maxicode;
+use super::postal::{self, PostalBar};
+use super::qr::QrOptions;
+use super::stacked::{self, Pdf417Options, Stacked};
+use super::subset;
+use super::{
+    encode_aztec, encode_barcode_1d, encode_code39, encode_code128, encode_datamatrix,
+    encode_maxicode, encode_qr, encode_stacked, encode_tlc39,
+};
+use crate::engine::font::{BitmapText, ShapedRun};
+use crate::engine::{
+    Barcode1DKind, FontManager, PostalKind, QrSegment, TextDecoration, ZplForgeBackend,
+};
 use crate::{ZplError, ZplResult};
 
 /// Bézier control-point factor for approximating a quarter-circle arc.
 const KAPPA: f64 = 0.5522847498;
 
-// ─── WinAnsi (CP1252) encoding ──────────────────────────────────────────────
+// ─── Identity-H text encoding ───────────────────────────────────────────────
 //
-// Embedded fonts are declared with /Encoding WinAnsiEncoding, so text shown
-// with `Tj` must be CP1252 bytes — not UTF-8. This is what makes accented
-// characters (ñ, á, é...) render and copy correctly.
-
-/// Unicode characters for CP1252 codes 0x80..=0x9F (`\u{0}` = undefined).
-const CP1252_80_9F: [char; 32] = [
-    '\u{20AC}', '\u{0}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
-    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{0}', '\u{017D}', '\u{0}',
-    '\u{0}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
-    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{0}', '\u{017E}', '\u{0178}',
-];
-
-/// Encodes a Unicode char to its CP1252 byte, when representable.
-fn char_to_winansi(c: char) -> Option<u8> {
-    let cp = c as u32;
-    match cp {
-        0x20..=0x7E => Some(cp as u8),
-        // CP1252 0xA0..=0xFF is identical to Latin-1.
-        0xA0..=0xFF => Some(cp as u8),
-        _ => CP1252_80_9F
-            .iter()
-            .position(|&m| m == c && m != '\u{0}')
-            .map(|i| 0x80 + i as u8),
-    }
-}
-
-/// Decodes a CP1252 byte back to its Unicode char, when defined.
-fn winansi_to_char(code: u8) -> Option<char> {
-    match code {
-        0x20..=0x7E => Some(code as char),
-        0xA0..=0xFF => Some(code as char),
-        0x80..=0x9F => {
-            let c = CP1252_80_9F[(code - 0x80) as usize];
-            (c != '\u{0}').then_some(c)
-        }
-        _ => None,
-    }
-}
-
-/// Builds a ToUnicode CMap stream body for the WinAnsi code range.
-fn build_tounicode_cmap() -> Vec<u8> {
-    let mut s = String::with_capacity(4096);
+// Embedded fonts are Type0 fonts with the Identity-H encoding, so text shown
+// with `TJ` is a string of big-endian 2-byte glyph ids (CID = GID). Any
+// character the registered font covers renders, and the ToUnicode CMap built
+// from the glyphs actually shown keeps the text selectable and searchable.
+
+/// Builds a ToUnicode CMap stream body mapping each used glyph id back to the
+/// text it was shown for.
+fn build_tounicode_cmap(glyphs: &BTreeMap<u16, String>) -> Vec<u8> {
+    let mut s = String::with_capacity(64 + glyphs.len() * 16);
     s.push_str(
         "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
          /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
          /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
-         1 begincodespacerange\n<20> <FF>\nendcodespacerange\n",
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
     );
-    let entries: Vec<(u8, char)> = (0x20..=0xFFu32)
-        .filter_map(|c| winansi_to_char(c as u8).map(|ch| (c as u8, ch)))
-        .collect();
+    // Glyphs that only continue a cluster (no text of their own) stay
+    // unmapped.
+    let entries: Vec<(&u16, &String)> = glyphs.iter().filter(|(_, t)| !t.is_empty()).collect();
     for chunk in entries.chunks(100) {
         s.push_str(&format!("{} beginbfchar\n", chunk.len()));
-        for (code, ch) in chunk {
-            s.push_str(&format!("<{:02X}> <{:04X}>\n", code, *ch as u32));
+        for (gid, text) in chunk {
+            s.push_str(&format!("<{:04X}> <", gid));
+            // Destination strings are UTF-16BE (astral characters take a
+            // surrogate pair); a ligature maps to all of its characters.
+            for unit in text.encode_utf16() {
+                s.push_str(&format!("{:04X}", unit));
+            }
+            s.push_str(">\n");
         }
         s.push_str("endbfchar\n");
     }
@@ -87,6 +73,49 @@ fn build_tounicode_cmap() -> Vec<u8> {
     s.into_bytes()
 }
 
+/// Six-letter subset tag (`ABCDEF+`) derived from the font name and the
+/// glyphs in the subset, so different subsets of a font get different names.
+fn subset_tag(name: &str, glyphs: &BTreeMap<u16, String>) -> String {
+    // FNV-1a over the name and the glyph ids.
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let gid_bytes = glyphs.keys().flat_map(|g| g.to_be_bytes());
+    for b in name.bytes().chain(gid_bytes) {
+        hash = (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+    }
+    (0..6)
+        .map(|_| {
+            let c = (b'A' + (hash % 26) as u8) as char;
+            hash /= 26;
+            c
+        })
+        .collect()
+}
+
+/// Builds the CIDFont `/W` array from the used glyph ids, grouping runs of
+/// consecutive ids as `first [w1 w2 ...]`.
+fn build_cid_widths(glyphs: &BTreeMap<u16, String>, width: impl Fn(u16) -> i64) -> Vec<Object> {
+    let mut w = Vec::new();
+    let mut run: Vec<Object> = Vec::new();
+    let mut run_start = 0u16;
+    let mut prev: Option<u16> = None;
+    for &gid in glyphs.keys() {
+        if prev.is_some_and(|p| p + 1 != gid) {
+            w.push(i64::from(run_start).into());
+            w.push(std::mem::take(&mut run).into());
+        }
+        if run.is_empty() {
+            run_start = gid;
+        }
+        run.push(width(gid).into());
+        prev = Some(gid);
+    }
+    if !run.is_empty() {
+        w.push(i64::from(run_start).into());
+        w.push(run.into());
+    }
+    w
+}
+
 // ─── Internal types ─────────────────────────────────────────────────────────
 
 /// Collected image data to be embedded as a PDF XObject during [`PdfNativeBackend::finalize`].
@@ -103,7 +132,7 @@ struct ImageXObject {
 
 /// A rendering backend that produces PDF documents with native vector operations.
 ///
-/// Text is rendered using an embedded TrueType font, shapes are drawn as PDF
+/// Text is rendered using embedded Unicode (Type0) fonts, shapes are drawn as PDF
 /// paths with Bézier curves, and barcodes are composed of filled rectangles.
 /// Bitmap data (graphic fields, custom images) is embedded as compressed
 /// XObject image streams.
@@ -122,8 +151,12 @@ pub struct PdfNativeBackend {
     font_manager: Option<Arc<FontManager>>,
     images: Vec<ImageXObject>,
     image_counter: usize,
-    /// Tracks which font identifiers (e.g. 'A', 'B', '0') have been used during rendering.
-    used_fonts: HashSet<char>,
+    /// Internal names of the fonts used during rendering, in first-use
+    /// order; a font's index names its `/F{index}` resource.
+    used_fonts: Vec<String>,
+    /// Glyph ids shown per internal font name, with the text each was shown
+    /// for (ToUnicode).
+    used_glyphs: HashMap<String, BTreeMap<u16, String>>,
     compression: Compression,
     /// Optional document title for the PDF Info dictionary.
     title: Option<String>,
@@ -157,7 +190,8 @@ impl PdfNativeBackend {
             font_manager: None,
             images: Vec::new(),
             image_counter: 0,
-            used_fonts: HashSet::new(),
+            used_fonts: Vec::new(),
+            used_glyphs: HashMap::new(),
             compression: Compression::default(),
             title: None,
             backdrop_rects: Vec::new(),
@@ -315,21 +349,48 @@ impl PdfNativeBackend {
         self.content.push(b'\n');
     }
 
-    /// Emit `(escaped) Tj\n`, encoding the text as WinAnsi (CP1252) to match
-    /// the embedded fonts' /Encoding. Unmappable characters become '?'.
-    fn emit_tj(&mut self, text: &str) {
-        self.content.push(b'(');
-        for c in text.chars() {
-            let b = char_to_winansi(c).unwrap_or(b'?');
-            match b {
-                b'(' | b')' | b'\\' => {
-                    self.content.push(b'\\');
-                    self.content.push(b);
+    /// Emit shaped text as `[<gid> adj <gid> ...] TJ` arrays of 2-byte
+    /// glyph ids, matching the embedded fonts' Identity-H encoding.
+    ///
+    /// `nominal` holds each glyph's `/W` width, in 1000/em. The `TJ`
+    /// adjustments move the pen from those widths to the shaped advances and
+    /// offsets; vertical offsets (attached marks) use the text rise `Ts`.
+    fn emit_shaped(&mut self, run: &ShapedRun, nominal: &[f64]) {
+        let to_thousandths = 1000.0 / run.units_per_em as f64;
+        let mut array_open = false;
+        let mut rise = 0.0;
+        // Pending pen shift to the right, in 1000/em. Shifts below half a
+        // unit (the /W rounding) are carried over rather than written.
+        let mut shift = 0.0;
+        for (glyph, &width) in run.glyphs.iter().zip(nominal) {
+            let glyph_rise = glyph.y_offset as f64 / run.units_per_em as f64;
+            if glyph_rise != rise {
+                if array_open {
+                    self.content.extend_from_slice(b"] TJ\n");
+                    array_open = false;
                 }
-                _ => self.content.push(b),
+                self.emit_nums(&[glyph_rise], "Ts");
+                rise = glyph_rise;
+            }
+            if !array_open {
+                self.content.push(b'[');
+                array_open = true;
+            }
+            shift += glyph.x_offset as f64 * to_thousandths;
+            if shift.abs() >= 0.5 {
+                let rounded = shift.round();
+                Self::put_num(&mut self.content, -rounded);
+                shift -= rounded;
             }
+            let _ = write!(self.content, "<{:04X}>", glyph.id.0);
+            shift += (glyph.x_advance - glyph.x_offset) as f64 * to_thousandths - width;
+        }
+        if array_open {
+            self.content.extend_from_slice(b"] TJ\n");
+        }
+        if rise != 0.0 {
+            self.emit_nums(&[0.0], "Ts");
         }
-        self.content.extend_from_slice(b") Tj\n");
     }
 
     fn set_fill_color(&mut self, r: f64, g: f64, b: f64) {
@@ -471,7 +532,7 @@ impl PdfNativeBackend {
         width: Option<u32>,
     ) -> u32 {
         match self.font_manager.as_ref() {
-            Some(fm) => fm.measure_text(font_char, height, width, text),
+            Some(fm) => fm.measure_text(font_char, height, width, text, 0),
             None => 0,
         }
     }
@@ -625,7 +686,7 @@ impl PdfNativeBackend {
         }
     }
 
-    // ── 1-D barcode rendering (shared by Code 128 / Code 39) ──────
+    // ── 1-D barcode rendering (shared by all linear symbologies) ──
 
     #[allow(clippy::too_many_arguments)]
     fn draw_1d_barcode(
@@ -635,23 +696,43 @@ impl PdfNativeBackend {
         orientation: char,
         height: u32,
         module_width: u32,
-        data: &str,
-        format: BarcodeFormat,
+        column_width: u32,
+        bit_matrix: &BitMatrix,
         reverse_print: bool,
         interpretation_line: char,
         interpretation_line_above: char,
-        hints: Option<EncodeHints>,
-        hints_key: &str,
+        hri: &[HriGroup],
+        guards: &[(i32, i32)],
     ) -> ZplResult<()> {
-        let bit_matrix = barcode_cache::encode_cached(format, data, hints_key, hints.as_ref())?;
-
-        let mw = max(module_width, 1);
+        let mw = max(column_width, 1);
         let bh = height;
         let bw = bit_matrix.getWidth() * mw;
+        // Retail guard bars reach down between the interpretation groups,
+        // which are only laid out under the bars of an unrotated symbol.
+        let extension =
+            if orientation == 'N' && interpretation_line == 'Y' && interpretation_line_above != 'Y'
+            {
+                let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
+                gap + text_h / 2
+            } else {
+                0
+            };
+        let frame_h = if guards.is_empty() {
+            bh
+        } else {
+            bh + extension
+        };
+        let bar_h = |gx: u32| {
+            if guards.iter().any(|&(s, e)| (s..e).contains(&(gx as i32))) {
+                frame_h
+            } else {
+                bh
+            }
+        };
 
         let (full_w, full_h) = match orientation {
-            'R' | 'B' => (bh, bw),
-            _ => (bw, bh),
+            'R' | 'B' => (frame_h, bw),
+            _ => (bw, frame_h),
         };
 
         // ── emit bar rectangles ────────────────────────────────────
@@ -662,8 +743,17 @@ impl PdfNativeBackend {
 
         for gx in 0..bit_matrix.getWidth() {
             if bit_matrix.get(gx, 0) {
-                let (rx, ry, rw, rh) =
-                    Self::transform_1d_bar(orientation, x, y, (gx * mw) as i32, 0, mw, bh, bw, bh);
+                let (rx, ry, rw, rh) = Self::transform_1d_bar(
+                    orientation,
+                    x,
+                    y,
+                    (gx * mw) as i32,
+                    0,
+                    mw,
+                    bar_h(gx),
+                    bw,
+                    frame_h,
+                );
                 let px = self.d2pt(rx as f64);
                 let py = self.height_pt - self.d2pt(ry as f64 + rh as f64);
                 let pw = self.d2pt(rw as f64);
@@ -683,13 +773,17 @@ impl PdfNativeBackend {
 
         // ── interpretation line ────────────────────────────────────
         if interpretation_line == 'Y' {
+            let bars_h = match orientation {
+                'R' | 'B' => bw,
+                _ => bh,
+            };
             self.draw_interpretation_line(
                 x,
                 y,
-                full_w,
-                full_h,
+                bars_h,
+                module_width,
                 mw,
-                data,
+                hri,
                 interpretation_line_above,
             )?;
         }
@@ -697,32 +791,158 @@ impl PdfNativeBackend {
         Ok(())
     }
 
+    /// Paints height-modulated postal bars: every bar is `module_width` dots
+    /// wide, and its vertical extent is a fraction of `height`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_postal_bars(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        height: u32,
+        module_width: u32,
+        bars: &[PostalBar],
+        reverse_print: bool,
+    ) {
+        let mw = max(module_width, 1);
+        let pitch = mw + postal::bar_gap(mw);
+        let bh = height;
+        let bw = (bars.len() as u32 * pitch).saturating_sub(pitch - mw);
+        let (full_w, full_h) = match orientation {
+            'R' | 'B' => (bh, bw),
+            _ => (bw, bh),
+        };
+
+        self.save_state();
+        if !reverse_print {
+            self.set_fill_color(0.0, 0.0, 0.0);
+        }
+        for (i, bar) in bars.iter().enumerate() {
+            let (top, bottom) = bar.extent();
+            let ly = (top * bh as f64).round() as u32;
+            let lh = ((bottom * bh as f64).round() as u32)
+                .saturating_sub(ly)
+                .max(1);
+            let (rx, ry, rw, rh) = Self::transform_1d_bar(
+                orientation,
+                x,
+                y,
+                (i as u32 * pitch) as i32,
+                ly as i32,
+                mw,
+                lh,
+                bw,
+                bh,
+            );
+            let px = self.d2pt(rx as f64);
+            let py = self.height_pt - self.d2pt(ry as f64 + rh as f64);
+            let pw = self.d2pt(rw as f64);
+            let ph = self.d2pt(rh as f64);
+            self.emit_nums(&[px, py, pw, ph], "re");
+        }
+        if reverse_print {
+            self.emit_op("W");
+            self.emit_op("n");
+            self.fill_inverse_backdrop(x as f64, y as f64, full_w as f64, full_h as f64);
+        } else {
+            self.emit_op("f");
+        }
+        self.restore_state();
+    }
+
+    /// Paints a stacked symbol row by row (GS1 DataBar, CODABLOCK F): each
+    /// dark run is a bar `module_width` dots per module wide and as tall as
+    /// its row.
+    fn draw_module_rows(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        module_width: u32,
+        rows: &[DataBarRow],
+        reverse_print: bool,
+    ) {
+        let mw = max(module_width, 1);
+        let bw = rows
+            .iter()
+            .map(|row| row.modules.len() as u32)
+            .max()
+            .unwrap_or(0)
+            * mw;
+        let bh: u32 = rows.iter().map(|row| row.height).sum();
+        let (full_w, full_h) = match orientation {
+            'R' | 'B' => (bh, bw),
+            _ => (bw, bh),
+        };
+
+        self.save_state();
+        if !reverse_print {
+            self.set_fill_color(0.0, 0.0, 0.0);
+        }
+        let mut ly = 0;
+        for row in rows {
+            for (start, len) in row.dark_runs() {
+                let (rx, ry, rw, rh) = Self::transform_1d_bar(
+                    orientation,
+                    x,
+                    y,
+                    (start as u32 * mw) as i32,
+                    ly as i32,
+                    len as u32 * mw,
+                    row.height,
+                    bw,
+                    bh,
+                );
+                let px = self.d2pt(rx as f64);
+                let py = self.height_pt - self.d2pt(ry as f64 + rh as f64);
+                let pw = self.d2pt(rw as f64);
+                let ph = self.d2pt(rh as f64);
+                self.emit_nums(&[px, py, pw, ph], "re");
+            }
+            ly += row.height;
+        }
+        if reverse_print {
+            self.emit_op("W");
+            self.emit_op("n");
+            self.fill_inverse_backdrop(x as f64, y as f64, full_w as f64, full_h as f64);
+        } else {
+            self.emit_op("f");
+        }
+        self.restore_state();
+    }
+
+    /// Draws the interpretation-line groups above or below a 1-D symbol whose
+    /// bars span `full_h` dots from `(x, y)`. Group spans are in matrix columns
+    /// of `column_width` dots; the text size follows `module_width`.
     #[allow(clippy::too_many_arguments)]
     fn draw_interpretation_line(
         &mut self,
         x: u32,
         y: u32,
-        full_w: u32,
         full_h: u32,
         module_width: u32,
-        data: &str,
+        column_width: u32,
+        hri: &[HriGroup],
         interpretation_line_above: char,
     ) -> ZplResult<()> {
-        {
-            let font_char = '0';
-            let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
-            let text_y = if interpretation_line_above == 'Y' {
-                y.saturating_sub(text_h + gap)
-            } else {
-                y + full_h + gap
-            };
+        let font_char = '0';
+        let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
+        let text_y = if interpretation_line_above == 'Y' {
+            y.saturating_sub(text_h + gap)
+        } else {
+            y + full_h + gap
+        };
 
-            let text_width = self.get_text_width(data, font_char, Some(text_h), None);
-            let text_x = if full_w > text_width {
-                x + (full_w - text_width) / 2
+        for group in hri {
+            let span_x = group.start * column_width as i32;
+            let span_w = (group.end - group.start).max(0) * column_width as i32;
+            let text_width = self.get_text_width(&group.text, font_char, Some(text_h), None) as i32;
+            let offset = if span_w > text_width {
+                (span_w - text_width) / 2
             } else {
-                x
+                0
             };
+            let text_x = (x as i32 + span_x + offset).max(0) as u32;
 
             self.draw_text(
                 text_x,
@@ -731,9 +951,10 @@ impl PdfNativeBackend {
                 Some(text_h),
                 None,
                 'N',
-                data,
+                &group.text,
                 false,
                 None,
+                TextDecoration::default(),
             )?;
         }
 
@@ -799,6 +1020,55 @@ impl PdfNativeBackend {
         }
         self.restore_state();
     }
+
+    /// Draws the dots of bitmap-font text as vector rectangles, rotated by
+    /// `orientation` around the top-left corner (x, y) like matrix cells.
+    fn fill_bitmap_text(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        bitmap: &BitmapText,
+        reverse_print: bool,
+        color: Option<String>,
+    ) {
+        if bitmap.rects.is_empty() {
+            return;
+        }
+        self.save_state();
+        if !reverse_print {
+            let (r, g, b) = Self::parse_hex_color_f64(&color);
+            self.set_fill_color(r, g, b);
+        }
+        for &(lx, ly, w, h) in &bitmap.rects {
+            let (rx, ry, rw, rh) = Self::transform_2d_cell(
+                orientation,
+                x,
+                y,
+                lx as i32,
+                ly as i32,
+                w,
+                h,
+                bitmap.width,
+                bitmap.height,
+            );
+            let px = self.d2pt(rx as f64);
+            let py = self.height_pt - self.d2pt(ry as f64 + rh as f64);
+            self.emit_nums(&[px, py, self.d2pt(rw as f64), self.d2pt(rh as f64)], "re");
+        }
+        if reverse_print {
+            self.emit_op("W");
+            self.emit_op("n");
+            let (fw, fh) = match orientation {
+                'R' | 'B' => (bitmap.height, bitmap.width),
+                _ => (bitmap.width, bitmap.height),
+            };
+            self.fill_inverse_backdrop(x as f64, y as f64, fw as f64, fh as f64);
+        } else {
+            self.emit_op("f");
+        }
+        self.restore_state();
+    }
 }
 
 // ─── ZplForgeBackend ────────────────────────────────────────────────────────
@@ -837,27 +1107,62 @@ impl ZplForgeBackend for PdfNativeBackend {
         text: &str,
         reverse_print: bool,
         color: Option<String>,
+        decoration: TextDecoration,
     ) -> ZplResult<()> {
         if text.is_empty() {
             return Ok(());
         }
 
-        let layout = {
+        let (layout, shaped) = {
             let fm = self
                 .font_manager
-                .as_ref()
+                .clone()
                 .ok_or_else(|| ZplError::FontError("Font manager not initialized".into()))?;
-            fm.text_layout(font, height, width)
-                .ok_or_else(|| ZplError::FontError(format!("Font not found: {}", font)))?
-                .1
+            if let Some(lines) = fm.decoration_rects(font, height, width, text, &decoration) {
+                self.fill_bitmap_text(x, y, orientation, &lines, reverse_print, color.clone());
+            }
+            if let Some(bitmap) =
+                fm.bitmap_text(font, height, width, text, decoration.letter_spacing)
+            {
+                self.fill_bitmap_text(x, y, orientation, &bitmap, reverse_print, color);
+                return Ok(());
+            }
+            let (_, layout) = fm
+                .text_layout(font, height, width)
+                .ok_or_else(|| ZplError::FontError(format!("Font not found: {}", font)))?;
+            let shaped = fm
+                .shape_text(font, height, width, text, decoration.letter_spacing)
+                .ok_or_else(|| ZplError::FontError(format!("Font not found: {}", font)))?;
+            (layout, shaped)
         };
 
-        self.used_fonts.insert(font);
+        // Resource index and nominal /W widths of each run's font.
+        let mut runs = Vec::with_capacity(shaped.runs.len());
+        for run in &shaped.runs {
+            let used = self.used_glyphs.entry(run.font_name.clone()).or_default();
+            for glyph in &run.glyphs {
+                let mapped = used.entry(glyph.id.0).or_default();
+                if mapped.is_empty() {
+                    mapped.clone_from(&glyph.text);
+                }
+            }
+            let index = match self.used_fonts.iter().position(|n| *n == run.font_name) {
+                Some(index) => index,
+                None => {
+                    self.used_fonts.push(run.font_name.clone());
+                    self.used_fonts.len() - 1
+                }
+            };
+            // Same rounding as the /W entries written in `finalize`.
+            let upem = run.units_per_em as f64;
+            let nominal: Vec<f64> = run
+                .glyphs
+                .iter()
+                .map(|g| (run.font.h_advance_unscaled(g.id) as f64 * 1000.0 / upem).round())
+                .collect();
+            runs.push((run, index, nominal));
+        }
 
-        // PDF text space: `Tf 1` + a Tm scale of `s` renders a glyph em of
-        // `s` points, so the matrix carries the em sizes (not the ^A values).
-        let em_x_pt = self.d2pt(layout.em_x as f64);
-        let em_y_pt = self.d2pt(layout.em_y as f64);
         // Distance from the character-cell top to the baseline, in dots.
         let baseline_dots = layout.baseline as f64;
         let h_dots = layout.cell_h as f64;
@@ -866,46 +1171,47 @@ impl ZplForgeBackend for PdfNativeBackend {
 
         // Text width anchors 'I'/'B' rotations and sizes the reverse bbox.
         let tw_dots = if reverse_print || orientation == 'I' || orientation == 'B' {
-            self.get_text_width(text, font, height, width) as f64
+            shaped.width().ceil() as f64
         } else {
             0.0
         };
 
-        // Text matrix [a b c d tx ty]: scale plus the ^A rotation, with
-        // (x, y) anchoring the top-left corner of the rotated cell.
-        let tm = match orientation {
-            'R' => [
-                0.0,
-                -em_x_pt,
-                em_y_pt,
-                0.0,
-                self.x_pt(x + h_dots - baseline_dots),
-                self.height_pt - y * self.scale,
-            ],
-            'I' => [
-                -em_x_pt,
-                0.0,
-                0.0,
-                -em_y_pt,
-                self.x_pt(x + tw_dots),
-                self.height_pt - (y + h_dots - baseline_dots) * self.scale,
-            ],
-            'B' => [
-                0.0,
-                em_x_pt,
-                -em_y_pt,
-                0.0,
-                self.x_pt(x + baseline_dots),
-                self.height_pt - (y + tw_dots) * self.scale,
-            ],
-            _ => [
-                em_x_pt,
-                0.0,
-                0.0,
-                em_y_pt,
-                self.x_pt(x),
-                self.height_pt - (y + baseline_dots) * self.scale,
-            ],
+        // Baseline origin in points, with (x, y) anchoring the top-left
+        // corner of the rotated cell, and the unit vectors of the text
+        // direction and of "up" for the ^A rotation.
+        let (origin, dir, up) = match orientation {
+            'R' => (
+                (
+                    self.x_pt(x + h_dots - baseline_dots),
+                    self.height_pt - y * self.scale,
+                ),
+                (0.0, -1.0),
+                (1.0, 0.0),
+            ),
+            'I' => (
+                (
+                    self.x_pt(x + tw_dots),
+                    self.height_pt - (y + h_dots - baseline_dots) * self.scale,
+                ),
+                (-1.0, 0.0),
+                (0.0, -1.0),
+            ),
+            'B' => (
+                (
+                    self.x_pt(x + baseline_dots),
+                    self.height_pt - (y + tw_dots) * self.scale,
+                ),
+                (0.0, 1.0),
+                (-1.0, 0.0),
+            ),
+            _ => (
+                (
+                    self.x_pt(x),
+                    self.height_pt - (y + baseline_dots) * self.scale,
+                ),
+                (1.0, 0.0),
+                (0.0, 1.0),
+            ),
         };
 
         self.save_state();
@@ -919,10 +1225,27 @@ impl ZplForgeBackend for PdfNativeBackend {
             // Text rendering mode 7: glyph outlines become the clipping path.
             self.emit_nums(&[7.0], "Tr");
         }
-        self.emit_nums(&tm, "Tm");
-        let font_resource_name = format!("F_{}", font);
-        self.emit_name_op(&format!("{} 1", font_resource_name), "Tf");
-        self.emit_tj(text);
+        // Each run (one per fallback font) gets its own text matrix, starting
+        // where the previous run ended on the shared baseline. PDF text
+        // space: `Tf 1` + a Tm scale of `s` renders a glyph em of `s` points,
+        // so the matrix carries the em sizes (not the ^A values).
+        let mut pen_pt = 0.0;
+        for (run, index, nominal) in &runs {
+            let em_x_pt = self.d2pt((run.scale_x * run.units_per_em) as f64);
+            let em_y_pt = self.d2pt((run.scale_y * run.units_per_em) as f64);
+            let tm = [
+                dir.0 * em_x_pt,
+                dir.1 * em_x_pt,
+                up.0 * em_y_pt,
+                up.1 * em_y_pt,
+                origin.0 + dir.0 * pen_pt,
+                origin.1 + dir.1 * pen_pt,
+            ];
+            self.emit_nums(&tm, "Tm");
+            self.emit_name_op(&format!("F{} 1", index), "Tf");
+            self.emit_shaped(run, nominal);
+            pen_pt += self.d2pt(run.width() as f64);
+        }
         self.emit_op("ET");
 
         if reverse_print {
@@ -1221,29 +1544,12 @@ impl ZplForgeBackend for PdfNativeBackend {
         module_width: u32,
         interpretation_line: char,
         interpretation_line_above: char,
-        _check_digit: char,
-        _mode: char,
+        check_digit: char,
+        mode: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let (clean_data, hint_val) = if let Some(stripped) = data.strip_prefix(">:") {
-            (stripped, Some("B"))
-        } else if let Some(stripped) = data.strip_prefix(">;") {
-            (stripped, Some("C"))
-        } else if let Some(stripped) = data.strip_prefix(">9") {
-            (stripped, Some("A"))
-        } else {
-            (data, Some("B")) // Standard default is Code Set B
-        };
-
-        let hints = hint_val.map(|v| {
-            let mut h = HashMap::new();
-            h.insert(
-                EncodeHintType::FORCE_CODE_SET,
-                EncodeHintValue::ForceCodeSet(v.to_string()),
-            );
-            EncodeHints::from(h)
-        });
+        let symbol = encode_code128(mode, check_digit, module_width, data)?;
 
         self.draw_1d_barcode(
             x,
@@ -1251,13 +1557,13 @@ impl ZplForgeBackend for PdfNativeBackend {
             orientation,
             height,
             module_width,
-            clean_data,
-            BarcodeFormat::CODE_128,
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            hints,
-            hint_val.unwrap_or(""),
+            &linear::hri_groups(None, &symbol.text, &symbol.matrix),
+            &[],
         )
     }
 
@@ -1268,38 +1574,23 @@ impl ZplForgeBackend for PdfNativeBackend {
         x: u32,
         y: u32,
         orientation: char,
-        _model: u32,
+        model: u32,
         magnification: u32,
         error_correction: char,
         _mask: u32,
         data: &str,
+        segments: &[QrSegment],
+        structured_append: Option<(u32, u32, u8)>,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let level = match error_correction {
-            'L' => "L",
-            'M' => "M",
-            'Q' => "Q",
-            'H' => "H",
-            _ => "M",
+        let options = QrOptions {
+            model,
+            error_correction,
+            structured_append,
+            gs1,
         };
-
-        let mut hints = HashMap::new();
-        hints.insert(
-            EncodeHintType::ERROR_CORRECTION,
-            EncodeHintValue::ErrorCorrection(level.to_string()),
-        );
-        hints.insert(
-            EncodeHintType::MARGIN,
-            EncodeHintValue::Margin("0".to_owned()),
-        );
-        let hints: EncodeHints = hints.into();
-
-        let bit_matrix = barcode_cache::encode_cached(
-            BarcodeFormat::QR_CODE,
-            data,
-            &format!("ec:{}", level),
-            Some(&hints),
-        )?;
+        let bit_matrix = encode_qr(&options, data, segments)?;
 
         let mag = max(magnification, 1);
         self.fill_matrix_cells(x, y, orientation, mag, mag, &bit_matrix, reverse_print);
@@ -1314,10 +1605,23 @@ impl ZplForgeBackend for PdfNativeBackend {
         y: u32,
         orientation: char,
         module_size: u32,
+        _quality: u32,
+        columns: u32,
+        rows: u32,
+        rectangular: bool,
+        escape: char,
         data: &str,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let bit_matrix = barcode_cache::encode_cached(BarcodeFormat::DATA_MATRIX, data, "", None)?;
+        let options = DataMatrixOptions {
+            columns,
+            rows,
+            rectangular,
+            escape,
+            gs1,
+        };
+        let bit_matrix = encode_datamatrix(&options, data)?;
 
         let m = max(module_size, 1);
         self.fill_matrix_cells(x, y, orientation, m, m, &bit_matrix, reverse_print);
@@ -1334,30 +1638,131 @@ impl ZplForgeBackend for PdfNativeBackend {
         row_height: u32,
         module_width: u32,
         security_level: u32,
+        columns: u32,
+        rows: u32,
+        truncate: bool,
+        macro_origins: &[Option<(u32, u32)>],
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let mut hints = HashMap::new();
-        hints.insert(
-            EncodeHintType::ERROR_CORRECTION,
-            EncodeHintValue::ErrorCorrection(security_level.min(8).to_string()),
-        );
-        hints.insert(
-            EncodeHintType::MARGIN,
-            EncodeHintValue::Margin("0".to_owned()),
-        );
-        let hints: EncodeHints = hints.into();
+        let options = Pdf417Options {
+            security_level,
+            columns,
+            rows,
+            truncate,
+        };
+        let symbols = encode_stacked(Stacked::Pdf417(options), x, y, macro_origins, data)?;
 
-        let bit_matrix = barcode_cache::encode_cached(
-            BarcodeFormat::PDF_417,
-            data,
-            &format!("ec:{}", security_level.min(8)),
-            Some(&hints),
-        )?;
+        let cw = max(module_width, 1);
+        let ch = max(row_height, 1);
+        for (x, y, bit_matrix) in symbols {
+            self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        }
+        Ok(())
+    }
+
+    // ── MicroPDF417 (^BF) ──────────────────────────────────────────
+
+    fn draw_micro_pdf417(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        mode: u32,
+        macro_origins: &[Option<(u32, u32)>],
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbols = encode_stacked(Stacked::MicroPdf417 { mode }, x, y, macro_origins, data)?;
 
         let cw = max(module_width, 1);
         let ch = max(row_height, 1);
-        self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        for (x, y, bit_matrix) in symbols {
+            self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        }
+        Ok(())
+    }
+
+    // ── TLC39 (^BT) ────────────────────────────────────────────────
+    fn draw_tlc39(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        module_width: u32,
+        ratio: f64,
+        height: u32,
+        micro_module_width: u32,
+        row_height: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = encode_tlc39(
+            data,
+            module_width,
+            ratio,
+            height,
+            micro_module_width,
+            row_height,
+        )?;
+        let ((lx, ly), micro_origin) = symbol.layout.origins(x, y, orientation);
+
+        self.draw_1d_barcode(
+            lx,
+            ly,
+            orientation,
+            height,
+            module_width,
+            symbol.linear.column_width,
+            &symbol.linear.matrix,
+            reverse_print,
+            'N',
+            'N',
+            &[],
+            &[],
+        )?;
+        if let (Some(matrix), Some((mx, my))) = (&symbol.micro, micro_origin) {
+            self.fill_matrix_cells(
+                mx,
+                my,
+                orientation,
+                max(micro_module_width, 1),
+                max(row_height, 1),
+                matrix,
+                reverse_print,
+            );
+        }
+        Ok(())
+    }
+
+    // ── CODABLOCK (^BB) ────────────────────────────────────────────
+
+    fn draw_codablock(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        security: char,
+        columns: u32,
+        rows: u32,
+        mode: char,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = stacked::encode_codablock(
+            mode,
+            security,
+            columns,
+            rows,
+            row_height,
+            module_width,
+            data,
+        )?;
+        self.draw_module_rows(x, y, orientation, module_width, &symbol, reverse_print);
         Ok(())
     }
 
@@ -1368,31 +1773,34 @@ impl ZplForgeBackend for PdfNativeBackend {
         x: u32,
         y: u32,
         orientation: char,
-        _check_digit: char,
+        check_digit: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
+        let symbol = encode_code39(data, check_digit, module_width, ratio)?;
+
         self.draw_1d_barcode(
             x,
             y,
             orientation,
             height,
             module_width,
-            data,
-            BarcodeFormat::CODE_39,
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            None,
-            "",
+            &linear::hri_groups(None, &symbol.text, &symbol.matrix),
+            &[],
         )
     }
 
-    // ── generic 1-D barcodes (EAN-13, UPC-A, ITF, Code 93) ────────
+    // ── generic 1-D barcodes (EAN/UPC, add-ons, ITF, Code 93) ─────
 
     fn draw_barcode_1d(
         &mut self,
@@ -1402,27 +1810,196 @@ impl ZplForgeBackend for PdfNativeBackend {
         orientation: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
+        check_digit: char,
+        print_check_digit: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
+        let options = LinearOptions {
+            module_width,
+            ratio,
+            check_digit,
+            print_check_digit: print_check_digit == 'Y',
+        };
+        let symbol = encode_barcode_1d(kind, data, &options)?;
+
         self.draw_1d_barcode(
             x,
             y,
             orientation,
             height,
             module_width,
-            data,
-            barcode_1d_format(kind),
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            None,
-            "",
+            &linear::hri_groups(Some(kind), &symbol.text, &symbol.matrix),
+            &linear::extended_guards(Some(kind), &symbol.text, &symbol.matrix),
         )
     }
 
+    // ── Aztec (^B0) ────────────────────────────────────────────────
+
+    fn draw_aztec(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        magnification: u32,
+        extended_channel: char,
+        error_control: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let bit_matrix = encode_aztec(data, extended_channel, error_control)?;
+
+        let mag = max(magnification, 1);
+        self.fill_matrix_cells(x, y, orientation, mag, mag, &bit_matrix, reverse_print);
+        Ok(())
+    }
+
+    // ── MaxiCode (^BD) ─────────────────────────────────────────────
+
+    fn draw_maxicode(
+        &mut self,
+        x: u32,
+        y: u32,
+        mode: u32,
+        symbol_number: u32,
+        symbol_count: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let bit_matrix = encode_maxicode(mode, symbol_number, symbol_count, data)?;
+        let geometry = maxicode::Geometry::new(self.resolution as f64);
+        let to_pt = |this: &Self, (hx, hy): (f64, f64)| {
+            (
+                this.x_pt(x as f64 + hx),
+                this.height_pt - (y as f64 + hy) * this.scale,
+            )
+        };
+
+        self.save_state();
+        if !reverse_print {
+            self.set_fill_color(0.0, 0.0, 0.0);
+        }
+
+        for row in 0..maxicode::ROWS {
+            for col in 0..maxicode::COLUMNS {
+                if !bit_matrix.get(col, row) {
+                    continue;
+                }
+                let hexagon = geometry.hexagon(col, row).map(|p| to_pt(self, p));
+                let (sx, sy) = hexagon[0];
+                self.emit_nums(&[sx, sy], "m");
+                for &(px, py) in &hexagon[1..] {
+                    self.emit_nums(&[px, py], "l");
+                }
+                self.emit_op("h");
+            }
+        }
+
+        // Bullseye finder: nested circles filled even-odd give the
+        // alternating dark and light rings.
+        let (cx, cy) = to_pt(self, geometry.bullseye_center());
+        for radius in geometry.bullseye_radii() {
+            let r = self.d2pt(radius);
+            self.push_ellipse_path(cx, cy, r, r);
+        }
+
+        if reverse_print {
+            self.emit_op("W*");
+            self.emit_op("n");
+            self.fill_inverse_backdrop(x as f64, y as f64, geometry.width, geometry.height);
+        } else {
+            self.emit_op("f*");
+        }
+        self.restore_state();
+        Ok(())
+    }
+
+    // ── GS1 DataBar (^BR) ──────────────────────────────────────────
+
+    fn draw_databar(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        symbology: u32,
+        magnification: u32,
+        separator_height: u32,
+        height: u32,
+        segment_width: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let options = DataBarOptions {
+            magnification,
+            separator_height,
+            bar_height: height,
+            segment_width,
+        };
+        let rows = databar::encode(symbology, data, &options)?;
+        self.draw_module_rows(x, y, orientation, magnification, &rows, reverse_print);
+        Ok(())
+    }
+
+    // ── postal barcodes (^BZ, ^B5) ─────────────────────────────────
+
+    fn draw_postal_barcode(
+        &mut self,
+        kind: PostalKind,
+        x: u32,
+        y: u32,
+        orientation: char,
+        height: u32,
+        module_width: u32,
+        interpretation_line: char,
+        interpretation_line_above: char,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = postal::encode(kind, data)?;
+        self.draw_postal_bars(
+            x,
+            y,
+            orientation,
+            height,
+            module_width,
+            &symbol.bars,
+            reverse_print,
+        );
+
+        if interpretation_line == 'Y' {
+            let mw = max(module_width, 1);
+            let pitch = mw + postal::bar_gap(mw);
+            let width = symbol.bars.len() as i32 * pitch as i32;
+            let full_h = match orientation {
+                'R' | 'B' => width as u32,
+                _ => height,
+            };
+            let hri = [HriGroup {
+                text: symbol.text,
+                start: 0,
+                end: width,
+            }];
+            self.draw_interpretation_line(
+                x,
+                y,
+                full_h,
+                module_width,
+                1,
+                &hri,
+                interpretation_line_above,
+            )?;
+        }
+        Ok(())
+    }
+
     // ── diagonal line (^GD) ────────────────────────────────────────
 
     fn draw_graphic_diagonal(
@@ -1481,71 +2058,79 @@ impl ZplForgeBackend for PdfNativeBackend {
     // ── finalize ───────────────────────────────────────────────────
 
     fn finalize(&mut self) -> ZplResult<Vec<u8>> {
-        let mut doc = Document::with_version("1.5");
+        let mut doc = Document::with_version("1.6");
         let pages_id = doc.new_object_id();
 
         // ── embed fonts ────────────────────────────────────────────
         //
         // Font objects are built manually instead of using `lopdf::Document::
-        // add_font`, which omits /Widths and /ToUnicode and stores descriptor
-        // metrics in raw font units. Here every metric is normalized to the
-        // 1000/em glyph space and a ToUnicode CMap makes text extraction
-        // (copy/paste, search) work for the full WinAnsi range.
-        let default_font_bytes: &[u8] = include_bytes!("../assets/IosevkaTermSlab-Regular.ttf");
+        // add_font`, which only writes simple fonts. Each font becomes a Type0
+        // font with the Identity-H encoding over a CIDFont descendant:
+        // CIDFontType2 for TrueType outlines, CIDFontType0 with an OpenType
+        // FontFile3 for CFF outlines. Metrics are normalized to the 1000/em
+        // glyph space, /W carries the width of every glyph shown and the
+        // ToUnicode CMap maps those glyphs back to their characters. Font
+        // programs are subset to the glyphs shown on the document's pages,
+        // under a tagged `/FontName`; variable fonts become static TrueType
+        // instances of those glyphs.
+        let fm = self.font_manager.clone();
         let mut font_dict = lopdf::Dictionary::new();
-        // Dedup: multiple ZPL identifiers often map to the same font.
-        let mut embedded_fonts: HashMap<String, lopdf::ObjectId> = HashMap::new();
-        let tounicode_id = doc.add_object(Stream::new(dictionary! {}, build_tounicode_cmap()));
-
-        for font_char in &self.used_fonts {
-            let font_key = font_char.to_string();
-            let resource_name = format!("F_{}", font_char);
-
-            let actual_name = self
-                .font_manager
-                .as_ref()
-                .and_then(|fm| fm.get_font_name(&font_key).map(|s| s.to_string()))
-                .unwrap_or_else(|| "Iosevka Term Slab".to_string());
-
-            if let Some(font_id) = embedded_fonts.get(&actual_name) {
-                font_dict.set(resource_name.as_str(), *font_id);
-                continue;
-            }
+        for (index, actual_name) in self.used_fonts.iter().enumerate() {
+            let resource_name = format!("F{}", index);
+
+            let not_found = || ZplError::FontError(format!("Font not found: {}", actual_name));
+            let fm = fm.as_ref().ok_or_else(not_found)?;
+            let raw_bytes = fm.font_bytes_by_name(actual_name).ok_or_else(not_found)?;
+            // The registered instance: its advances match the content stream.
+            let face = fm.font_by_name(actual_name).ok_or_else(not_found)?;
+            let variations = fm.font_variations(actual_name);
+            let glyphs = self
+                .used_glyphs
+                .get(actual_name)
+                .cloned()
+                .unwrap_or_default();
 
-            let raw_bytes = self
-                .font_manager
-                .as_ref()
-                .and_then(|fm| fm.get_font_bytes(&font_key))
-                .unwrap_or(default_font_bytes);
-
-            let face = FontArc::try_from_vec(raw_bytes.to_vec())
-                .map_err(|e| ZplError::FontError(format!("Invalid font data: {}", e)))?;
             let upem = face.units_per_em().unwrap_or(1000.0) as f64;
             let to_glyph_space = |v: f64| (v * 1000.0 / upem).round() as i64;
 
-            // /Widths for the WinAnsi code range 32..=255.
-            let widths: Vec<Object> = (0x20..=0xFFu32)
-                .map(|code| {
-                    let w = winansi_to_char(code as u8)
-                        .map(|ch| to_glyph_space(face.h_advance_unscaled(face.glyph_id(ch)) as f64))
-                        .unwrap_or(0);
-                    w.into()
-                })
-                .collect();
+            let widths = build_cid_widths(&glyphs, |gid| {
+                to_glyph_space(face.h_advance_unscaled(GlyphId(gid)) as f64)
+            });
 
             // Bounding box and style metrics via ttf-parser (lopdf::FontData).
             let fd = FontData::new(raw_bytes, actual_name.clone());
 
-            let font_stream = Stream::new(
-                dictionary! { "Length1" => raw_bytes.len() as i64 },
-                raw_bytes.to_vec(),
-            );
+            // Fonts the subsetter cannot handle are embedded whole, untagged
+            // (variable fonts then show their default instance).
+            let gids = glyphs.keys().copied().collect();
+            let subset = if variations.is_empty() {
+                subset::subset_font(raw_bytes, &gids)
+            } else {
+                subset::instance_font(raw_bytes, variations, &gids)
+            };
+            let (program, base_font) = match subset {
+                Some(program) => {
+                    let tag = subset_tag(actual_name, &glyphs);
+                    (program, format!("{}+{}", tag, actual_name.replace(' ', "")))
+                }
+                None => (raw_bytes.to_vec(), actual_name.replace(' ', "")),
+            };
+            let base_font = Object::Name(base_font.into_bytes());
+            let is_cff = program.starts_with(b"OTTO");
+
+            let (font_file_key, font_stream) = if is_cff {
+                let dict = dictionary! { "Subtype" => "OpenType" };
+                ("FontFile3", Stream::new(dict, program))
+            } else {
+                let dict = dictionary! { "Length1" => program.len() as i64 };
+                ("FontFile2", Stream::new(dict, program))
+            };
             let font_file_id = doc.add_object(font_stream);
 
             let descriptor_id = doc.add_object(dictionary! {
                 "Type" => "FontDescriptor",
-                "FontName" => Object::Name(actual_name.clone().into_bytes()),
-                "Flags" => 32_i64,
+                "FontName" => base_font.clone(),
+                "Flags" => 4_i64,
                 "FontBBox" => vec![
                     to_glyph_space(fd.font_bbox.0 as f64).into(),
                     to_glyph_space(fd.font_bbox.1 as f64).into(),
@@ -1557,23 +2142,39 @@ impl ZplForgeBackend for PdfNativeBackend {
                 "Descent" => to_glyph_space(fd.descent as f64),
                 "CapHeight" => to_glyph_space(fd.cap_height as f64),
                 "StemV" => 80_i64,
-                "FontFile2" => font_file_id,
+                font_file_key => font_file_id,
             });
 
-            let font_id = doc.add_object(dictionary! {
+            let mut cid_font = dictionary! {
                 "Type" => "Font",
-                "Subtype" => "TrueType",
-                "BaseFont" => Object::Name(actual_name.clone().into_bytes()),
-                "FirstChar" => 32_i64,
-                "LastChar" => 255_i64,
-                "Widths" => widths,
+                "Subtype" => if is_cff { "CIDFontType0" } else { "CIDFontType2" },
+                "BaseFont" => base_font.clone(),
+                "CIDSystemInfo" => dictionary! {
+                    "Registry" => Object::string_literal("Adobe"),
+                    "Ordering" => Object::string_literal("Identity"),
+                    "Supplement" => 0_i64,
+                },
                 "FontDescriptor" => descriptor_id,
-                "Encoding" => "WinAnsiEncoding",
+                "W" => widths,
+            };
+            if !is_cff {
+                cid_font.set("CIDToGIDMap", "Identity");
+            }
+            let cid_font_id = doc.add_object(cid_font);
+
+            let tounicode_id =
+                doc.add_object(Stream::new(dictionary! {}, build_tounicode_cmap(&glyphs)));
+
+            let font_id = doc.add_object(dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type0",
+                "BaseFont" => base_font,
+                "Encoding" => "Identity-H",
+                "DescendantFonts" => vec![cid_font_id.into()],
                 "ToUnicode" => tounicode_id,
             });
 
             font_dict.set(resource_name.as_str(), font_id);
-            embedded_fonts.insert(actual_name, font_id);
         }
 
         // ── XObject images ─────────────────────────────────────────
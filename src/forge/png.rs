@@ -4,28 +4,100 @@
 //! RGB PNG images using the `image` and `imageproc` crates.
 
 use std::cmp::max;
-use std::collections::HashMap;
 use std::sync::Arc;
 
-use ab_glyph::{Font, ScaleFont};
+use ab_glyph::{Font, ScaleFont, point};
 use base64::{Engine as _, engine::general_purpose};
+use image::Pixel;
 use image::{
     ImageBuffer, Rgb, RgbImage, Rgba, RgbaImage,
     imageops::{overlay, rotate90, rotate180, rotate270},
 };
+use imageproc::definitions::Clamp;
 use imageproc::drawing::{
-    draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_polygon_mut,
-    draw_text_mut,
+    Canvas, draw_filled_circle_mut, draw_filled_ellipse_mut, draw_filled_rect_mut, draw_polygon_mut,
 };
+use imageproc::pixelops::weighted_sum;
 use imageproc::point::Point;
 use imageproc::rect::Rect;
 use rxing::common::BitMatrix;
-use rxing::{BarcodeFormat, EncodeHintType, EncodeHintValue, EncodeHints};
 
-use super::{barcode_1d_format, barcode_cache};
-use crate::engine::{Barcode1DKind, FontManager, ZplForgeBackend};
+use super::databar::{self, DataBarOptions, DataBarRow};
+use super::datamatrix::DataMatrixOptions;
+use super::linear::{self, HriGroup, LinearOptions};
+use super::maxicode;
+use super::postal::{self, PostalBar};
+use super::qr::QrOptions;
+use super::stacked::{self, Pdf417Options, Stacked};
+use super::{
+    encode_aztec, encode_barcode_1d, encode_code39, encode_code128, encode_datamatrix,
+    encode_maxicode, encode_qr, encode_stacked, encode_tlc39,
+};
+use crate::engine::font::{BitmapText, ShapedText};
+use crate::engine::{
+    Barcode1DKind, FontManager, PostalKind, QrSegment, TextDecoration, ZplForgeBackend,
+};
 use crate::{ZplError, ZplResult};
 
+/// Glyph coverage below which a pixel is left untouched.
+const MIN_COVERAGE: f32 = 0.01;
+
+/// Rasterizes shaped text from `(x, y)` with the baseline `baseline` dots
+/// below it, each run in its own font, blending `color` by glyph coverage
+/// like `imageproc::drawing::draw_text_mut`.
+fn draw_shaped_text<C>(
+    canvas: &mut C,
+    color: C::Pixel,
+    x: i32,
+    y: i32,
+    baseline: f32,
+    shaped: &ShapedText,
+) where
+    C: Canvas,
+    <C::Pixel as Pixel>::Subpixel: Into<f32> + Clamp<f32>,
+{
+    let (width, height) = (canvas.width() as i32, canvas.height() as i32);
+    let mut pen = 0.0;
+    for run in &shaped.runs {
+        let mut run_pen = 0;
+        for glyph in &run.glyphs {
+            let gx = pen + (run_pen + glyph.x_offset) as f32 * run.scale_x;
+            let gy = baseline - glyph.y_offset as f32 * run.scale_y;
+            run_pen += glyph.x_advance;
+            let positioned = glyph.id.with_scale_and_position(run.px, point(gx, gy));
+            let Some(outline) = run.font.outline_glyph(positioned) else {
+                continue;
+            };
+            let bounds = outline.px_bounds();
+            let (x_shift, y_shift) = (
+                x + bounds.min.x.round() as i32,
+                y + bounds.min.y.round() as i32,
+            );
+            outline.draw(|px, py, coverage| {
+                let (ix, iy) = (px as i32 + x_shift, py as i32 + y_shift);
+                // Accumulation residue of the rasterizer can leave a faint box
+                // of near-zero coverage over some glyphs (combining marks).
+                if coverage < MIN_COVERAGE
+                    || !(0..width).contains(&ix)
+                    || !(0..height).contains(&iy)
+                {
+                    return;
+                }
+                let mut pixel = canvas.get_pixel(ix as u32, iy as u32);
+                let coverage = coverage.clamp(0.0, 1.0);
+                if C::Pixel::HAS_ALPHA {
+                    let color = color.map_with_alpha(|c| c, |a| Clamp::clamp(a.into() * coverage));
+                    pixel.blend(&color);
+                } else {
+                    pixel = weighted_sum(pixel, color, 1.0 - coverage, coverage);
+                }
+                canvas.draw_pixel(ix as u32, iy as u32, pixel);
+            });
+        }
+        pen += run.width();
+    }
+}
+
 /// A rendering backend that produces PNG images.
 ///
 /// This backend uses the `image` and `imageproc` crates to draw ZPL instructions
@@ -33,6 +105,8 @@ use crate::{ZplError, ZplResult};
 pub struct PngBackend {
     canvas: RgbImage,
     font_manager: Option<Arc<FontManager>>,
+    /// Dots per inch of the current page, for fixed-size symbols.
+    resolution: f32,
 }
 
 impl Default for PngBackend {
@@ -47,6 +121,7 @@ impl PngBackend {
         Self {
             canvas: ImageBuffer::new(0, 0),
             font_manager: None,
+            resolution: 203.2,
         }
     }
 
@@ -151,19 +226,20 @@ impl PngBackend {
         width: Option<u32>,
     ) -> u32 {
         match self.font_manager.as_ref() {
-            Some(fm) => fm.measure_text(font_char, height, width, text),
+            Some(fm) => fm.measure_text(font_char, height, width, text, 0),
             None => 0,
         }
     }
 }
 
 impl ZplForgeBackend for PngBackend {
-    fn setup_page(&mut self, width: f64, height: f64, _resolution: f32) {
+    fn setup_page(&mut self, width: f64, height: f64, resolution: f32) {
         // Safety limit to avoid OOM: 8192x8192 is enough for most labels
         const MAX_DIM: u32 = 8192;
         let w = (width as u32).min(MAX_DIM);
         let h = (height as u32).min(MAX_DIM);
         self.canvas = ImageBuffer::from_pixel(w, h, Rgb([255, 255, 255]));
+        self.resolution = if resolution == 0.0 { 203.2 } else { resolution };
     }
 
     fn setup_font_manager(&mut self, font_manager: &FontManager) {
@@ -179,8 +255,9 @@ impl ZplForgeBackend for PngBackend {
         width: Option<u32>,
         orientation: char,
         text: &str,
-        _reverse_print: bool,
+        reverse_print: bool,
         color: Option<String>,
+        decoration: TextDecoration,
     ) -> ZplResult<()> {
         if text.is_empty() {
             return Ok(());
@@ -188,30 +265,35 @@ impl ZplForgeBackend for PngBackend {
 
         let fm = self
             .font_manager
-            .as_ref()
+            .clone()
             .ok_or_else(|| ZplError::FontError("Font manager not initialized".into()))?;
-        let (font_arc, layout) = fm
+        let text_color = self.parse_hex_color(&color);
+        if let Some(lines) = fm.decoration_rects(font, height, width, text, &decoration) {
+            self.fill_bitmap_text(x, y, orientation, &lines, text_color, reverse_print);
+        }
+        if let Some(bitmap) = fm.bitmap_text(font, height, width, text, decoration.letter_spacing) {
+            self.fill_bitmap_text(x, y, orientation, &bitmap, text_color, reverse_print);
+            return Ok(());
+        }
+        let (_, layout) = fm
             .text_layout(font, height, width)
             .ok_or_else(|| ZplError::FontError(format!("Font not found: {}", font)))?;
-        let font_data = font_arc.clone();
-        let scale = layout.px;
-
-        // imageproc places the baseline at `y + ascent`; shift so capital
-        // letters start exactly at the ZPL cell top (Zebra behavior).
-        let ascent = font_data.as_scaled(scale).ascent();
-        let y_offset = (layout.baseline - ascent).round() as i32;
+        let shaped = fm
+            .shape_text(font, height, width, text, decoration.letter_spacing)
+            .ok_or_else(|| ZplError::FontError(format!("Font not found: {}", font)))?;
 
-        let text_color = self.parse_hex_color(&color);
+        // The baseline sits at `layout.baseline` below the ZPL cell top so
+        // capital letters start exactly there (Zebra behavior); fallback runs
+        // share that baseline.
 
         if !matches!(orientation, 'R' | 'I' | 'B') {
-            draw_text_mut(
+            draw_shaped_text(
                 &mut self.canvas,
                 text_color,
                 x as i32,
-                y as i32 + y_offset,
-                scale,
-                &font_data,
-                text,
+                y as i32,
+                layout.baseline,
+                &shaped,
             );
             return Ok(());
         }
@@ -220,28 +302,30 @@ impl ZplForgeBackend for PngBackend {
         // non-transparent pixels so the background stays transparent.
         //
         // Ink can overflow the character cell on both sides: ascenders and
-        // accents rise above the cap line (`y_offset` is negative because the
-        // font ascent exceeds the cap height) and descenders can drop below
-        // `cell_h`. Pad the surface so nothing is clipped, then shift the blit
-        // anchor so the cell's top-left corner still lands exactly on (x, y).
-        let text_w = self.get_text_width(text, font, height, width).max(1);
+        // accents rise above the cap line (the ascent of every run's font
+        // exceeds the cap height) and descenders can drop below `cell_h`.
+        // Pad the surface so nothing is clipped, then shift the blit anchor
+        // so the cell's top-left corner still lands exactly on (x, y).
+        let text_w = (shaped.width().ceil() as u32).max(1);
         let font_h = (layout.cell_h.ceil() as u32).max(1);
-        let top_pad = (-y_offset).max(0) as u32;
+        let (ascent, descent) = shaped.runs.iter().fold((0.0f32, 0.0f32), |(a, d), run| {
+            let scaled = run.font.as_scaled(run.px);
+            (a.max(scaled.ascent()), d.min(scaled.descent()))
+        });
+        let top_pad = (ascent - layout.baseline).ceil().max(0.0) as u32;
         // `descent()` is negative: ink below the baseline reaches `baseline - descent`.
-        let descent = font_data.as_scaled(scale).descent();
         let ink_bottom = (layout.baseline - descent).ceil() as i32;
         let bottom_pad = (ink_bottom - font_h as i32).max(0) as u32;
         let mut tmp =
             RgbaImage::from_pixel(text_w, font_h + top_pad + bottom_pad, Rgba([0, 0, 0, 0]));
         let text_rgba = Rgba([text_color.0[0], text_color.0[1], text_color.0[2], 255]);
-        draw_text_mut(
+        draw_shaped_text(
             &mut tmp,
             text_rgba,
             0,
-            y_offset + top_pad as i32,
-            scale,
-            &font_data,
-            text,
+            top_pad as i32,
+            layout.baseline,
+            &shaped,
         );
 
         // Rotation moves each pad to a different edge; only pads landing on
@@ -496,29 +580,12 @@ impl ZplForgeBackend for PngBackend {
         module_width: u32,
         interpretation_line: char,
         interpretation_line_above: char,
-        _check_digit: char,
-        _mode: char,
+        check_digit: char,
+        mode: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let (clean_data, hint_val) = if let Some(stripped) = data.strip_prefix(">:") {
-            (stripped, Some("B"))
-        } else if let Some(stripped) = data.strip_prefix(">;") {
-            (stripped, Some("C"))
-        } else if let Some(stripped) = data.strip_prefix(">9") {
-            (stripped, Some("A"))
-        } else {
-            (data, Some("B")) // Standard default is Code Set B
-        };
-
-        let hints = hint_val.map(|v| {
-            let mut h = HashMap::new();
-            h.insert(
-                EncodeHintType::FORCE_CODE_SET,
-                EncodeHintValue::ForceCodeSet(v.to_string()),
-            );
-            EncodeHints::from(h)
-        });
+        let symbol = encode_code128(mode, check_digit, module_width, data)?;
 
         self.draw_1d_barcode(
             x,
@@ -526,13 +593,13 @@ impl ZplForgeBackend for PngBackend {
             orientation,
             height,
             module_width,
-            clean_data,
-            BarcodeFormat::CODE_128,
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            hints,
-            hint_val.unwrap_or(""),
+            &linear::hri_groups(None, &symbol.text, &symbol.matrix),
+            &[],
         )
     }
 
@@ -541,38 +608,23 @@ impl ZplForgeBackend for PngBackend {
         x: u32,
         y: u32,
         orientation: char,
-        _model: u32,
+        model: u32,
         magnification: u32,
         error_correction: char,
         _mask: u32,
         data: &str,
+        segments: &[QrSegment],
+        structured_append: Option<(u32, u32, u8)>,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let level = match error_correction {
-            'L' => "L",
-            'M' => "M",
-            'Q' => "Q",
-            'H' => "H",
-            _ => "M",
+        let options = QrOptions {
+            model,
+            error_correction,
+            structured_append,
+            gs1,
         };
-
-        let mut hints = HashMap::new();
-        hints.insert(
-            EncodeHintType::ERROR_CORRECTION,
-            EncodeHintValue::ErrorCorrection(level.to_string()),
-        );
-        hints.insert(
-            EncodeHintType::MARGIN,
-            EncodeHintValue::Margin("0".to_owned()),
-        );
-        let hints: EncodeHints = hints.into();
-
-        let bit_matrix = barcode_cache::encode_cached(
-            BarcodeFormat::QR_CODE,
-            data,
-            &format!("ec:{}", level),
-            Some(&hints),
-        )?;
+        let bit_matrix = encode_qr(&options, data, segments)?;
 
         let mag = max(magnification, 1);
         self.fill_matrix_cells(x, y, orientation, mag, mag, &bit_matrix, reverse_print);
@@ -585,10 +637,23 @@ impl ZplForgeBackend for PngBackend {
         y: u32,
         orientation: char,
         module_size: u32,
+        _quality: u32,
+        columns: u32,
+        rows: u32,
+        rectangular: bool,
+        escape: char,
         data: &str,
+        gs1: bool,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let bit_matrix = barcode_cache::encode_cached(BarcodeFormat::DATA_MATRIX, data, "", None)?;
+        let options = DataMatrixOptions {
+            columns,
+            rows,
+            rectangular,
+            escape,
+            gs1,
+        };
+        let bit_matrix = encode_datamatrix(&options, data)?;
 
         let m = max(module_size, 1);
         self.fill_matrix_cells(x, y, orientation, m, m, &bit_matrix, reverse_print);
@@ -603,30 +668,126 @@ impl ZplForgeBackend for PngBackend {
         row_height: u32,
         module_width: u32,
         security_level: u32,
+        columns: u32,
+        rows: u32,
+        truncate: bool,
+        macro_origins: &[Option<(u32, u32)>],
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
-        let mut hints = HashMap::new();
-        hints.insert(
-            EncodeHintType::ERROR_CORRECTION,
-            EncodeHintValue::ErrorCorrection(security_level.min(8).to_string()),
-        );
-        hints.insert(
-            EncodeHintType::MARGIN,
-            EncodeHintValue::Margin("0".to_owned()),
-        );
-        let hints: EncodeHints = hints.into();
+        let options = Pdf417Options {
+            security_level,
+            columns,
+            rows,
+            truncate,
+        };
+        let symbols = encode_stacked(Stacked::Pdf417(options), x, y, macro_origins, data)?;
 
-        let bit_matrix = barcode_cache::encode_cached(
-            BarcodeFormat::PDF_417,
-            data,
-            &format!("ec:{}", security_level.min(8)),
-            Some(&hints),
-        )?;
+        let cw = max(module_width, 1);
+        let ch = max(row_height, 1);
+        for (x, y, bit_matrix) in symbols {
+            self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        }
+        Ok(())
+    }
+
+    fn draw_micro_pdf417(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        mode: u32,
+        macro_origins: &[Option<(u32, u32)>],
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbols = encode_stacked(Stacked::MicroPdf417 { mode }, x, y, macro_origins, data)?;
 
         let cw = max(module_width, 1);
         let ch = max(row_height, 1);
-        self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        for (x, y, bit_matrix) in symbols {
+            self.fill_matrix_cells(x, y, orientation, cw, ch, &bit_matrix, reverse_print);
+        }
+        Ok(())
+    }
+
+    fn draw_tlc39(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        module_width: u32,
+        ratio: f64,
+        height: u32,
+        micro_module_width: u32,
+        row_height: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = encode_tlc39(
+            data,
+            module_width,
+            ratio,
+            height,
+            micro_module_width,
+            row_height,
+        )?;
+        let ((lx, ly), micro_origin) = symbol.layout.origins(x, y, orientation);
+
+        self.draw_1d_barcode(
+            lx,
+            ly,
+            orientation,
+            height,
+            module_width,
+            symbol.linear.column_width,
+            &symbol.linear.matrix,
+            reverse_print,
+            'N',
+            'N',
+            &[],
+            &[],
+        )?;
+        if let (Some(matrix), Some((mx, my))) = (&symbol.micro, micro_origin) {
+            self.fill_matrix_cells(
+                mx,
+                my,
+                orientation,
+                max(micro_module_width, 1),
+                max(row_height, 1),
+                matrix,
+                reverse_print,
+            );
+        }
+        Ok(())
+    }
+
+    fn draw_codablock(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        row_height: u32,
+        module_width: u32,
+        security: char,
+        columns: u32,
+        rows: u32,
+        mode: char,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = stacked::encode_codablock(
+            mode,
+            security,
+            columns,
+            rows,
+            row_height,
+            module_width,
+            data,
+        )?;
+        self.draw_module_rows(x, y, orientation, module_width, &symbol, reverse_print);
         Ok(())
     }
 
@@ -635,27 +796,30 @@ impl ZplForgeBackend for PngBackend {
         x: u32,
         y: u32,
         orientation: char,
-        _check_digit: char,
+        check_digit: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
+        let symbol = encode_code39(data, check_digit, module_width, ratio)?;
+
         self.draw_1d_barcode(
             x,
             y,
             orientation,
             height,
             module_width,
-            data,
-            BarcodeFormat::CODE_39,
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            None,
-            "",
+            &linear::hri_groups(None, &symbol.text, &symbol.matrix),
+            &[],
         )
     }
 
@@ -667,27 +831,176 @@ impl ZplForgeBackend for PngBackend {
         orientation: char,
         height: u32,
         module_width: u32,
+        ratio: f64,
         interpretation_line: char,
         interpretation_line_above: char,
+        check_digit: char,
+        print_check_digit: char,
         data: &str,
         reverse_print: bool,
     ) -> ZplResult<()> {
+        let options = LinearOptions {
+            module_width,
+            ratio,
+            check_digit,
+            print_check_digit: print_check_digit == 'Y',
+        };
+        let symbol = encode_barcode_1d(kind, data, &options)?;
+
         self.draw_1d_barcode(
             x,
             y,
             orientation,
             height,
             module_width,
-            data,
-            barcode_1d_format(kind),
+            symbol.column_width,
+            &symbol.matrix,
             reverse_print,
             interpretation_line,
             interpretation_line_above,
-            None,
-            "",
+            &linear::hri_groups(Some(kind), &symbol.text, &symbol.matrix),
+            &linear::extended_guards(Some(kind), &symbol.text, &symbol.matrix),
+        )
+    }
+
+    fn draw_aztec(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        magnification: u32,
+        extended_channel: char,
+        error_control: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let bit_matrix = encode_aztec(data, extended_channel, error_control)?;
+
+        let mag = max(magnification, 1);
+        self.fill_matrix_cells(x, y, orientation, mag, mag, &bit_matrix, reverse_print);
+        Ok(())
+    }
+
+    fn draw_maxicode(
+        &mut self,
+        x: u32,
+        y: u32,
+        mode: u32,
+        symbol_number: u32,
+        symbol_count: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let bit_matrix = encode_maxicode(mode, symbol_number, symbol_count, data)?;
+        let geometry = maxicode::Geometry::new(self.resolution as f64);
+
+        let draw_op = |img: &mut RgbImage, px: i32, py: i32| {
+            let dark = Rgb([0, 0, 0]);
+            let light = Rgb([255, 255, 255]);
+            let to_point =
+                |(hx, hy): (f64, f64)| Point::new(px + hx.round() as i32, py + hy.round() as i32);
+
+            for row in 0..maxicode::ROWS {
+                for col in 0..maxicode::COLUMNS {
+                    if bit_matrix.get(col, row) {
+                        let hexagon = geometry.hexagon(col, row).map(to_point);
+                        draw_polygon_mut(img, &hexagon, dark);
+                    }
+                }
+            }
+
+            // Bullseye finder: concentric discs, alternating dark and light.
+            let center = to_point(geometry.bullseye_center());
+            for (i, radius) in geometry.bullseye_radii().into_iter().enumerate() {
+                let color = if i % 2 == 0 { dark } else { light };
+                draw_filled_circle_mut(img, (center.x, center.y), radius.round() as i32, color);
+            }
+        };
+
+        self.draw_wrapper(
+            x,
+            y,
+            geometry.width.ceil() as u32,
+            geometry.height.ceil() as u32,
+            reverse_print,
+            draw_op,
         )
     }
 
+    fn draw_databar(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        symbology: u32,
+        magnification: u32,
+        separator_height: u32,
+        height: u32,
+        segment_width: u32,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let options = DataBarOptions {
+            magnification,
+            separator_height,
+            bar_height: height,
+            segment_width,
+        };
+        let rows = databar::encode(symbology, data, &options)?;
+        self.draw_module_rows(x, y, orientation, magnification, &rows, reverse_print);
+        Ok(())
+    }
+
+    fn draw_postal_barcode(
+        &mut self,
+        kind: PostalKind,
+        x: u32,
+        y: u32,
+        orientation: char,
+        height: u32,
+        module_width: u32,
+        interpretation_line: char,
+        interpretation_line_above: char,
+        data: &str,
+        reverse_print: bool,
+    ) -> ZplResult<()> {
+        let symbol = postal::encode(kind, data)?;
+        self.draw_postal_bars(
+            x,
+            y,
+            orientation,
+            height,
+            module_width,
+            &symbol.bars,
+            reverse_print,
+        );
+
+        if interpretation_line == 'Y' {
+            let mw = max(module_width, 1);
+            let pitch = mw + postal::bar_gap(mw);
+            let width = symbol.bars.len() as i32 * pitch as i32;
+            let full_h = match orientation {
+                'R' | 'B' => width as u32,
+                _ => height,
+            };
+            let hri = [HriGroup {
+                text: symbol.text,
+                start: 0,
+                end: width,
+            }];
+            self.draw_interpretation_line(
+                x,
+                y,
+                full_h,
+                module_width,
+                1,
+                &hri,
+                interpretation_line_above,
+            )?;
+        }
+        Ok(())
+    }
+
     fn draw_graphic_diagonal(
         &mut self,
         x: u32,
@@ -800,6 +1113,83 @@ impl PngBackend {
         }
     }
 
+    /// Fills the dots of bitmap-font text, rotated by `orientation` around
+    /// the top-left corner (x, y) like [`Self::fill_matrix_cells`].
+    fn fill_bitmap_text(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        bitmap: &BitmapText,
+        color: Rgb<u8>,
+        reverse_print: bool,
+    ) {
+        let (full_w, full_h) = (bitmap.width as i32, bitmap.height as i32);
+        for &(lx, ly, w, h) in &bitmap.rects {
+            let (lx, ly) = (lx as i32, ly as i32);
+            let rect = match orientation {
+                'R' => Rect::at(x as i32 + full_h - (ly + h as i32), y as i32 + lx).of_size(h, w),
+                'I' => Rect::at(
+                    x as i32 + full_w - (lx + w as i32),
+                    y as i32 + full_h - (ly + h as i32),
+                )
+                .of_size(w, h),
+                'B' => Rect::at(x as i32 + ly, y as i32 + full_w - (lx + w as i32)).of_size(h, w),
+                _ => Rect::at(x as i32 + lx, y as i32 + ly).of_size(w, h),
+            };
+            if reverse_print {
+                self.invert_rect(rect);
+            } else {
+                draw_filled_rect_mut(&mut self.canvas, rect, color);
+            }
+        }
+    }
+
+    /// Maps a local bar rectangle inside a 1-D symbol of `bw` × `bh` dots to
+    /// canvas coordinates according to the requested orientation.
+    #[allow(clippy::too_many_arguments)]
+    fn transform_1d_rect(
+        orientation: char,
+        x: u32,
+        y: u32,
+        lx: i32,
+        ly: i32,
+        w: u32,
+        h: u32,
+        bw: u32,
+        bh: u32,
+    ) -> Rect {
+        match orientation {
+            'R' => {
+                let new_x = bh as i32 - (ly + h as i32);
+                let new_y = lx;
+                Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(h, w)
+            }
+            'I' => {
+                let new_x = bw as i32 - (lx + w as i32);
+                let new_y = bh as i32 - (ly + h as i32);
+                Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(w, h)
+            }
+            'B' => {
+                let new_x = ly;
+                let new_y = bw as i32 - (lx + w as i32);
+                Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(h, w)
+            }
+            _ => Rect::at(x as i32 + lx, y as i32 + ly).of_size(w, h),
+        }
+    }
+
+    /// Fills a bar rectangle, or inverts the canvas under it for `^FR`.
+    fn fill_bar(&mut self, rect: Rect, reverse_print: bool) {
+        if reverse_print {
+            self.invert_rect(rect);
+        } else {
+            draw_filled_rect_mut(&mut self.canvas, rect, Rgb([0, 0, 0]));
+        }
+    }
+
+    /// Paints the first row of a 1-D bit matrix as bars `height` dots tall,
+    /// followed by the interpretation-line groups.
     #[allow(clippy::too_many_arguments)]
     fn draw_1d_barcode(
         &mut self,
@@ -808,74 +1198,189 @@ impl PngBackend {
         orientation: char,
         height: u32,
         module_width: u32,
-        data: &str,
-        format: BarcodeFormat,
+        column_width: u32,
+        bit_matrix: &BitMatrix,
         reverse_print: bool,
         interpretation_line: char,
         interpretation_line_above: char,
-        hints: Option<EncodeHints>,
-        hints_key: &str,
+        hri: &[HriGroup],
+        guards: &[(i32, i32)],
     ) -> ZplResult<()> {
-        let bit_matrix = barcode_cache::encode_cached(format, data, hints_key, hints.as_ref())?;
-
-        let mw = max(module_width, 1);
+        let mw = max(column_width, 1);
         let bh = height;
         let bw = bit_matrix.getWidth() * mw;
-
-        let (full_w, full_h) = match orientation {
-            'N' | 'I' => (bw, bh),
-            'R' | 'B' => (bh, bw),
-            _ => (bw, bh),
+        // Retail guard bars reach down between the interpretation groups,
+        // which are only laid out under the bars of an unrotated symbol.
+        let extension =
+            if orientation == 'N' && interpretation_line == 'Y' && interpretation_line_above != 'Y'
+            {
+                let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
+                gap + text_h / 2
+            } else {
+                0
+            };
+        let frame_h = if guards.is_empty() {
+            bh
+        } else {
+            bh + extension
         };
-
-        let transform_rect = |lx: i32, ly: i32, w: u32, h: u32| -> Rect {
-            match orientation {
-                'N' => Rect::at(x as i32 + lx, y as i32 + ly).of_size(w, h),
-                'R' => {
-                    let new_x = bh as i32 - (ly + h as i32);
-                    let new_y = lx;
-                    Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(h, w)
-                }
-                'I' => {
-                    let new_x = bw as i32 - (lx + w as i32);
-                    let new_y = bh as i32 - (ly + h as i32);
-                    Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(w, h)
-                }
-                'B' => {
-                    let new_x = ly;
-                    let new_y = bw as i32 - (lx + w as i32);
-                    Rect::at(x as i32 + new_x, y as i32 + new_y).of_size(h, w)
-                }
-                _ => Rect::at(x as i32 + lx, y as i32 + ly).of_size(w, h),
+        let bar_h = |gx: u32| {
+            if guards.iter().any(|&(s, e)| (s..e).contains(&(gx as i32))) {
+                frame_h
+            } else {
+                bh
             }
         };
 
+        let full_h = match orientation {
+            'R' | 'B' => bw,
+            _ => bh,
+        };
+
         for gx in 0..bit_matrix.getWidth() {
             if bit_matrix.get(gx, 0) {
-                let rect = transform_rect((gx * mw) as i32, 0, mw, bh);
-                if reverse_print {
-                    self.invert_rect(rect);
-                } else {
-                    draw_filled_rect_mut(&mut self.canvas, rect, Rgb([0, 0, 0]));
-                }
+                let rect = Self::transform_1d_rect(
+                    orientation,
+                    x,
+                    y,
+                    (gx * mw) as i32,
+                    0,
+                    mw,
+                    bar_h(gx),
+                    bw,
+                    frame_h,
+                );
+                self.fill_bar(rect, reverse_print);
             }
         }
 
         if interpretation_line == 'Y' {
-            let font_char = '0';
-            let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
-            let text_y = if interpretation_line_above == 'Y' {
-                y.saturating_sub(text_h + gap)
-            } else {
-                y + full_h + gap
-            };
+            self.draw_interpretation_line(
+                x,
+                y,
+                full_h,
+                module_width,
+                mw,
+                hri,
+                interpretation_line_above,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Paints height-modulated postal bars: every bar is `module_width` dots
+    /// wide, and its vertical extent is a fraction of `height`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_postal_bars(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        height: u32,
+        module_width: u32,
+        bars: &[PostalBar],
+        reverse_print: bool,
+    ) {
+        let mw = max(module_width, 1);
+        let pitch = mw + postal::bar_gap(mw);
+        let bh = height;
+        let bw = (bars.len() as u32 * pitch).saturating_sub(pitch - mw);
+
+        for (i, bar) in bars.iter().enumerate() {
+            let (top, bottom) = bar.extent();
+            let ly = (top * bh as f64).round() as u32;
+            let lh = ((bottom * bh as f64).round() as u32)
+                .saturating_sub(ly)
+                .max(1);
+            let rect = Self::transform_1d_rect(
+                orientation,
+                x,
+                y,
+                (i as u32 * pitch) as i32,
+                ly as i32,
+                mw,
+                lh,
+                bw,
+                bh,
+            );
+            self.fill_bar(rect, reverse_print);
+        }
+    }
+
+    /// Paints a stacked symbol row by row (GS1 DataBar, CODABLOCK F): each
+    /// dark run is a bar `module_width` dots per module wide and as tall as
+    /// its row.
+    fn draw_module_rows(
+        &mut self,
+        x: u32,
+        y: u32,
+        orientation: char,
+        module_width: u32,
+        rows: &[DataBarRow],
+        reverse_print: bool,
+    ) {
+        let mw = max(module_width, 1);
+        let bw = rows
+            .iter()
+            .map(|row| row.modules.len() as u32)
+            .max()
+            .unwrap_or(0)
+            * mw;
+        let bh: u32 = rows.iter().map(|row| row.height).sum();
+
+        let mut ly = 0;
+        for row in rows {
+            for (start, len) in row.dark_runs() {
+                let rect = Self::transform_1d_rect(
+                    orientation,
+                    x,
+                    y,
+                    (start as u32 * mw) as i32,
+                    ly as i32,
+                    len as u32 * mw,
+                    row.height,
+                    bw,
+                    bh,
+                );
+                self.fill_bar(rect, reverse_print);
+            }
+            ly += row.height;
+        }
+    }
+
+    /// Draws the interpretation-line groups above or below a 1-D symbol whose
+    /// bars span `full_h` dots from `(x, y)`. Group spans are in matrix columns
+    /// of `column_width` dots; the text size follows `module_width`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_interpretation_line(
+        &mut self,
+        x: u32,
+        y: u32,
+        full_h: u32,
+        module_width: u32,
+        column_width: u32,
+        hri: &[HriGroup],
+        interpretation_line_above: char,
+    ) -> ZplResult<()> {
+        let font_char = '0';
+        let (text_h, gap) = crate::engine::font::interpretation_metrics(module_width);
+        let text_y = if interpretation_line_above == 'Y' {
+            y.saturating_sub(text_h + gap)
+        } else {
+            y + full_h + gap
+        };
 
-            let text_width = self.get_text_width(data, font_char, Some(text_h), None);
-            let text_x = if full_w > text_width {
-                x + (full_w - text_width) / 2
+        for group in hri {
+            let span_x = group.start * column_width as i32;
+            let span_w = (group.end - group.start).max(0) * column_width as i32;
+            let text_width = self.get_text_width(&group.text, font_char, Some(text_h), None) as i32;
+            let offset = if span_w > text_width {
+                (span_w - text_width) / 2
             } else {
-                x
+                0
             };
+            let text_x = (x as i32 + span_x + offset).max(0) as u32;
 
             self.draw_text(
                 text_x,
@@ -884,9 +1389,10 @@ impl PngBackend {
                 Some(text_h),
                 None,
                 'N',
-                data,
+                &group.text,
                 false,
                 None,
+                TextDecoration::default(),
             )?;
         }
 
@@ -0,0 +1,267 @@
+//! Height-modulated postal barcodes shared by both backends.
+//!
+//! Postal symbologies carry their data in bar heights rather than widths:
+//! every bar has the same width and pitch, and only its vertical extent
+//! changes. Encoders here produce a sequence of [`PostalBar`]s that the
+//! backends draw with their postal bar renderer.
+
+use std::sync::OnceLock;
+
+use crate::engine::PostalKind;
+use crate::{ZplError, ZplResult};
+
+/// Vertical extent of one postal bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PostalBar {
+    /// Spans the full symbol height.
+    Full,
+    /// POSTNET/PLANET short bar, standing on the baseline.
+    Half,
+    /// IMb bar extending from the top down through the tracker.
+    Ascender,
+    /// IMb bar extending from the tracker down to the bottom.
+    Descender,
+    /// IMb middle third only.
+    Tracker,
+}
+
+impl PostalBar {
+    /// Top and bottom of the bar as fractions of the full height.
+    pub(crate) fn extent(self) -> (f64, f64) {
+        match self {
+            PostalBar::Full => (0.0, 1.0),
+            PostalBar::Half => (0.6, 1.0),
+            PostalBar::Ascender => (0.0, 2.0 / 3.0),
+            PostalBar::Descender => (1.0 / 3.0, 1.0),
+            PostalBar::Tracker => (1.0 / 3.0, 2.0 / 3.0),
+        }
+    }
+}
+
+/// An encoded postal symbol ready to draw.
+#[derive(Debug, Clone)]
+pub(crate) struct PostalSymbol {
+    /// Bars from left to right.
+    pub bars: Vec<PostalBar>,
+    /// Text printed in the interpretation line.
+    pub text: String,
+}
+
+/// Space between bars for a given bar width: the USPS specifications put
+/// bars at roughly 2.25 bar widths pitch.
+pub(crate) fn bar_gap(module_width: u32) -> u32 {
+    (module_width.max(1) * 5).div_ceil(4)
+}
+
+/// Encodes `data` in the given postal symbology.
+pub(crate) fn encode(kind: PostalKind, data: &str) -> ZplResult<PostalSymbol> {
+    let data: String = data.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    if data.is_empty() || !data.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ZplError::BackendError(format!(
+            "Barcode Generation Error: postal barcode data must be numeric, got {:?}",
+            data
+        )));
+    }
+    match kind {
+        PostalKind::Postnet => Ok(postnet(&data, false)),
+        PostalKind::Planet => Ok(postnet(&data, true)),
+        PostalKind::IntelligentMail => intelligent_mail(&data),
+    }
+}
+
+// ─── POSTNET / PLANET ───────────────────────────────────────────────────────
+
+/// POSTNET digit patterns (weights 7, 4, 2, 1, 0), `1` = full bar. PLANET
+/// uses the complement.
+const POSTNET_PATTERNS: [u8; 10] = [
+    0b11000, 0b00011, 0b00101, 0b00110, 0b01001, 0b01010, 0b01100, 0b10001, 0b10010, 0b10100,
+];
+
+/// POSTNET and PLANET: framing bars around five bars per digit, with a
+/// Mod 10 check digit completing the digit sum.
+fn postnet(data: &str, planet: bool) -> PostalSymbol {
+    let sum: u32 = data.bytes().map(|b| (b - b'0') as u32).sum();
+    let check = char::from(b'0' + ((10 - sum % 10) % 10) as u8);
+    let full = format!("{}{}", data, check);
+
+    let mut bars = Vec::with_capacity(full.len() * 5 + 2);
+    bars.push(PostalBar::Full);
+    for b in full.bytes() {
+        let pattern = POSTNET_PATTERNS[(b - b'0') as usize];
+        for i in (0..5).rev() {
+            let tall = (pattern >> i & 1 == 1) != planet;
+            bars.push(if tall {
+                PostalBar::Full
+            } else {
+                PostalBar::Half
+            });
+        }
+    }
+    bars.push(PostalBar::Full);
+    PostalSymbol { bars, text: full }
+}
+
+// ─── USPS Intelligent Mail (USPS-B-3200) ────────────────────────────────────
+
+/// Character index (A-J = 0-9) feeding each bar's descender.
+const IMB_DESCENDER_CHAR: [usize; 65] = [
+    7, 1, 9, 5, 8, 0, 2, 4, 6, 3, 5, 8, 9, 7, 3, 0, 6, 1, 7, 4, 6, 8, 9, 2, 5, 1, 7, 5, 4, 3, 8, 7,
+    6, 0, 2, 5, 4, 9, 3, 0, 1, 6, 8, 2, 0, 4, 5, 9, 6, 7, 5, 2, 6, 3, 8, 5, 1, 9, 8, 7, 4, 0, 2, 6,
+    3,
+];
+
+/// Character bit feeding each bar's descender.
+const IMB_DESCENDER_BIT: [u32; 65] = [
+    2, 10, 12, 5, 9, 1, 5, 4, 3, 9, 11, 5, 10, 1, 6, 3, 4, 1, 10, 0, 2, 11, 8, 6, 1, 12, 3, 8, 6,
+    4, 4, 11, 0, 6, 1, 9, 11, 5, 3, 7, 3, 10, 7, 11, 8, 2, 10, 3, 5, 8, 0, 3, 12, 11, 8, 4, 5, 1,
+    3, 0, 7, 12, 9, 8, 10,
+];
+
+/// Character index (A-J = 0-9) feeding each bar's ascender.
+const IMB_ASCENDER_CHAR: [usize; 65] = [
+    4, 0, 2, 6, 3, 5, 1, 9, 8, 7, 1, 2, 0, 6, 4, 8, 2, 9, 5, 3, 0, 1, 3, 7, 4, 6, 8, 9, 2, 0, 5, 1,
+    9, 4, 3, 8, 6, 7, 1, 2, 4, 3, 9, 5, 7, 8, 3, 0, 2, 1, 4, 0, 9, 1, 7, 0, 2, 4, 6, 3, 7, 1, 9, 5,
+    8,
+];
+
+/// Character bit feeding each bar's ascender.
+const IMB_ASCENDER_BIT: [u32; 65] = [
+    3, 0, 8, 11, 1, 12, 8, 11, 10, 6, 4, 12, 2, 7, 9, 6, 7, 9, 2, 8, 4, 0, 12, 7, 10, 9, 0, 7, 10,
+    5, 7, 9, 6, 8, 2, 12, 1, 4, 2, 0, 1, 5, 4, 6, 12, 1, 0, 9, 4, 7, 5, 10, 2, 6, 9, 11, 2, 12, 6,
+    7, 5, 11, 0, 3, 2,
+];
+
+/// Builds the "N of 13" character table: every 13-bit value with `n` bits
+/// set, palindromes at the end and other values next to their bit reversal.
+fn n_of_13_table(n: u32, len: usize) -> Vec<u16> {
+    let mut table = vec![0u16; len];
+    let (mut lower, mut upper) = (0, len - 1);
+    for value in 0u16..8192 {
+        if value.count_ones() != n {
+            continue;
+        }
+        let reverse = value.reverse_bits() >> 3;
+        if reverse < value {
+            continue;
+        }
+        if reverse == value {
+            table[upper] = value;
+            upper -= 1;
+        } else {
+            table[lower] = value;
+            table[lower + 1] = reverse;
+            lower += 2;
+        }
+    }
+    table
+}
+
+/// The 5-of-13 (1287 entries) and 2-of-13 (78 entries) character tables.
+fn imb_tables() -> &'static (Vec<u16>, Vec<u16>) {
+    static TABLES: OnceLock<(Vec<u16>, Vec<u16>)> = OnceLock::new();
+    TABLES.get_or_init(|| (n_of_13_table(5, 1287), n_of_13_table(2, 78)))
+}
+
+/// 11-bit frame check sequence over the 102-bit binary value.
+fn imb_crc(value: u128) -> u16 {
+    const POLY: u16 = 0x0F35;
+    let bytes = value.to_be_bytes();
+    let mut fcs: u16 = 0x07FF;
+    let mut step = |data: &mut u16| {
+        fcs = if (fcs ^ *data) & 0x400 != 0 {
+            (fcs << 1) ^ POLY
+        } else {
+            fcs << 1
+        } & 0x7FF;
+        *data <<= 1;
+    };
+    // The value occupies the low 13 bytes; the first holds only 6 bits.
+    let mut data = (bytes[3] as u16) << 5;
+    for _ in 2..8 {
+        step(&mut data);
+    }
+    for &byte in &bytes[4..] {
+        let mut data = (byte as u16) << 3;
+        for _ in 0..8 {
+            step(&mut data);
+        }
+    }
+    fcs
+}
+
+/// Intelligent Mail: a 20-digit tracking code followed by an optional 5, 9
+/// or 11-digit routing (ZIP) code, encoded in 65 four-state bars.
+fn intelligent_mail(data: &str) -> ZplResult<PostalSymbol> {
+    if !matches!(data.len(), 20 | 25 | 29 | 31) {
+        return Err(ZplError::BackendError(format!(
+            "Barcode Generation Error: Intelligent Mail needs 20, 25, 29 or 31 digits, got {}",
+            data.len()
+        )));
+    }
+    let (tracking, routing) = data.split_at(20);
+    if tracking.as_bytes()[1] > b'4' {
+        return Err(ZplError::BackendError(
+            "Barcode Generation Error: Intelligent Mail barcode identifier must end in 0-4".into(),
+        ));
+    }
+
+    // Binary conversion of the routing code, then the tracking digits.
+    let routing_value: u128 = routing.parse().unwrap_or(0);
+    let mut value = match routing.len() {
+        5 => routing_value + 1,
+        9 => routing_value + 100_001,
+        11 => routing_value + 1_000_100_001,
+        _ => 0,
+    };
+    let t = tracking.as_bytes();
+    value = value * 10 + (t[0] - b'0') as u128;
+    value = value * 5 + (t[1] - b'0') as u128;
+    for &b in &t[2..] {
+        value = value * 10 + (b - b'0') as u128;
+    }
+    let fcs = imb_crc(value);
+
+    // Codewords: J in base 636, A-I in base 1365.
+    let mut codewords = [0u32; 10];
+    codewords[9] = (value % 636) as u32 * 2;
+    value /= 636;
+    for cw in codewords[..9].iter_mut().rev() {
+        *cw = (value % 1365) as u32;
+        value /= 1365;
+    }
+    if fcs & 0x400 != 0 {
+        codewords[0] += 659;
+    }
+
+    let (table_5, table_2) = imb_tables();
+    let mut chars = [0u16; 10];
+    for (i, (&cw, ch)) in codewords.iter().zip(chars.iter_mut()).enumerate() {
+        let cw = cw as usize;
+        *ch = if cw < 1287 {
+            table_5[cw]
+        } else {
+            table_2[cw - 1287]
+        };
+        if fcs >> i & 1 == 1 {
+            *ch = !*ch & 0x1FFF;
+        }
+    }
+
+    let bars = (0..65)
+        .map(|i| {
+            let ascender = chars[IMB_ASCENDER_CHAR[i]] >> IMB_ASCENDER_BIT[i] & 1 == 1;
+            let descender = chars[IMB_DESCENDER_CHAR[i]] >> IMB_DESCENDER_BIT[i] & 1 == 1;
+            match (ascender, descender) {
+                (true, true) => PostalBar::Full,
+                (true, false) => PostalBar::Ascender,
+                (false, true) => PostalBar::Descender,
+                (false, false) => PostalBar::Tracker,
+            }
+        })
+        .collect();
+
+    Ok(PostalSymbol {
+        bars,
+        text: data.to_string(),
+    })
+}
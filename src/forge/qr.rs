@@ -0,0 +1,397 @@
+//! QR Code (`^BQ`) encoding for manual-mode segments, structured append and
+//! Model 1 symbols.
+//!
+//! Automatic-mode Model 2 data goes straight through `rxing`'s writer. The
+//! other cases need a bitstream `rxing` cannot be asked for, so it is built
+//! here from `rxing`'s segment encoders (one mode indicator and character
+//! count per `^FD` segment). Model 2 symbols are then laid out by `rxing`;
+//! Model 1 symbols (versions 1-14) are laid out natively: finder, timing
+//! and format patterns are shared with Model 2, but codewords sit in fixed
+//! 2x4 and 4x2 module blocks instead of the Model 2 zig-zag, and there are
+//! no alignment patterns. The extension pattern blocks of larger Model 1
+//! versions are left light.
+
+use rxing::common::cpp_essentials::ByteMatrix;
+use rxing::common::{BitArray, BitMatrix, CharacterSet};
+use rxing::qrcode::common::{ErrorCorrectionLevel, Mode, Version, VersionRef};
+use rxing::qrcode::encoder::{matrix_util, qrcode_encoder};
+
+use crate::engine::QrSegment;
+use crate::gs1;
+use crate::{ZplError, ZplResult};
+
+/// Format information mask of Model 1 symbols (0x5412 for Model 2).
+const MODEL1_FORMAT_MASK: u32 = 0x2825;
+/// BCH(15,5) generator polynomial of the format information.
+const FORMAT_POLY: u32 = 0x537;
+
+/// `^BQ` options that affect encoding.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct QrOptions {
+    /// QR Code model (1 or 2).
+    pub model: u32,
+    /// Error correction level (`H`, `Q`, `M` or `L`).
+    pub error_correction: char,
+    /// Structured append `(symbol number 1-16, symbol count, parity)`.
+    pub structured_append: Option<(u32, u32, u8)>,
+    /// Encode bracketed GS1 AI data behind an FNC1 in first position.
+    pub gs1: bool,
+}
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+pub(crate) fn ec_level(error_correction: char) -> ErrorCorrectionLevel {
+    match error_correction {
+        'L' => ErrorCorrectionLevel::L,
+        'Q' => ErrorCorrectionLevel::Q,
+        'H' => ErrorCorrectionLevel::H,
+        _ => ErrorCorrectionLevel::M,
+    }
+}
+
+/// Two-bit error correction indicator of the format information.
+fn ec_bits(level: ErrorCorrectionLevel) -> u32 {
+    match level {
+        ErrorCorrectionLevel::L => 1,
+        ErrorCorrectionLevel::Q => 3,
+        ErrorCorrectionLevel::H => 2,
+        _ => 0,
+    }
+}
+
+/// A segment resolved to its `rxing` mode, checked against that mode's
+/// character set.
+struct Segment<'a> {
+    mode: Mode,
+    data: &'a str,
+    /// Byte-mode text encoding (Latin-1 when possible, UTF-8 otherwise).
+    encoding: CharacterSet,
+}
+
+fn is_alphanumeric(c: char) -> bool {
+    qrcode_encoder::getAlphanumericCode(c as u32) >= 0
+}
+
+fn resolve<'a>(mode: char, data: &'a str) -> ZplResult<Segment<'a>> {
+    let encoding = if data.chars().all(|c| (c as u32) < 256) {
+        CharacterSet::ISO8859_1
+    } else {
+        CharacterSet::UTF8
+    };
+    let (mode, valid) = match mode {
+        'N' => (Mode::NUMERIC, data.chars().all(|c| c.is_ascii_digit())),
+        'A' => (Mode::ALPHANUMERIC, data.chars().all(is_alphanumeric)),
+        'K' => (Mode::KANJI, qrcode_encoder::isOnlyDoubleByteKanji(data)),
+        _ => (Mode::BYTE, true),
+    };
+    if !valid {
+        return Err(generation_error(format!(
+            "QR Code {:?} segment contains characters it cannot encode: {}",
+            mode, data
+        )));
+    }
+    Ok(Segment {
+        mode,
+        data,
+        encoding,
+    })
+}
+
+/// Character count written in a segment header.
+fn character_count(segment: &Segment, data_bits: &BitArray) -> u32 {
+    match segment.mode {
+        Mode::BYTE => data_bits.getSizeInBytes() as u32,
+        Mode::KANJI => (data_bits.get_size() / 13) as u32,
+        _ => segment.data.chars().count() as u32,
+    }
+}
+
+/// Builds the unterminated bitstream for `version`.
+fn message_bits(
+    options: &QrOptions,
+    segments: &[(Segment, BitArray)],
+    version: VersionRef,
+) -> ZplResult<BitArray> {
+    let mut bits = BitArray::new();
+    let mut append = |value: u32, count: usize| -> ZplResult<()> {
+        bits.appendBits(value as _, count).map_err(generation_error)
+    };
+    if options.model == 1 {
+        // Model 1 data starts with four zero bits.
+        append(0, 4)?;
+    }
+    if let Some((number, count, parity)) = options.structured_append {
+        append(u32::from(Mode::STRUCTURED_APPEND.getBits()), 4)?;
+        append(number.saturating_sub(1), 4)?;
+        append(count.saturating_sub(1), 4)?;
+        append(u32::from(parity), 8)?;
+    }
+    if options.gs1 {
+        append(u32::from(Mode::FNC1_FIRST_POSITION.getBits()), 4)?;
+    }
+    for (segment, data_bits) in segments {
+        qrcode_encoder::appendModeInfo(segment.mode, &mut bits).map_err(generation_error)?;
+        qrcode_encoder::appendLengthInfo(
+            character_count(segment, data_bits),
+            version,
+            segment.mode,
+            &mut bits,
+        )
+        .map_err(generation_error)?;
+        bits.appendBitArray(data_bits.clone());
+    }
+    Ok(bits)
+}
+
+/// Module positions of each Model 1 codeword, most significant bit first,
+/// in placement order.
+fn model1_codeword_positions(dimension: u32) -> Vec<[(u32, u32); 8]> {
+    let columns = dimension / 4 + 1 + 2;
+    let mut positions = Vec::new();
+    let block = |x: u32, y: u32, width: u32| {
+        std::array::from_fn(|b| (x - b as u32 % width, y - b as u32 / width))
+    };
+    for j in 0..columns {
+        if j <= 1 {
+            // Vertical blocks along the right edge.
+            let rows = (dimension - 8) / 4;
+            for i in 0..rows {
+                if j == 0 && i % 2 == 0 && i > 0 && i < rows - 1 {
+                    continue; // extension pattern
+                }
+                positions.push(block(dimension - 1 - j * 2, dimension - 1 - i * 4, 2));
+            }
+        } else if columns - j <= 4 {
+            // Vertical blocks between the left finder patterns.
+            let rows = (dimension - 16) / 4;
+            let x = (columns - j - 1) * 2 + 1 + u32::from(columns - j == 4);
+            for i in 0..rows {
+                positions.push(block(x, dimension - 1 - 8 - i * 4, 2));
+            }
+        } else {
+            // Horizontal blocks.
+            let rows = dimension / 2;
+            for i in 0..rows {
+                if j == 2 && i >= rows - 4 {
+                    continue; // top-right finder pattern
+                }
+                if i == 0 && j % 2 == 1 && j + 1 != columns - 4 {
+                    continue; // extension pattern
+                }
+                let x = dimension - 1 - 4 - (j - 2) * 4;
+                let y = dimension - 1 - i * 2 - u32::from(i >= rows - 3);
+                positions.push(block(x, y, 4));
+            }
+        }
+    }
+    positions
+}
+
+fn mask_bit(mask: u32, x: u32, y: u32) -> bool {
+    match mask {
+        0 => (y + x).is_multiple_of(2),
+        1 => y.is_multiple_of(2),
+        2 => x.is_multiple_of(3),
+        3 => (y + x).is_multiple_of(3),
+        4 => (y / 2 + x / 3).is_multiple_of(2),
+        5 => (y * x).is_multiple_of(6),
+        6 => (y * x) % 6 < 3,
+        _ => (y + x + (y * x) % 3).is_multiple_of(2),
+    }
+}
+
+/// Lays out a Model 1 symbol with the given mask.
+fn model1_matrix(
+    codewords: &[u8],
+    level: ErrorCorrectionLevel,
+    dimension: u32,
+    mask: u32,
+) -> ZplResult<ByteMatrix> {
+    let mut matrix = ByteMatrix::new(dimension, dimension);
+    matrix_util::clearMatrix(&mut matrix);
+    matrix_util::embedPositionDetectionPatternsAndSeparators(&mut matrix)
+        .map_err(generation_error)?;
+    matrix_util::embedTimingPatterns(&mut matrix);
+
+    let data = (ec_bits(level) << 3) | mask;
+    let bch = matrix_util::calculateBCHCode(data, FORMAT_POLY).map_err(generation_error)?;
+    let format = ((data << 10) | bch) ^ MODEL1_FORMAT_MASK;
+    let bit = |i: u32| ((format >> (14 - i)) & 1) as u8;
+    let first = [
+        (0, 8),
+        (1, 8),
+        (2, 8),
+        (3, 8),
+        (4, 8),
+        (5, 8),
+        (7, 8),
+        (8, 8),
+        (8, 7),
+        (8, 5),
+        (8, 4),
+        (8, 3),
+        (8, 2),
+        (8, 1),
+        (8, 0),
+    ];
+    for (i, (x, y)) in first.into_iter().enumerate() {
+        matrix.set(x, y, bit(i as u32));
+    }
+    for i in 0..7 {
+        matrix.set(8, dimension - 1 - i, bit(i));
+    }
+    for i in 0..8 {
+        matrix.set(dimension - 8 + i, 8, bit(7 + i));
+    }
+    matrix.set(8, dimension - 8, 1);
+
+    for (codeword, block) in codewords.iter().zip(model1_codeword_positions(dimension)) {
+        for (b, (x, y)) in block.into_iter().enumerate() {
+            let dark = (codeword >> (7 - b)) & 1 == 1;
+            matrix.set_bool(x, y, dark != mask_bit(mask, x, y));
+        }
+    }
+    // Extension pattern blocks stay light.
+    for y in 0..dimension {
+        for x in 0..dimension {
+            if matrix_util::isEmpty(matrix.get(x, y)) {
+                matrix.set(x, y, 0);
+            }
+        }
+    }
+    Ok(matrix)
+}
+
+/// Returns the matrix built with the lowest-penalty mask.
+fn best_mask<F>(build: F) -> ZplResult<ByteMatrix>
+where
+    F: Fn(u32) -> ZplResult<ByteMatrix>,
+{
+    let mut best: Option<(u32, ByteMatrix)> = None;
+    for mask in 0..8 {
+        let matrix = build(mask)?;
+        let penalty = qrcode_encoder::calculateMaskPenalty(&matrix);
+        if best.as_ref().is_none_or(|(p, _)| penalty < *p) {
+            best = Some((penalty, matrix));
+        }
+    }
+    best.map(|(_, m)| m)
+        .ok_or_else(|| generation_error("no QR Code mask pattern"))
+}
+
+/// Encodes `segments` (or `data` as one automatic segment when there are
+/// none) into a QR Code symbol.
+pub(crate) fn encode(
+    options: &QrOptions,
+    data: &str,
+    segments: &[QrSegment],
+) -> ZplResult<BitMatrix> {
+    let level = ec_level(options.error_correction);
+    let element_string;
+    let data = if options.gs1 {
+        if options.model == 1 || !segments.is_empty() {
+            return Err(generation_error(
+                "GS1 QR Code needs a Model 2 symbol in automatic mode",
+            ));
+        }
+        // FNC1 separators are GS in byte mode and `%` in alphanumeric mode,
+        // where a literal `%` is doubled.
+        let elements = gs1::element_string(data)?;
+        element_string = match qrcode_encoder::chooseMode(&elements) {
+            Mode::ALPHANUMERIC => elements.replace('%', "%%"),
+            _ => elements,
+        };
+        element_string.as_str()
+    } else {
+        data
+    };
+    let resolved = if segments.is_empty() {
+        let mode = qrcode_encoder::chooseMode(data);
+        let mode = match mode {
+            Mode::NUMERIC => 'N',
+            Mode::ALPHANUMERIC => 'A',
+            Mode::KANJI => 'K',
+            _ => 'B',
+        };
+        vec![resolve(mode, data)?]
+    } else {
+        segments
+            .iter()
+            .filter(|s| !s.data.is_empty())
+            .map(|s| resolve(s.mode, &s.data))
+            .collect::<ZplResult<Vec<_>>>()?
+    };
+    let encoded = resolved
+        .into_iter()
+        .map(|segment| {
+            let mut bits = BitArray::new();
+            qrcode_encoder::appendBytes(segment.data, segment.mode, &mut bits, segment.encoding)
+                .map_err(generation_error)?;
+            Ok((segment, bits))
+        })
+        .collect::<ZplResult<Vec<_>>>()?;
+
+    let max_version = if options.model == 1 { 14 } else { 40 };
+    let mut chosen = None;
+    for number in 1..=max_version {
+        let version = if options.model == 1 {
+            Version::Model1(number)
+        } else {
+            Version::getVersionForNumber(number)
+        }
+        .map_err(generation_error)?;
+        // Character count fields can overflow in small versions.
+        let Ok(bits) = message_bits(options, &encoded, version) else {
+            continue;
+        };
+        if qrcode_encoder::willFit(bits.get_size() as u32, version, &level) {
+            chosen = Some((version, bits));
+            break;
+        }
+    }
+    let (version, mut bits) = chosen.ok_or_else(|| {
+        generation_error(format!(
+            "data does not fit in a Model {} QR Code",
+            options.model
+        ))
+    })?;
+
+    let ec_blocks = version.getECBlocksForLevel(level);
+    let total = version.getTotalCodewords();
+    let data_bytes = total - ec_blocks.getTotalECCodewords();
+    qrcode_encoder::terminateBits(data_bytes, &mut bits).map_err(generation_error)?;
+    let final_bits =
+        qrcode_encoder::interleaveWithECBytes(&bits, total, data_bytes, ec_blocks.getNumBlocks())
+            .map_err(generation_error)?;
+
+    let dimension = version.getDimensionForVersion();
+    let matrix = if options.model == 1 {
+        let codewords: Vec<u8> = (0..final_bits.getSizeInBytes())
+            .map(|i| {
+                (0..8).fold(0u8, |byte, b| {
+                    (byte << 1) | u8::from(final_bits.get(i * 8 + b))
+                })
+            })
+            .collect();
+        best_mask(|mask| model1_matrix(&codewords, level, dimension, mask))?
+    } else {
+        best_mask(|mask| {
+            let mut matrix = ByteMatrix::new(dimension, dimension);
+            matrix_util::buildMatrix(&final_bits, &level, version, mask as i32, &mut matrix)
+                .map_err(generation_error)?;
+            Ok(matrix)
+        })?
+    };
+
+    let mut output = BitMatrix::new(dimension, dimension).map_err(generation_error)?;
+    for y in 0..dimension {
+        for x in 0..dimension {
+            if matrix.get(x, y) == 1 {
+                output.set(x, y);
+            }
+        }
+    }
+    Ok(output)
+}
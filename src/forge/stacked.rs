@@ -0,0 +1,769 @@
+//! Native encoders for the PDF417 family and other stacked symbologies:
+//! PDF417 (`^B7`, including truncated symbols and Macro PDF417 segments for
+//! `^FM`), MicroPDF417 (`^BF`) and CODABLOCK F (`^BB`).
+//!
+//! Data compaction reuses `rxing`'s PDF417 high-level encoder and its
+//! codeword bar/space tables; error correction and the row layout are built
+//! here. The resulting [`BitMatrix`] holds one module per column and one
+//! symbol row per row, drawn `module_width` wide and `row_height` tall.
+//! CODABLOCK F rows are Code 128 symbols and come out as [`DataBarRow`]s,
+//! so the separator bars between them can be thinner than the rows.
+
+use std::sync::OnceLock;
+
+use rxing::common::BitMatrix;
+use rxing::pdf417::encoder::Compaction;
+use rxing::pdf417::encoder::pdf_417_high_level_encoder::encodeHighLevel;
+use rxing::pdf417::pdf_417_common::{CODEWORD_TABLE, SYMBOL_TABLE};
+
+use super::code128::{self, Item, Subset};
+use super::databar::DataBarRow;
+use crate::{ZplError, ZplResult};
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+/// A stacked symbology that can be split into macro segments with `^FM`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Stacked {
+    /// PDF417 with the given `^B7` shape.
+    Pdf417(Pdf417Options),
+    /// MicroPDF417 in the given `^BF` mode (0-33).
+    MicroPdf417 { mode: u32 },
+}
+
+// ─── Codewords ──────────────────────────────────────────────────────────────
+
+/// Number of codeword values in GF(929).
+const PRIME: u32 = 929;
+/// Pad codeword (a text compaction latch, ignored by readers).
+const PAD: u32 = 900;
+/// Starts a Macro PDF417 control block.
+const MACRO_CONTROL_BLOCK: u32 = 928;
+/// Ends the control block of the last macro segment.
+const MACRO_TERMINATOR: u32 = 922;
+
+/// Bar/space patterns (17 modules, most significant bit first) of every
+/// codeword, indexed by cluster number / 3.
+fn codeword_patterns() -> &'static [[u32; 929]; 3] {
+    static PATTERNS: OnceLock<[[u32; 929]; 3]> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let mut patterns = [[0; 929]; 3];
+        // CODEWORD_TABLE holds `cluster / 3 * 929 + codeword + 1` for each
+        // entry of the sorted SYMBOL_TABLE.
+        for (&symbol, &entry) in SYMBOL_TABLE.iter().zip(CODEWORD_TABLE.iter()) {
+            let value = (entry - 1) as usize;
+            patterns[value / PRIME as usize][value % PRIME as usize] = symbol;
+        }
+        patterns
+    })
+}
+
+/// Compacts `data` into PDF417 data codewords (text, byte and numeric
+/// compaction, as chosen by `rxing`).
+fn compact(data: &str) -> ZplResult<Vec<u32>> {
+    encodeHighLevel(data, Compaction::AUTO, None, false)
+        .map(|codewords| codewords.chars().map(|c| c as u32).collect())
+        .map_err(generation_error)
+}
+
+/// Reed-Solomon check codewords over GF(929) with generator roots
+/// 3, 3², … 3^`ec_len`, as shared by PDF417 and MicroPDF417.
+fn error_correction(data: &[u32], ec_len: usize) -> Vec<u32> {
+    let mut generator = vec![1u32];
+    let mut root = 1;
+    for _ in 0..ec_len {
+        root = root * 3 % PRIME;
+        let mut next = vec![0; generator.len() + 1];
+        for (i, &c) in generator.iter().enumerate() {
+            next[i] = (next[i] + c) % PRIME;
+            next[i + 1] = (next[i + 1] + PRIME - c * root % PRIME) % PRIME;
+        }
+        generator = next;
+    }
+
+    let mut remainder = vec![0u32; ec_len];
+    for &codeword in data {
+        let factor = (codeword + remainder[0]) % PRIME;
+        for i in 0..ec_len {
+            let carry = remainder.get(i + 1).copied().unwrap_or(0);
+            remainder[i] = (carry + PRIME - factor * generator[i + 1] % PRIME) % PRIME;
+        }
+    }
+    remainder.iter().map(|&r| (PRIME - r) % PRIME).collect()
+}
+
+/// Macro PDF417 control block for segment `index`.
+///
+/// The segment index is five digits in numeric compaction (two codewords);
+/// the file ID is derived from the whole message so that segments of
+/// different messages are not merged by readers.
+fn macro_control_block(index: u32, file_id: &[u32; 2], last: bool) -> Vec<u32> {
+    let index = 100_000 + index;
+    let mut block = vec![MACRO_CONTROL_BLOCK, index / 900, index % 900];
+    block.extend_from_slice(file_id);
+    if last {
+        block.push(MACRO_TERMINATOR);
+    }
+    block
+}
+
+fn file_id(data: &str) -> [u32; 2] {
+    let hash = data
+        .bytes()
+        .fold(0u32, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u32));
+    [hash % 900, hash / 900 % 900]
+}
+
+// ─── PDF417 ─────────────────────────────────────────────────────────────────
+
+const START_PATTERN: u32 = 0x1FEA8;
+const STOP_PATTERN: u32 = 0x3FA29;
+
+/// `^B7` symbol shape.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Pdf417Options {
+    /// Error correction security level (0-8).
+    pub security_level: u32,
+    /// Data columns (1-30), or 0 to derive them.
+    pub columns: u32,
+    /// Rows (3-90), or 0 to derive them.
+    pub rows: u32,
+    /// Truncated PDF417: right row indicators and stop pattern are dropped.
+    pub truncate: bool,
+}
+
+fn pdf417_ec_len(security_level: u32) -> usize {
+    2 << security_level.min(8)
+}
+
+/// Picks columns and rows for `codewords` (length descriptor and error
+/// correction included).
+///
+/// Following Zebra, a given column count derives the rows and a given row
+/// count derives the columns; when both are given the data must fit in
+/// them. Without either, the shape aims for a 3:1 symbol aspect ratio the
+/// way `rxing` does.
+fn pdf417_dimensions(codewords: usize, options: &Pdf417Options) -> ZplResult<(usize, usize)> {
+    let (columns, rows) = (options.columns as usize, options.rows as usize);
+    let dimensions = match (columns, rows) {
+        (0, 0) => {
+            let mut best: Option<(usize, usize, f32)> = None;
+            for columns in 2..=30 {
+                let rows = codewords.div_ceil(columns).max(3);
+                if rows > 90 {
+                    continue;
+                }
+                let ratio = (17 * columns + 69) as f32 * 0.357 / (rows as f32 * 2.0);
+                if best.is_some_and(|(_, _, r)| (ratio - 3.0).abs() > (r - 3.0).abs()) {
+                    continue;
+                }
+                best = Some((columns, rows, ratio));
+            }
+            best.map(|(c, r, _)| (c, r))
+        }
+        (columns, 0) => Some((columns, codewords.div_ceil(columns).max(3))),
+        (0, rows) => Some((codewords.div_ceil(rows).max(1), rows)),
+        (columns, rows) => Some((columns, rows)),
+    };
+    match dimensions {
+        Some((c, r)) if c <= 30 && r <= 90 && c * r >= codewords => Ok((c, r)),
+        _ => Err(generation_error(format!(
+            "data does not fit in PDF417 ({} codewords{})",
+            codewords,
+            match (columns, rows) {
+                (0, 0) => String::new(),
+                (c, 0) => format!(", {} columns", c),
+                (0, r) => format!(", {} rows", r),
+                (c, r) => format!(", {} columns x {} rows", c, r),
+            }
+        ))),
+    }
+}
+
+/// Data codewords a PDF417 symbol can carry besides its length descriptor.
+fn pdf417_capacity(options: &Pdf417Options) -> usize {
+    let columns = if options.columns > 0 {
+        options.columns
+    } else {
+        30
+    };
+    let rows = if options.rows > 0 { options.rows } else { 90 };
+    (columns * rows).min(928) as usize - 1 - pdf417_ec_len(options.security_level)
+}
+
+fn pdf417_symbol(
+    options: &Pdf417Options,
+    message: &[u32],
+    trailer: &[u32],
+) -> ZplResult<BitMatrix> {
+    let level = options.security_level.min(8);
+    let ec_len = pdf417_ec_len(level);
+    let (columns, rows) = pdf417_dimensions(1 + message.len() + trailer.len() + ec_len, options)?;
+    if columns * rows > 928 {
+        return Err(generation_error(format!(
+            "PDF417 symbol of {} columns x {} rows exceeds 928 codewords",
+            columns, rows
+        )));
+    }
+
+    let data_len = columns * rows - ec_len;
+    let mut codewords = Vec::with_capacity(columns * rows);
+    codewords.push(data_len as u32);
+    codewords.extend_from_slice(message);
+    codewords.resize(data_len - trailer.len(), PAD);
+    codewords.extend_from_slice(trailer);
+    let ec = error_correction(&codewords, ec_len);
+    codewords.extend(ec);
+
+    let patterns = codeword_patterns();
+    let width = if options.truncate {
+        17 * (columns as u32 + 2) + 1
+    } else {
+        17 * (columns as u32 + 4) + 1
+    };
+    let mut matrix = BitMatrix::new(width, rows as u32).map_err(generation_error)?;
+    let (c, r, l) = (columns as u32, rows as u32, level * 3);
+    for row in 0..rows {
+        let cluster = row % 3;
+        let base = (row / 3) as u32 * 30;
+        let (left, right) = match cluster {
+            0 => (base + (r - 1) / 3, base + c - 1),
+            1 => (base + l + (r - 1) % 3, base + (r - 1) / 3),
+            _ => (base + c - 1, base + l + (r - 1) % 3),
+        };
+        let mut modules = vec![(START_PATTERN, 17), (patterns[cluster][left as usize], 17)];
+        for &codeword in &codewords[row * columns..(row + 1) * columns] {
+            modules.push((patterns[cluster][codeword as usize], 17));
+        }
+        if options.truncate {
+            modules.push((1, 1));
+        } else {
+            modules.push((patterns[cluster][right as usize], 17));
+            modules.push((STOP_PATTERN, 18));
+        }
+        set_row(&mut matrix, row as u32, &modules);
+    }
+    Ok(matrix)
+}
+
+/// Encodes `data` as a single PDF417 symbol.
+pub(crate) fn encode_pdf417(options: &Pdf417Options, data: &str) -> ZplResult<BitMatrix> {
+    pdf417_symbol(options, &compact(data)?, &[])
+}
+
+/// Sets the dark modules of `patterns` (value, width in modules) along row
+/// `y`, left to right.
+fn set_row(matrix: &mut BitMatrix, y: u32, patterns: &[(u32, u32)]) {
+    let mut x = 0;
+    for &(pattern, width) in patterns {
+        for bit in (0..width).rev() {
+            if (pattern >> bit) & 1 == 1 {
+                matrix.set(x, y);
+            }
+            x += 1;
+        }
+    }
+}
+
+// ─── MicroPDF417 (^BF) ──────────────────────────────────────────────────────
+
+/// Left and right Row Address Patterns (10 modules) from ISO/IEC 24728,
+/// indexed by RAP number - 1.
+const SIDE_RAPS: [u32; 52] = [
+    0x322, 0x3A2, 0x3B2, 0x332, 0x372, 0x37A, 0x33A, 0x3BA, 0x39A, 0x3DA, 0x3CA, 0x38A, 0x30A,
+    0x31A, 0x312, 0x392, 0x3D2, 0x3D6, 0x3D4, 0x394, 0x3B4, 0x3A4, 0x3A6, 0x3AE, 0x3AC, 0x3A8,
+    0x328, 0x32C, 0x32E, 0x326, 0x336, 0x3B6, 0x396, 0x316, 0x314, 0x334, 0x374, 0x376, 0x366,
+    0x364, 0x36C, 0x36E, 0x34E, 0x35E, 0x35C, 0x358, 0x348, 0x34C, 0x344, 0x346, 0x342, 0x362,
+];
+
+/// Centre Row Address Patterns (10 modules) of three- and four-column
+/// symbols, indexed by RAP number - 1.
+const CENTRE_RAPS: [u32; 52] = [
+    0x2CE, 0x24E, 0x26E, 0x22E, 0x226, 0x236, 0x216, 0x212, 0x21A, 0x23A, 0x232, 0x222, 0x262,
+    0x272, 0x27A, 0x2FA, 0x2F2, 0x2F6, 0x276, 0x274, 0x264, 0x266, 0x246, 0x242, 0x2C2, 0x2E2,
+    0x2E6, 0x2E4, 0x2EC, 0x26C, 0x22C, 0x228, 0x268, 0x2E8, 0x2C8, 0x2CC, 0x2C4, 0x2C6, 0x286,
+    0x28E, 0x28C, 0x29C, 0x298, 0x2B8, 0x2B0, 0x290, 0x2D0, 0x250, 0x258, 0x25C, 0x2DC, 0x2DE,
+];
+
+/// A MicroPDF417 symbol size: data columns, rows, error correction
+/// codewords, starting left/centre/right RAP numbers (the centre RAP is
+/// only used by three- and four-column symbols) and starting cluster.
+struct MicroVariant {
+    columns: usize,
+    rows: usize,
+    ec_len: usize,
+    left_rap: usize,
+    centre_rap: usize,
+    right_rap: usize,
+    cluster: usize,
+}
+
+const fn variant(
+    columns: usize,
+    rows: usize,
+    ec_len: usize,
+    (left_rap, centre_rap, right_rap): (usize, usize, usize),
+    cluster: usize,
+) -> MicroVariant {
+    MicroVariant {
+        columns,
+        rows,
+        ec_len,
+        left_rap,
+        centre_rap,
+        right_rap,
+        cluster,
+    }
+}
+
+/// `^BF` modes 0-33: the one- and two-column sizes (0-12), the
+/// three-column sizes (13-22), the four-column sizes (23-32) and the
+/// four-column, four-row size (33).
+const MICRO_VARIANTS: [MicroVariant; 34] = [
+    variant(1, 11, 7, (1, 0, 9), 0),
+    variant(1, 14, 7, (8, 0, 8), 3),
+    variant(1, 17, 7, (36, 0, 36), 6),
+    variant(1, 20, 8, (19, 0, 19), 0),
+    variant(1, 24, 8, (9, 0, 17), 6),
+    variant(1, 28, 8, (25, 0, 33), 0),
+    variant(2, 8, 8, (1, 0, 1), 0),
+    variant(2, 11, 9, (1, 0, 9), 0),
+    variant(2, 14, 9, (8, 0, 8), 3),
+    variant(2, 17, 10, (36, 0, 36), 6),
+    variant(2, 20, 11, (19, 0, 19), 0),
+    variant(2, 23, 13, (9, 0, 17), 6),
+    variant(2, 26, 15, (27, 0, 35), 6),
+    variant(3, 6, 12, (1, 1, 1), 0),
+    variant(3, 8, 14, (7, 7, 7), 0),
+    variant(3, 10, 16, (15, 15, 15), 6),
+    variant(3, 12, 18, (25, 25, 25), 0),
+    variant(3, 15, 21, (37, 37, 37), 0),
+    variant(3, 20, 26, (1, 17, 33), 0),
+    variant(3, 26, 32, (1, 9, 17), 0),
+    variant(3, 32, 38, (21, 29, 37), 6),
+    variant(3, 38, 44, (15, 31, 47), 6),
+    variant(3, 44, 50, (1, 25, 49), 0),
+    variant(4, 6, 12, (1, 1, 1), 0),
+    variant(4, 8, 14, (7, 7, 7), 0),
+    variant(4, 10, 16, (15, 15, 15), 6),
+    variant(4, 12, 18, (25, 25, 25), 0),
+    variant(4, 15, 21, (37, 37, 37), 0),
+    variant(4, 20, 26, (1, 17, 33), 0),
+    variant(4, 26, 32, (1, 9, 17), 0),
+    variant(4, 32, 38, (21, 29, 37), 6),
+    variant(4, 38, 44, (15, 31, 47), 6),
+    variant(4, 44, 50, (1, 25, 49), 0),
+    variant(4, 4, 8, (47, 19, 43), 3),
+];
+
+fn micro_variant(mode: u32) -> ZplResult<&'static MicroVariant> {
+    MICRO_VARIANTS
+        .get(mode as usize)
+        .ok_or_else(|| generation_error(format!("MicroPDF417 mode must be 0-33, got {}", mode)))
+}
+
+fn micro_capacity(mode: u32) -> ZplResult<usize> {
+    let variant = micro_variant(mode)?;
+    Ok(variant.columns * variant.rows - variant.ec_len)
+}
+
+fn micro_symbol(mode: u32, message: &[u32], trailer: &[u32]) -> ZplResult<BitMatrix> {
+    let variant = micro_variant(mode)?;
+    let data_len = variant.columns * variant.rows - variant.ec_len;
+    if message.len() + trailer.len() > data_len {
+        return Err(generation_error(format!(
+            "data too long for MicroPDF417 mode {} ({} codewords, {} available)",
+            mode,
+            message.len() + trailer.len(),
+            data_len
+        )));
+    }
+    let mut codewords = Vec::with_capacity(variant.columns * variant.rows);
+    codewords.extend_from_slice(message);
+    codewords.resize(data_len - trailer.len(), PAD);
+    codewords.extend_from_slice(trailer);
+    let ec = error_correction(&codewords, variant.ec_len);
+    codewords.extend(ec);
+
+    let patterns = codeword_patterns();
+    // Three-column symbols put the centre RAP after the first data column,
+    // four-column symbols after the second.
+    let centre_column = match variant.columns {
+        3 => Some(1),
+        4 => Some(2),
+        _ => None,
+    };
+    let width = 10 + 17 * variant.columns as u32 + 10 * centre_column.map_or(1, |_| 2) + 1;
+    let mut matrix = BitMatrix::new(width, variant.rows as u32).map_err(generation_error)?;
+    for row in 0..variant.rows {
+        let cluster = (variant.cluster / 3 + row) % 3;
+        let left = SIDE_RAPS[(variant.left_rap - 1 + row) % 52];
+        let right = SIDE_RAPS[(variant.right_rap - 1 + row) % 52];
+        let mut modules = vec![(left, 10)];
+        let row_codewords = &codewords[row * variant.columns..(row + 1) * variant.columns];
+        for (column, &codeword) in row_codewords.iter().enumerate() {
+            if centre_column == Some(column) {
+                modules.push((CENTRE_RAPS[(variant.centre_rap - 1 + row) % 52], 10));
+            }
+            modules.push((patterns[cluster][codeword as usize], 17));
+        }
+        // The right RAP is followed by a one-module stop bar.
+        modules.push((right, 10));
+        modules.push((1, 1));
+        set_row(&mut matrix, row as u32, &modules);
+    }
+    Ok(matrix)
+}
+
+/// Encodes `data` as a single MicroPDF417 symbol in `^BF` `mode`.
+pub(crate) fn encode_micro_pdf417(mode: u32, data: &str) -> ZplResult<BitMatrix> {
+    micro_symbol(mode, &compact(data)?, &[])
+}
+
+// ─── Macro segments (^FM) ───────────────────────────────────────────────────
+
+/// Splits `data` into Macro PDF417 segments and encodes each one, filling
+/// every symbol to capacity in order.
+///
+/// At most `max_segments` symbols are produced; fewer are returned when the
+/// data runs out first.
+pub(crate) fn encode_segments(
+    symbology: Stacked,
+    data: &str,
+    max_segments: usize,
+) -> ZplResult<Vec<BitMatrix>> {
+    let capacity = match symbology {
+        Stacked::Pdf417(options) => pdf417_capacity(&options),
+        Stacked::MicroPdf417 { mode } => micro_capacity(mode)?,
+    };
+    let file_id = file_id(data);
+    let chars: Vec<(usize, char)> = data.char_indices().collect();
+
+    let mut symbols = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let index = symbols.len() as u32;
+        if symbols.len() == max_segments {
+            return Err(generation_error(format!(
+                "data needs more than {} macro symbols",
+                max_segments
+            )));
+        }
+        let offset = chars[start].0;
+        let rest = &data[offset..];
+
+        // The whole remainder fits behind a terminating control block.
+        let block = macro_control_block(index, &file_id, true);
+        let message = compact(rest)?;
+        if message.len() + block.len() <= capacity {
+            symbols.push(build(symbology, &message, &block)?);
+            break;
+        }
+
+        // Otherwise take the longest prefix that fits.
+        let block = macro_control_block(index, &file_id, false);
+        let (mut low, mut high) = (0, chars.len() - start);
+        while low < high {
+            let mid = (low + high).div_ceil(2);
+            let end = chars.get(start + mid).map_or(data.len(), |c| c.0);
+            if compact(&data[offset..end])?.len() + block.len() <= capacity {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        if low == 0 {
+            return Err(generation_error("symbol too small to hold a macro segment"));
+        }
+        let end = chars.get(start + low).map_or(data.len(), |c| c.0);
+        symbols.push(build(symbology, &compact(&data[offset..end])?, &block)?);
+        start += low;
+    }
+    Ok(symbols)
+}
+
+fn build(symbology: Stacked, message: &[u32], trailer: &[u32]) -> ZplResult<BitMatrix> {
+    match symbology {
+        Stacked::Pdf417(options) => pdf417_symbol(&options, message, trailer),
+        Stacked::MicroPdf417 { mode } => micro_symbol(mode, message, trailer),
+    }
+}
+
+// ─── CODABLOCK F (^BB) ──────────────────────────────────────────────────────
+
+/// Most rows a CODABLOCK F symbol can have.
+const CODABLOCK_MAX_ROWS: usize = 44;
+
+fn digit(item: Item) -> Option<u8> {
+    match item {
+        Item::Char(c) if c.is_ascii_digit() => Some(c - b'0'),
+        _ => None,
+    }
+}
+
+/// Value of `item` in subset A or B, if the subset has it.
+fn subset_value(subset: Subset, item: Item) -> Option<u8> {
+    match item {
+        Item::Fnc1 => Some(code128::FNC1),
+        Item::Char(c) => subset.value(c),
+    }
+}
+
+/// Subset a row (or a switch) should use for `items`.
+fn codablock_subset(items: &[Item]) -> Subset {
+    if items.len() >= 2 && items[..2].iter().all(|&item| digit(item).is_some()) {
+        Subset::C
+    } else if items.first().is_some_and(|&item| {
+        subset_value(Subset::A, item).is_some() && subset_value(Subset::B, item).is_none()
+    }) {
+        Subset::A
+    } else {
+        Subset::B
+    }
+}
+
+/// Fills up to `slots` data columns of a row from `items`, starting in
+/// `subset`. Returns the symbol values, the items consumed and the subset
+/// in effect at the end.
+fn codablock_row_data(
+    items: &[Item],
+    mut subset: Subset,
+    slots: usize,
+) -> (Vec<u8>, usize, Subset) {
+    let mut values = Vec::with_capacity(slots);
+    let mut pos = 0;
+    while pos < items.len() && values.len() < slots {
+        let item = items[pos];
+        let digits = items[pos..]
+            .iter()
+            .take_while(|&&item| digit(item).is_some())
+            .count();
+        if subset == Subset::C {
+            if item == Item::Fnc1 {
+                values.push(code128::FNC1);
+                pos += 1;
+            } else if digits >= 2 {
+                values.push(digit(item).unwrap_or(0) * 10 + digit(items[pos + 1]).unwrap_or(0));
+                pos += 2;
+            } else {
+                subset = codablock_subset(&items[pos..]);
+                values.push(subset.switch());
+            }
+            continue;
+        }
+        // Runs of four or more digits go to subset C, an odd leading digit
+        // staying in the current subset.
+        if digits >= 4 && digits % 2 == 0 {
+            values.push(code128::CODE_C);
+            subset = Subset::C;
+            continue;
+        }
+        if let Some(value) = subset_value(subset, item) {
+            values.push(value);
+            pos += 1;
+            continue;
+        }
+        let other = if subset == Subset::A {
+            Subset::B
+        } else {
+            Subset::A
+        };
+        // A single character of the other subset is shifted when the one
+        // after it fits the current subset again.
+        let shifted = items
+            .get(pos + 1)
+            .is_none_or(|&next| subset_value(subset, next).is_some());
+        if shifted {
+            if values.len() + 2 > slots {
+                break;
+            }
+            values.push(code128::SHIFT);
+            values.push(subset_value(other, item).unwrap_or(0));
+            pos += 1;
+        } else {
+            values.push(other.switch());
+            subset = other;
+        }
+    }
+    (values, pos, subset)
+}
+
+/// Fills the remaining data columns with subset switches, which readers
+/// skip, never ending in subset C.
+fn codablock_pad(values: &mut Vec<u8>, mut subset: Subset, slots: usize) {
+    while values.len() < slots {
+        subset = if subset == Subset::B {
+            Subset::A
+        } else {
+            Subset::B
+        };
+        values.push(subset.switch());
+    }
+}
+
+/// Symbol check characters K1 and K2 over the data characters.
+fn codablock_checks(data: &[u8]) -> [u8; 2] {
+    let mut k1 = 0;
+    let mut k2 = 0;
+    for (i, &b) in data.iter().enumerate() {
+        k1 = (k1 + (i as u32 + 1) * b as u32) % 86;
+        k2 = (k2 + i as u32 * b as u32) % 86;
+    }
+    [k1 as u8, k2 as u8]
+}
+
+/// Splits `items` into rows of `columns` data characters. Each row is
+/// Start A, a code set selector, the row indicator, the data columns and
+/// the row check character; the last row ends its data with K1/K2 when
+/// `checks` is given. At least `min_rows` rows are produced.
+fn codablock_rows(
+    items: &[Item],
+    columns: usize,
+    min_rows: usize,
+    checks: Option<[u8; 2]>,
+) -> Vec<Vec<u8>> {
+    let reserved = if checks.is_some() { 2 } else { 0 };
+    let mut rows: Vec<(Subset, Vec<u8>)> = Vec::new();
+    let mut pos = 0;
+    loop {
+        let rest = &items[pos..];
+        let subset = codablock_subset(rest);
+        if rows.len() + 1 >= min_rows {
+            // The last row keeps room for K1/K2, which are sent in subset
+            // A or B.
+            let slots = columns.saturating_sub(reserved);
+            let (mut values, used, end) = codablock_row_data(rest, subset, slots);
+            if used == rest.len() && (checks.is_none() || end != Subset::C || values.len() < slots)
+            {
+                codablock_pad(&mut values, end, slots);
+                values.extend(checks.into_iter().flatten());
+                rows.push((subset, values));
+                break;
+            }
+        }
+        // One row past the limit marks data that does not fit.
+        if rows.len() > CODABLOCK_MAX_ROWS {
+            break;
+        }
+        let (mut values, used, end) = codablock_row_data(rest, subset, columns);
+        codablock_pad(&mut values, end, columns);
+        rows.push((subset, values));
+        pos += used;
+    }
+
+    let count = rows.len();
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, (subset, data))| {
+            // A row in subset A shifts its indicator into subset B.
+            let selector = match subset {
+                Subset::A => code128::SHIFT,
+                _ => subset.switch(),
+            };
+            let indicator = if index == 0 { count - 2 } else { index + 42 };
+            let mut row = vec![code128::START_A, selector, indicator as u8];
+            row.extend(data);
+            let check = row
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| i.max(1) as u32 * v as u32)
+                .sum::<u32>()
+                % 103;
+            row.push(check as u8);
+            row
+        })
+        .collect()
+}
+
+/// Encodes `data` as a CODABLOCK F symbol (`^BB` modes F and E).
+///
+/// Every row is a Code 128 symbol starting in code set A, with the code set
+/// of its data and a row indicator (the row count in the first row, the
+/// row number otherwise) up front. `security` 'Y' ends the data with the
+/// K1/K2 symbol check characters. `columns` is the number of data
+/// characters per row (2-62) and `rows` the number of rows (2-44); either
+/// can be 0 to derive it. Mode E starts the data with FNC1 and encodes
+/// group separators as FNC1. Rows are `row_height` dots tall and separated
+/// by one-module bars, with another above and below the symbol.
+///
+/// CODABLOCK A (the Code 39 based mode) is not supported.
+pub(crate) fn encode_codablock(
+    mode: char,
+    security: char,
+    columns: u32,
+    rows: u32,
+    row_height: u32,
+    module_width: u32,
+    data: &str,
+) -> ZplResult<Vec<DataBarRow>> {
+    if !matches!(mode, 'E' | 'F') {
+        return Err(generation_error(format!(
+            "CODABLOCK mode {} is not supported",
+            mode
+        )));
+    }
+    if !data.is_ascii() {
+        return Err(generation_error("CODABLOCK F data must be ASCII"));
+    }
+    let gs1 = mode == 'E';
+    let mut items = Vec::with_capacity(data.len() + 1);
+    if gs1 {
+        items.push(Item::Fnc1);
+    }
+    items.extend(data.bytes().map(|b| {
+        if gs1 && b as char == crate::gs1::GS {
+            Item::Fnc1
+        } else {
+            Item::Char(b)
+        }
+    }));
+    let checks = (security != 'N').then(|| codablock_checks(data.as_bytes()));
+
+    let min_rows = (rows as usize).max(2);
+    let symbol = match columns {
+        0 => (4..=62).find_map(|columns| {
+            let symbol = codablock_rows(&items, columns, min_rows, checks);
+            let limit = if rows > 0 { min_rows } else { columns };
+            (symbol.len() <= limit).then_some(symbol)
+        }),
+        2..=62 => Some(codablock_rows(&items, columns as usize, min_rows, checks)),
+        _ => {
+            return Err(generation_error(format!(
+                "CODABLOCK F needs 2-62 columns, got {}",
+                columns
+            )));
+        }
+    };
+    let symbol = symbol
+        .filter(|symbol| {
+            symbol.len() <= CODABLOCK_MAX_ROWS && (rows == 0 || symbol.len() <= rows as usize)
+        })
+        .ok_or_else(|| generation_error("data does not fit in CODABLOCK F"))?;
+
+    let width = symbol.first().map_or(0, |row| row.len() * 11 + 13);
+    let separator = DataBarRow {
+        modules: vec![true; width],
+        height: module_width.max(1),
+    };
+    let mut modules_rows = vec![separator.clone()];
+    for row in &symbol {
+        let mut modules = Vec::with_capacity(width);
+        for &value in row {
+            push_pattern(&mut modules, code128::PATTERNS[value as usize], 11);
+        }
+        push_pattern(&mut modules, code128::STOP, 13);
+        modules_rows.push(DataBarRow {
+            modules,
+            height: row_height.max(1),
+        });
+        modules_rows.push(separator.clone());
+    }
+    Ok(modules_rows)
+}
+
+/// Appends the `width` modules of `pattern`, most significant bit first.
+fn push_pattern(modules: &mut Vec<bool>, pattern: u16, width: u32) {
+    modules.extend((0..width).rev().map(|bit| (pattern >> bit) & 1 == 1));
+}
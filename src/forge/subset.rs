@@ -0,0 +1,1013 @@
+//! Font subsetting for the native PDF backend.
+//!
+//! Subsets keep glyph ids stable, so the Identity-H content streams, `/W`
+//! arrays and ToUnicode CMaps built against the original font stay valid:
+//! unused glyphs are emptied and the glyph count is cut after the highest
+//! glyph shown. TrueType (`glyf`) subsets pull in the components of
+//! composite glyphs; CFF subsets keep the subroutines and get an identity
+//! charset, so that CIDs are glyph ids in CID-keyed fonts as well.
+//!
+//! Variable fonts are embedded as static instances instead: the outlines of
+//! the glyphs shown are evaluated at the registered axis coordinates and
+//! written to a fresh TrueType font, under the same glyph ids.
+
+use std::collections::BTreeSet;
+
+use rustybuzz::ttf_parser::{Face, GlyphId, OutlineBuilder, Tag};
+
+/// Tables copied unchanged into TrueType subsets: the hinting programs.
+const HINTING_TABLES: [&[u8; 4]; 3] = [b"cvt ", b"fpgm", b"prep"];
+
+/// CFF DICT operators, with two-byte operators as `0x0c00 | b1`.
+const CHARSET: u16 = 15;
+const ENCODING: u16 = 16;
+const CHARSTRINGS: u16 = 17;
+const PRIVATE: u16 = 18;
+const SUBRS: u16 = 19;
+const ROS: u16 = 0x0c1e;
+const CID_COUNT: u16 = 0x0c22;
+const FD_ARRAY: u16 = 0x0c24;
+const FD_SELECT: u16 = 0x0c25;
+
+/// Type 2 charstring of an emptied glyph: a bare `endchar`.
+const EMPTY_CHARSTRING: &[u8] = &[14];
+
+fn u16_at(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn u32_at(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn set_u16(data: &mut [u8], pos: usize, value: u16) {
+    if let Some(b) = data.get_mut(pos..pos + 2) {
+        b.copy_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// Subsets an OpenType font (TrueType or CFF outlines) to the glyphs in
+/// `glyphs`, plus `.notdef`.
+///
+/// Returns `None` for fonts it cannot subset (collections, CFF2 or
+/// malformed tables); the caller then embeds the original bytes.
+pub(crate) fn subset_font(font: &[u8], glyphs: &BTreeSet<u16>) -> Option<Vec<u8>> {
+    let tables = read_tables(font)?;
+    let find = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).map(|(_, d)| *d);
+
+    let num_glyphs = u16_at(find(b"maxp")?, 4)?;
+    let mut keep: BTreeSet<u16> = glyphs.iter().copied().filter(|&g| g < num_glyphs).collect();
+    keep.insert(0);
+
+    let version: [u8; 4] = font.get(..4)?.try_into().ok()?;
+    let mut out: Vec<([u8; 4], Vec<u8>)> = Vec::new();
+    match &version {
+        b"OTTO" => {
+            let new_count = keep.last().map_or(1, |&g| g + 1);
+            out.push((*b"CFF ", subset_cff(find(b"CFF ")?, &keep, new_count)?));
+        }
+        [0, 1, 0, 0] | b"true" => {
+            close_composites(find(b"head")?, find(b"loca")?, find(b"glyf")?, &mut keep)?;
+            let (loca, glyf) = subset_glyf(find(b"head")?, find(b"loca")?, find(b"glyf")?, &keep)?;
+            out.push((*b"loca", loca));
+            out.push((*b"glyf", glyf));
+            for tag in HINTING_TABLES {
+                if let Some(data) = find(tag) {
+                    out.push((*tag, data.to_vec()));
+                }
+            }
+        }
+        _ => return None,
+    }
+    if let Some(os2) = find(b"OS/2") {
+        out.push((*b"OS/2", os2.to_vec()));
+    }
+
+    let new_count = keep.last().map_or(1, |&g| g + 1);
+    let mut head = find(b"head")?.to_vec();
+    // checkSumAdjustment is recomputed for the whole subset; loca is
+    // always written in the long format.
+    head.get_mut(8..12)?.fill(0);
+    if &version != b"OTTO" {
+        set_u16(&mut head, 50, 1);
+    }
+    out.push((*b"head", head));
+
+    let mut maxp = find(b"maxp")?.to_vec();
+    set_u16(&mut maxp, 4, new_count);
+    out.push((*b"maxp", maxp));
+
+    let (hhea, hmtx) = subset_metrics(find(b"hhea")?, find(b"hmtx")?, new_count)?;
+    out.push((*b"hhea", hhea));
+    out.push((*b"hmtx", hmtx));
+
+    Some(write_sfnt(version, out))
+}
+
+/// Reads the table directory of an sfnt as `(tag, data)` pairs.
+fn read_tables(font: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let count = u16_at(font, 4)? as usize;
+    (0..count)
+        .map(|i| {
+            let record = 12 + 16 * i;
+            let tag: [u8; 4] = font.get(record..record + 4)?.try_into().ok()?;
+            let offset = u32_at(font, record + 8)? as usize;
+            let length = u32_at(font, record + 12)? as usize;
+            Some((tag, font.get(offset..offset + length)?))
+        })
+        .collect()
+}
+
+/// Writes an sfnt from its tables, with table and whole-font checksums.
+fn write_sfnt(version: [u8; 4], mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    fn checksum(data: &[u8]) -> u32 {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    }
+
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.max(1).leading_zeros() as u16;
+    let search_range = 16u16 << entry_selector;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&version);
+    for v in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    let mut head_offset = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = Some(out.len());
+        }
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    if let Some(pos) = head_offset {
+        let adjustment = 0xB1B0_AFBA_u32.wrapping_sub(checksum(&out));
+        out[pos + 8..pos + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+/// Cuts `hmtx` after `count` glyphs and updates `numberOfHMetrics`.
+fn subset_metrics(hhea: &[u8], hmtx: &[u8], count: u16) -> Option<(Vec<u8>, Vec<u8>)> {
+    let long_metrics = u16_at(hhea, 34)?;
+    let new_long = long_metrics.min(count);
+    let mut new_hmtx = hmtx.get(..4 * new_long as usize)?.to_vec();
+    for g in new_long..count {
+        let lsb = 4 * long_metrics as usize + 2 * (g - long_metrics) as usize;
+        new_hmtx.extend_from_slice(hmtx.get(lsb..lsb + 2)?);
+    }
+    let mut new_hhea = hhea.to_vec();
+    set_u16(&mut new_hhea, 34, new_long);
+    Some((new_hhea, new_hmtx))
+}
+
+// ─── TrueType outlines ──────────────────────────────────────────────────────
+
+/// Outline data of glyph `gid` in `glyf`, located through `loca`.
+fn glyph_data<'a>(head: &[u8], loca: &[u8], glyf: &'a [u8], gid: u16) -> Option<&'a [u8]> {
+    let g = gid as usize;
+    let (start, end) = if u16_at(head, 50)? == 1 {
+        (
+            u32_at(loca, 4 * g)? as usize,
+            u32_at(loca, 4 * g + 4)? as usize,
+        )
+    } else {
+        (
+            u16_at(loca, 2 * g)? as usize * 2,
+            u16_at(loca, 2 * g + 2)? as usize * 2,
+        )
+    };
+    glyf.get(start..end)
+}
+
+/// Glyph ids referenced by a composite glyph.
+fn components(data: &[u8]) -> Option<Vec<u16>> {
+    const ARGS_ARE_WORDS: u16 = 0x0001;
+    const HAVE_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const HAVE_XY_SCALE: u16 = 0x0040;
+    const HAVE_TWO_BY_TWO: u16 = 0x0080;
+
+    let mut gids = Vec::new();
+    if data.len() < 10 || u16_at(data, 0)? as i16 >= 0 {
+        return Some(gids);
+    }
+    let mut pos = 10;
+    loop {
+        let flags = u16_at(data, pos)?;
+        gids.push(u16_at(data, pos + 2)?);
+        pos += if flags & ARGS_ARE_WORDS != 0 { 8 } else { 6 };
+        pos += if flags & HAVE_SCALE != 0 {
+            2
+        } else if flags & HAVE_XY_SCALE != 0 {
+            4
+        } else if flags & HAVE_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(gids);
+        }
+    }
+}
+
+/// Adds the components of every composite glyph in `keep`, recursively.
+fn close_composites(head: &[u8], loca: &[u8], glyf: &[u8], keep: &mut BTreeSet<u16>) -> Option<()> {
+    let mut pending: Vec<u16> = keep.iter().copied().collect();
+    while let Some(gid) = pending.pop() {
+        for component in components(glyph_data(head, loca, glyf, gid)?)? {
+            if keep.insert(component) {
+                pending.push(component);
+            }
+        }
+    }
+    Some(())
+}
+
+/// Rebuilds `loca` (long format) and `glyf` with only the glyphs in `keep`.
+fn subset_glyf(
+    head: &[u8],
+    loca: &[u8],
+    glyf: &[u8],
+    keep: &BTreeSet<u16>,
+) -> Option<(Vec<u8>, Vec<u8>)> {
+    let count = keep.last().map_or(1, |&g| g + 1);
+    let mut new_loca = Vec::with_capacity(4 * (count as usize + 1));
+    let mut new_glyf = Vec::new();
+    for gid in 0..count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if keep.contains(&gid) {
+            new_glyf.extend_from_slice(glyph_data(head, loca, glyf, gid)?);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    Some((new_loca, new_glyf))
+}
+
+// ─── CFF outlines ───────────────────────────────────────────────────────────
+
+/// Reads a CFF INDEX at `pos`, returning its items and the end offset.
+fn read_index(data: &[u8], pos: usize) -> Option<(Vec<&[u8]>, usize)> {
+    let count = u16_at(data, pos)? as usize;
+    if count == 0 {
+        return Some((Vec::new(), pos + 2));
+    }
+    let off_size = *data.get(pos + 2)? as usize;
+    if !(1..=4).contains(&off_size) {
+        return None;
+    }
+    let offset = |i: usize| -> Option<usize> {
+        let at = pos + 3 + i * off_size;
+        let bytes = data.get(at..at + off_size)?;
+        Some(bytes.iter().fold(0usize, |v, &b| (v << 8) | b as usize))
+    };
+    let base = pos + 2 + (count + 1) * off_size;
+    let items = (0..count)
+        .map(|i| data.get(base + offset(i)?..base + offset(i + 1)?))
+        .collect::<Option<Vec<_>>>()?;
+    Some((items, base + offset(count)?))
+}
+
+fn write_index<T: AsRef<[u8]>>(items: &[T]) -> Vec<u8> {
+    let mut out = (items.len() as u16).to_be_bytes().to_vec();
+    if items.is_empty() {
+        return out;
+    }
+    let total: usize = items.iter().map(|i| i.as_ref().len()).sum();
+    let off_size = match total + 1 {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFF_FFFF => 3,
+        _ => 4,
+    };
+    out.push(off_size as u8);
+    let mut offset = 1usize;
+    let push_offset = |out: &mut Vec<u8>, offset: usize| {
+        out.extend_from_slice(&(offset as u32).to_be_bytes()[4 - off_size..]);
+    };
+    push_offset(&mut out, offset);
+    for item in items {
+        offset += item.as_ref().len();
+        push_offset(&mut out, offset);
+    }
+    for item in items {
+        out.extend_from_slice(item.as_ref());
+    }
+    out
+}
+
+/// One DICT entry: the operator, its raw operand bytes and their integer
+/// values (reals read as 0, they never hold offsets).
+struct DictEntry<'a> {
+    op: u16,
+    raw: &'a [u8],
+    values: Vec<i32>,
+}
+
+fn parse_dict(data: &[u8]) -> Option<Vec<DictEntry<'_>>> {
+    let mut entries = Vec::new();
+    let (mut start, mut pos) = (0, 0);
+    let mut values = Vec::new();
+    while pos < data.len() {
+        let b0 = data[pos];
+        let byte = |i: usize| data.get(pos + i).map(|&b| b as i32);
+        match b0 {
+            0..=21 => {
+                let (op, len) = if b0 == 12 {
+                    (0x0c00 | byte(1)? as u16, 2)
+                } else {
+                    (b0 as u16, 1)
+                };
+                entries.push(DictEntry {
+                    op,
+                    raw: &data[start..pos],
+                    values: std::mem::take(&mut values),
+                });
+                pos += len;
+                start = pos;
+            }
+            28 => {
+                values.push(i16::from_be_bytes([byte(1)? as u8, byte(2)? as u8]) as i32);
+                pos += 3;
+            }
+            29 => {
+                values.push(u32_at(data, pos + 1)? as i32);
+                pos += 5;
+            }
+            30 => {
+                pos += 1;
+                loop {
+                    let b = *data.get(pos)?;
+                    pos += 1;
+                    if b >> 4 == 0xF || b & 0xF == 0xF {
+                        break;
+                    }
+                }
+                values.push(0);
+            }
+            32..=246 => {
+                values.push(b0 as i32 - 139);
+                pos += 1;
+            }
+            247..=250 => {
+                values.push((b0 as i32 - 247) * 256 + byte(1)? + 108);
+                pos += 2;
+            }
+            251..=254 => {
+                values.push(-(b0 as i32 - 251) * 256 - byte(1)? - 108);
+                pos += 2;
+            }
+            _ => return None,
+        }
+    }
+    Some(entries)
+}
+
+/// Appends a DICT entry whose operands are written as 5-byte integers, so
+/// its size does not depend on the offsets it holds.
+fn push_entry(out: &mut Vec<u8>, op: u16, values: &[usize]) {
+    for &v in values {
+        out.push(29);
+        out.extend_from_slice(&(v as u32).to_be_bytes());
+    }
+    if op > 0xFF {
+        out.extend_from_slice(&op.to_be_bytes());
+    } else {
+        out.push(op as u8);
+    }
+}
+
+/// Copies every entry of `dict` except those with an operator in `skip`.
+fn copy_entries(out: &mut Vec<u8>, dict: &[DictEntry], skip: &[u16]) {
+    for entry in dict.iter().filter(|e| !skip.contains(&e.op)) {
+        out.extend_from_slice(entry.raw);
+        push_entry(out, entry.op, &[]);
+    }
+}
+
+fn dict_values<'a>(dict: &'a [DictEntry], op: u16) -> Option<&'a [i32]> {
+    dict.iter()
+        .find(|e| e.op == op)
+        .map(|e| e.values.as_slice())
+}
+
+/// A Private DICT with its local subroutines, copied as one block so the
+/// Subrs offset (relative to the DICT) stays valid.
+fn private_block(cff: &[u8], size: i32, offset: i32) -> Option<(usize, Vec<u8>)> {
+    let (size, offset) = (usize::try_from(size).ok()?, usize::try_from(offset).ok()?);
+    let dict = parse_dict(cff.get(offset..offset + size)?)?;
+    let mut end = offset + size;
+    if let Some(&[subrs]) = dict_values(&dict, SUBRS) {
+        end = end.max(read_index(cff, offset + usize::try_from(subrs).ok()?)?.1);
+    }
+    Some((size, cff.get(offset..end)?.to_vec()))
+}
+
+/// Reads a charset as the SID/CID of each glyph after `.notdef`.
+fn read_charset(cff: &[u8], pos: usize, count: usize) -> Option<Vec<u16>> {
+    let mut ids = Vec::with_capacity(count);
+    let format = *cff.get(pos)?;
+    let mut at = pos + 1;
+    while ids.len() + 1 < count {
+        match format {
+            0 => {
+                ids.push(u16_at(cff, at)?);
+                at += 2;
+            }
+            1 | 2 => {
+                let first = u16_at(cff, at)?;
+                let left = if format == 1 {
+                    *cff.get(at + 2)? as u16
+                } else {
+                    u16_at(cff, at + 2)?
+                };
+                at += if format == 1 { 3 } else { 4 };
+                ids.extend((0..=left).map(|i| first.wrapping_add(i)));
+            }
+            _ => return None,
+        }
+    }
+    ids.truncate(count.saturating_sub(1));
+    Some(ids)
+}
+
+/// Reads an FDSelect as the Font DICT index of each glyph.
+fn read_fd_select(cff: &[u8], pos: usize, count: usize) -> Option<Vec<u8>> {
+    match *cff.get(pos)? {
+        0 => Some(cff.get(pos + 1..pos + 1 + count)?.to_vec()),
+        3 => {
+            let ranges = u16_at(cff, pos + 1)? as usize;
+            let mut fds = vec![0u8; count];
+            for r in 0..ranges {
+                let at = pos + 3 + 3 * r;
+                let first = u16_at(cff, at)? as usize;
+                let next = u16_at(cff, at + 3)? as usize;
+                let fd = *cff.get(at + 2)?;
+                fds.get_mut(first..next.min(count))?.fill(fd);
+            }
+            Some(fds)
+        }
+        _ => None,
+    }
+}
+
+/// Writes an FDSelect in format 3.
+fn write_fd_select(fds: &[u8]) -> Vec<u8> {
+    let mut ranges: Vec<(u16, u8)> = Vec::new();
+    for (gid, &fd) in fds.iter().enumerate() {
+        if ranges.last().is_none_or(|&(_, last)| last != fd) {
+            ranges.push((gid as u16, fd));
+        }
+    }
+    let mut out = vec![3];
+    out.extend_from_slice(&(ranges.len() as u16).to_be_bytes());
+    for (first, fd) in ranges {
+        out.extend_from_slice(&first.to_be_bytes());
+        out.push(fd);
+    }
+    out.extend_from_slice(&(fds.len() as u16).to_be_bytes());
+    out
+}
+
+/// Rebuilds a CFF table with the charstrings of the glyphs outside `keep`
+/// emptied and the glyph count cut to `count`.
+fn subset_cff(cff: &[u8], keep: &BTreeSet<u16>, count: u16) -> Option<Vec<u8>> {
+    let header_size = *cff.get(2)? as usize;
+    let (_, name_end) = read_index(cff, header_size)?;
+    let (top_dicts, top_end) = read_index(cff, name_end)?;
+    let (_, strings_end) = read_index(cff, top_end)?;
+    let (_, gsubrs_end) = read_index(cff, strings_end)?;
+    let top = parse_dict(top_dicts.first()?)?;
+    let offset_of = |op| match dict_values(&top, op) {
+        Some(&[v]) => usize::try_from(v).ok(),
+        _ => None,
+    };
+
+    let (charstrings, _) = read_index(cff, offset_of(CHARSTRINGS)?)?;
+    let new_charstrings = write_index(
+        &(0..count)
+            .map(|g| {
+                if keep.contains(&g) {
+                    charstrings.get(g as usize).copied()
+                } else {
+                    Some(EMPTY_CHARSTRING)
+                }
+            })
+            .collect::<Option<Vec<_>>>()?,
+    );
+
+    let cid_keyed = dict_values(&top, ROS).is_some();
+    // Predefined charsets (0-2) have no data and survive the cut as is.
+    let (charset_id, charset) = match offset_of(CHARSET).unwrap_or(0) {
+        _ if cid_keyed => {
+            let mut identity = vec![2];
+            if count > 1 {
+                identity.extend_from_slice(&1u16.to_be_bytes());
+                identity.extend_from_slice(&(count - 2).to_be_bytes());
+            }
+            (None, identity)
+        }
+        id @ 0..=2 => (Some(id), Vec::new()),
+        pos => {
+            let mut charset = vec![0];
+            for sid in read_charset(cff, pos, count as usize)? {
+                charset.extend_from_slice(&sid.to_be_bytes());
+            }
+            (None, charset)
+        }
+    };
+
+    // Private DICTs: one for name-keyed fonts, one per Font DICT otherwise.
+    let (fd_select, font_dicts, privates) = if cid_keyed {
+        let fds = read_fd_select(cff, offset_of(FD_SELECT)?, count as usize)?;
+        let (fd_items, _) = read_index(cff, offset_of(FD_ARRAY)?)?;
+        let mut font_dicts = Vec::new();
+        let mut privates = Vec::new();
+        for item in fd_items {
+            let dict = parse_dict(item)?;
+            let Some(&[size, offset]) = dict_values(&dict, PRIVATE) else {
+                return None;
+            };
+            privates.push(private_block(cff, size, offset)?);
+            font_dicts.push(dict);
+        }
+        (write_fd_select(&fds), font_dicts, privates)
+    } else {
+        let privates = match dict_values(&top, PRIVATE) {
+            Some(&[size, offset]) => vec![private_block(cff, size, offset)?],
+            _ => Vec::new(),
+        };
+        (Vec::new(), Vec::new(), privates)
+    };
+
+    let build_top = |charset_at: usize,
+                     fd_select_at: usize,
+                     charstrings_at: usize,
+                     fd_array_at: usize,
+                     private_at: &[usize]| {
+        let mut dict = Vec::new();
+        copy_entries(
+            &mut dict,
+            &top,
+            &[
+                CHARSET,
+                ENCODING,
+                CHARSTRINGS,
+                PRIVATE,
+                FD_ARRAY,
+                FD_SELECT,
+                CID_COUNT,
+            ],
+        );
+        push_entry(&mut dict, CHARSET, &[charset_id.unwrap_or(charset_at)]);
+        push_entry(&mut dict, CHARSTRINGS, &[charstrings_at]);
+        if cid_keyed {
+            push_entry(&mut dict, CID_COUNT, &[count as usize]);
+            push_entry(&mut dict, FD_ARRAY, &[fd_array_at]);
+            push_entry(&mut dict, FD_SELECT, &[fd_select_at]);
+        } else if let (Some((size, _)), Some(&at)) = (privates.first(), private_at.first()) {
+            push_entry(&mut dict, PRIVATE, &[*size, at]);
+        }
+        write_index(&[dict])
+    };
+    let build_fd_array = |private_at: &[usize]| {
+        let items: Vec<Vec<u8>> = font_dicts
+            .iter()
+            .zip(&privates)
+            .zip(private_at)
+            .map(|((dict, (size, _)), &at)| {
+                let mut item = Vec::new();
+                copy_entries(&mut item, dict, &[PRIVATE]);
+                push_entry(&mut item, PRIVATE, &[*size, at]);
+                item
+            })
+            .collect();
+        write_index(&items)
+    };
+
+    // Entries are fixed-size, so a dry run with zero offsets gives the layout.
+    let zeros = vec![0; privates.len()];
+    let mut at = gsubrs_end - top_end + name_end + build_top(0, 0, 0, 0, &zeros).len();
+    let charset_at = at;
+    at += charset.len();
+    let fd_select_at = at;
+    at += fd_select.len();
+    let charstrings_at = at;
+    at += new_charstrings.len();
+    let fd_array_at = at;
+    if cid_keyed {
+        at += build_fd_array(&zeros).len();
+    }
+    let private_at: Vec<usize> = privates
+        .iter()
+        .map(|(_, block)| {
+            let start = at;
+            at += block.len();
+            start
+        })
+        .collect();
+
+    let mut out = cff.get(..name_end)?.to_vec();
+    out.extend(build_top(
+        charset_at,
+        fd_select_at,
+        charstrings_at,
+        fd_array_at,
+        &private_at,
+    ));
+    out.extend_from_slice(cff.get(top_end..gsubrs_end)?);
+    out.extend(charset);
+    out.extend(fd_select);
+    out.extend(new_charstrings);
+    if cid_keyed {
+        out.extend(build_fd_array(&private_at));
+    }
+    for (_, block) in privates {
+        out.extend(block);
+    }
+    Some(out)
+}
+
+// ─── Variable font instances ────────────────────────────────────────────────
+
+/// Quadratic segments each cubic Bézier (CFF2 outlines) is approximated by.
+const CUBIC_SPLITS: usize = 4;
+
+/// Builds a static TrueType instance of a variable font at the axis
+/// coordinates in `variations`, holding the glyphs in `glyphs` plus
+/// `.notdef` under their original ids.
+///
+/// Outlines (`gvar` or CFF2 blends) and advances (`HVAR` or phantom points)
+/// are evaluated by ttf-parser; glyphs are written as simple, unhinted
+/// `glyf` outlines. Returns `None` for fonts that cannot be parsed or have
+/// no axis with one of the tags.
+pub(crate) fn instance_font(
+    font: &[u8],
+    variations: &[([u8; 4], f32)],
+    glyphs: &BTreeSet<u16>,
+) -> Option<Vec<u8>> {
+    let mut face = Face::parse(font, 0).ok()?;
+    for (tag, value) in variations {
+        face.set_variation(Tag::from_bytes(tag), *value)?;
+    }
+    let tables = read_tables(font)?;
+    let find = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).map(|(_, d)| *d);
+
+    let mut keep: BTreeSet<u16> = glyphs
+        .iter()
+        .copied()
+        .filter(|&g| g < face.number_of_glyphs())
+        .collect();
+    keep.insert(0);
+    let count = keep.last().map_or(1, |&g| g + 1);
+
+    let mut loca = Vec::with_capacity(4 * (count as usize + 1));
+    let mut glyf = Vec::new();
+    let mut hmtx = Vec::with_capacity(4 * count as usize);
+    let (mut max_points, mut max_contours, mut max_advance) = (0, 0, 0);
+    let mut bbox: Option<[i16; 4]> = None;
+    for gid in 0..count {
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+        let advance = face.glyph_hor_advance(GlyphId(gid)).unwrap_or(0);
+        let mut lsb = 0;
+        if keep.contains(&gid) {
+            let mut outline = Contours::default();
+            face.outline_glyph(GlyphId(gid), &mut outline);
+            if let Some((data, glyph_bbox)) = outline.encode() {
+                lsb = glyph_bbox[0];
+                max_points = max_points.max(outline.contours.iter().map(Vec::len).sum());
+                max_contours = max_contours.max(outline.contours.len());
+                bbox = Some(bbox.map_or(glyph_bbox, |b| {
+                    [
+                        b[0].min(glyph_bbox[0]),
+                        b[1].min(glyph_bbox[1]),
+                        b[2].max(glyph_bbox[2]),
+                        b[3].max(glyph_bbox[3]),
+                    ]
+                }));
+                glyf.extend(data);
+                glyf.resize(glyf.len().next_multiple_of(4), 0);
+            }
+        }
+        max_advance = max_advance.max(advance);
+        hmtx.extend_from_slice(&advance.to_be_bytes());
+        hmtx.extend_from_slice(&lsb.to_be_bytes());
+    }
+    loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+    let mut head = find(b"head")?.to_vec();
+    head.get_mut(8..12)?.fill(0);
+    if let Some(bbox) = bbox {
+        for (i, v) in bbox.iter().enumerate() {
+            set_u16(&mut head, 36 + 2 * i, *v as u16);
+        }
+    }
+    // Long loca; glyph data format 0.
+    set_u16(&mut head, 50, 1);
+    set_u16(&mut head, 52, 0);
+
+    let mut hhea = find(b"hhea")?.to_vec();
+    set_u16(&mut hhea, 10, max_advance);
+    set_u16(&mut hhea, 34, count);
+
+    // maxp version 1.0 (TrueType outlines), without hinting limits.
+    let mut maxp = Vec::with_capacity(32);
+    maxp.extend_from_slice(&0x0001_0000_u32.to_be_bytes());
+    for v in [count, max_points as u16, max_contours as u16] {
+        maxp.extend_from_slice(&v.to_be_bytes());
+    }
+    maxp.extend_from_slice(&[0, 0, 0, 0, 0, 2]);
+    maxp.resize(32, 0);
+
+    let mut out = vec![
+        (*b"head", head),
+        (*b"hhea", hhea),
+        (*b"maxp", maxp),
+        (*b"hmtx", hmtx),
+        (*b"loca", loca),
+        (*b"glyf", glyf),
+    ];
+    if let Some(os2) = find(b"OS/2") {
+        out.push((*b"OS/2", os2.to_vec()));
+    }
+    Some(write_sfnt([0, 1, 0, 0], out))
+}
+
+/// Glyph contours collected from ttf-parser as TrueType points
+/// `(x, y, on_curve)`, with cubic curves approximated by quadratic ones.
+#[derive(Default)]
+struct Contours {
+    contours: Vec<Vec<(f32, f32, bool)>>,
+}
+
+impl Contours {
+    fn current_point(&self) -> (f32, f32) {
+        self.contours
+            .last()
+            .and_then(|c| c.last())
+            .map_or((0.0, 0.0), |&(x, y, _)| (x, y))
+    }
+
+    fn push(&mut self, x: f32, y: f32, on_curve: bool) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push((x, y, on_curve));
+        }
+    }
+
+    /// Encodes the contours as a simple `glyf` glyph with word coordinates,
+    /// returning its data and bounding box `[xMin, yMin, xMax, yMax]`.
+    fn encode(&self) -> Option<(Vec<u8>, [i16; 4])> {
+        let points: Vec<(i16, i16, bool)> = self
+            .contours
+            .iter()
+            .flatten()
+            .map(|&(x, y, on)| (x.round() as i16, y.round() as i16, on))
+            .collect();
+        let bbox = points
+            .iter()
+            .fold(None, |b: Option<[i16; 4]>, &(x, y, _)| {
+                Some(b.map_or([x, y, x, y], |b| {
+                    [b[0].min(x), b[1].min(y), b[2].max(x), b[3].max(y)]
+                }))
+            })?;
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&(self.contours.len() as i16).to_be_bytes());
+        for v in bbox {
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        let mut end = 0;
+        for contour in &self.contours {
+            end += contour.len();
+            data.extend_from_slice(&(end as u16 - 1).to_be_bytes());
+        }
+        // No instructions.
+        data.extend_from_slice(&[0, 0]);
+        data.extend(points.iter().map(|&(_, _, on)| u8::from(on)));
+        let (mut prev_x, mut prev_y) = (0i16, 0i16);
+        for &(x, _, _) in &points {
+            data.extend_from_slice(&x.wrapping_sub(prev_x).to_be_bytes());
+            prev_x = x;
+        }
+        for &(_, y, _) in &points {
+            data.extend_from_slice(&y.wrapping_sub(prev_y).to_be_bytes());
+            prev_y = y;
+        }
+        Some((data, bbox))
+    }
+}
+
+impl OutlineBuilder for Contours {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(vec![(x, y, true)]);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(x, y, true);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.push(x1, y1, false);
+        self.push(x, y, true);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let p0 = self.current_point();
+        let cubic = |t: f32, a: f32, b: f32, c: f32, d: f32| {
+            let u = 1.0 - t;
+            u * u * u * a + 3.0 * u * u * t * b + 3.0 * u * t * t * c + t * t * t * d
+        };
+        let derivative = |t: f32, a: f32, b: f32, c: f32, d: f32| {
+            let u = 1.0 - t;
+            3.0 * (u * u * (b - a) + 2.0 * u * t * (c - b) + t * t * (d - c))
+        };
+        // Each piece of the cubic, with control points `start + d0 * step / 3`
+        // and `end - d1 * step / 3`, becomes the quadratic with the control
+        // point `(3 * (c1 + c2) - (start + end)) / 4`.
+        let step = 1.0 / CUBIC_SPLITS as f32;
+        for i in 0..CUBIC_SPLITS {
+            let (t0, t1) = (i as f32 * step, (i + 1) as f32 * step);
+            let start = (cubic(t0, p0.0, x1, x2, x), cubic(t0, p0.1, y1, y2, y));
+            let end = (cubic(t1, p0.0, x1, x2, x), cubic(t1, p0.1, y1, y2, y));
+            let d0 = (
+                derivative(t0, p0.0, x1, x2, x),
+                derivative(t0, p0.1, y1, y2, y),
+            );
+            let d1 = (
+                derivative(t1, p0.0, x1, x2, x),
+                derivative(t1, p0.1, y1, y2, y),
+            );
+            let control = (
+                (start.0 + end.0) / 2.0 + (d0.0 - d1.0) * step / 4.0,
+                (start.1 + end.1) / 2.0 + (d0.1 - d1.1) * step / 4.0,
+            );
+            self.push(control.0, control.1, false);
+            self.push(end.0, end.1, true);
+        }
+    }
+
+    fn close(&mut self) {
+        // ttf-parser repeats the start point when a contour ends on it.
+        if let Some(contour) = self.contours.last_mut()
+            && contour.len() > 1
+            && contour.first() == contour.last()
+        {
+            contour.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../assets/IosevkaTermSlab-Regular.ttf");
+
+    fn checksum(data: &[u8]) -> u32 {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    }
+
+    fn table<'a>(font: &'a [u8], tag: &[u8; 4]) -> &'a [u8] {
+        let tables = read_tables(font).expect("table directory");
+        tables.iter().find(|(t, _)| t == tag).expect("table").1
+    }
+
+    fn glyph(font: &[u8], gid: u16) -> &[u8] {
+        glyph_data(
+            table(font, b"head"),
+            table(font, b"loca"),
+            table(font, b"glyf"),
+            gid,
+        )
+        .expect("glyph")
+    }
+
+    fn gid(c: char) -> u16 {
+        Face::parse(FONT, 0)
+            .unwrap()
+            .glyph_index(c)
+            .expect("mapped")
+            .0
+    }
+
+    /// First composite glyph of the bundled font, with its components.
+    fn first_composite() -> (u16, Vec<u16>) {
+        let num_glyphs = u16_at(table(FONT, b"maxp"), 4).unwrap();
+        (0..num_glyphs)
+            .find_map(|g| {
+                let parts = components(glyph(FONT, g))?;
+                (!parts.is_empty()).then_some((g, parts))
+            })
+            .expect("composite glyph")
+    }
+
+    fn subset(glyphs: &[u16]) -> Vec<u8> {
+        subset_font(FONT, &glyphs.iter().copied().collect()).expect("subset")
+    }
+
+    /// Glyph `g` of `out` is the original outline, zero-padded to 4 bytes.
+    fn assert_kept(out: &[u8], g: u16) {
+        let original = glyph(FONT, g);
+        let copied = glyph(out, g);
+        assert_eq!(
+            copied.len(),
+            original.len().next_multiple_of(4),
+            "glyph {g}"
+        );
+        assert_eq!(&copied[..original.len()], original, "glyph {g}");
+        assert!(copied[original.len()..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn kept_glyphs_keep_their_outline_bytes() {
+        let kept = [gid('A'), gid('g'), gid('7')];
+        let out = subset(&kept);
+        for g in [0].into_iter().chain(kept) {
+            assert_kept(&out, g);
+        }
+    }
+
+    #[test]
+    fn dropped_glyphs_are_emptied() {
+        let (a, z) = (gid('A'), gid('Z'));
+        let out = subset(&[a.max(z)]);
+        assert!(glyph(&out, a.min(z)).is_empty());
+        assert_eq!(u16_at(table(&out, b"maxp"), 4), Some(a.max(z) + 1));
+    }
+
+    #[test]
+    fn loca_is_monotonic_and_in_bounds() {
+        let out = subset(&[gid('A'), gid('x'), first_composite().0]);
+        assert_eq!(u16_at(table(&out, b"head"), 50), Some(1));
+        let count = u16_at(table(&out, b"maxp"), 4).unwrap() as usize;
+        let loca = table(&out, b"loca");
+        assert_eq!(loca.len(), 4 * (count + 1));
+        let offsets: Vec<u32> = loca.chunks(4).map(|b| u32_at(b, 0).unwrap()).collect();
+        assert_eq!(offsets[0], 0);
+        assert!(offsets.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(offsets[count] as usize, table(&out, b"glyf").len());
+    }
+
+    #[test]
+    fn composite_components_are_retained() {
+        let (composite, parts) = first_composite();
+        let out = subset(&[composite]);
+        assert_kept(&out, composite);
+        for part in parts {
+            assert!(!glyph(&out, part).is_empty(), "component {part}");
+            assert_kept(&out, part);
+        }
+        let face = Face::parse(&out, 0).expect("subset parses");
+        assert!(face.glyph_bounding_box(GlyphId(composite)).is_some());
+    }
+
+    #[test]
+    fn table_checksums_are_valid() {
+        let out = subset(&[gid('A'), gid('b'), first_composite().0]);
+        let count = u16_at(&out, 4).unwrap() as usize;
+        for i in 0..count {
+            let record = 12 + 16 * i;
+            let tag = &out[record..record + 4];
+            let offset = u32_at(&out, record + 8).unwrap() as usize;
+            let length = u32_at(&out, record + 12).unwrap() as usize;
+            let mut data = out[offset..offset + length].to_vec();
+            if tag == b"head" {
+                // The table checksum is taken with checkSumAdjustment zeroed.
+                data[8..12].fill(0);
+            }
+            assert_eq!(
+                checksum(&data),
+                u32_at(&out, record + 4).unwrap(),
+                "{tag:?}"
+            );
+        }
+        assert_eq!(checksum(&out), 0xB1B0_AFBA);
+    }
+}
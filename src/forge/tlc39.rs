@@ -0,0 +1,89 @@
+//! TLC39 (`^BT`), the telecommunications composite of a Code 39 symbol
+//! holding the six digit ECI number and a MicroPDF417 holding the serial
+//! number and additional data.
+//!
+//! The two components are encoded by [`linear::code39`](super::linear::code39)
+//! and [`stacked::encode_micro_pdf417`](super::stacked::encode_micro_pdf417);
+//! this module splits the field data between them and places them: the
+//! MicroPDF417 sits above the Code 39, starting over its first bar, one
+//! MicroPDF417 row height apart.
+
+use crate::{ZplError, ZplResult};
+
+fn generation_error(message: impl std::fmt::Display) -> ZplError {
+    ZplError::BackendError(format!("Barcode Generation Error: {}", message))
+}
+
+/// `^BF` modes tried for the MicroPDF417 component, smallest first: the
+/// four-column sizes, starting with the four-row one (mode 33).
+pub(crate) const MICRO_MODES: [u32; 11] = [33, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32];
+
+/// Splits `^BT` field data into the ECI number and, when the seventh
+/// character is a comma, the data of the MicroPDF417 component.
+pub(crate) fn split(data: &str) -> ZplResult<(&str, Option<&str>)> {
+    let eci = data.get(..6).unwrap_or(data);
+    if eci.len() != 6 || !eci.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(generation_error(format!(
+            "TLC39 data must start with a six digit ECI number, got '{}'",
+            data
+        )));
+    }
+    let serial = data[6..].strip_prefix(',').filter(|s| !s.is_empty());
+    Ok((eci, serial))
+}
+
+/// Size and position, in dots, of the TLC39 components in the unrotated
+/// symbol.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Tlc39Layout {
+    /// Width of the Code 39 bars.
+    pub linear_width: u32,
+    /// Height of the Code 39 bars.
+    pub linear_height: u32,
+    /// Width and height of the MicroPDF417, if there is one.
+    pub micro_size: Option<(u32, u32)>,
+    /// Indent of the MicroPDF417 that lines it up with the first Code 39
+    /// bar, past the quiet zone.
+    pub micro_indent: u32,
+    /// Gap between the MicroPDF417 and the Code 39.
+    pub gap: u32,
+}
+
+impl Tlc39Layout {
+    fn size(&self) -> (u32, u32) {
+        match self.micro_size {
+            Some((w, h)) => (
+                self.linear_width.max(self.micro_indent + w),
+                h + self.gap + self.linear_height,
+            ),
+            None => (self.linear_width, self.linear_height),
+        }
+    }
+
+    /// Origins of the Code 39 and the MicroPDF417 for a symbol at `x`/`y`
+    /// in `orientation`, so that the rotated components keep their
+    /// arrangement inside the bounding box of the whole composite.
+    pub(crate) fn origins(
+        &self,
+        x: u32,
+        y: u32,
+        orientation: char,
+    ) -> ((u32, u32), Option<(u32, u32)>) {
+        let (full_w, full_h) = self.size();
+        let place = |ox: u32, oy: u32, w: u32, h: u32| {
+            let (dx, dy) = match orientation {
+                'R' => (full_h - (oy + h), ox),
+                'I' => (full_w - (ox + w), full_h - (oy + h)),
+                'B' => (oy, full_w - (ox + w)),
+                _ => (ox, oy),
+            };
+            (x + dx, y + dy)
+        };
+        let linear_y = self.micro_size.map_or(0, |(_, h)| h + self.gap);
+        let linear = place(0, linear_y, self.linear_width, self.linear_height);
+        let micro = self
+            .micro_size
+            .map(|(w, h)| place(self.micro_indent, 0, w, h));
+        (linear, micro)
+    }
+}
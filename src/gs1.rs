@@ -0,0 +1,535 @@
+//! GS1 Application Identifier (AI) data.
+//!
+//! GS1-128, GS1 DataBar, GS1 Data Matrix and GS1 QR Code symbols carry
+//! element strings: each AI is followed by its data, and FNC1 ends a field
+//! of variable length when another AI follows. In `^FD` they are written in
+//! bracketed notation, e.g. `(01)09501101530003(17)261231(10)ABC`.
+//!
+//! [`parse`] splits that notation into [`Element`]s, [`validate`] checks
+//! every field against the AI table (length, character set, check digit and
+//! date), and [`element_string`] builds the string the symbologies encode,
+//! with [`GS`] standing for FNC1.
+
+use crate::forge::check_digit;
+use crate::{ZplError, ZplResult};
+use Charset::{Cset39 as C39, Cset82 as X, Numeric as N};
+use Rule::{CheckDigit, Date, DateTime};
+
+/// ASCII group separator, standing for FNC1 in element strings.
+pub const GS: char = '\u{1d}';
+
+/// Application Identifiers whose data has a predefined length and needs no
+/// FNC1 separator after it, by their first two digits.
+const FIXED_LENGTH_AIS: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+/// An Application Identifier and its data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// The Application Identifier, two to four digits.
+    pub ai: String,
+    /// The data field.
+    pub value: String,
+}
+
+fn gs1_error(ai: Option<&str>, message: impl Into<String>) -> ZplError {
+    ZplError::InvalidGs1Data {
+        ai: ai.map(str::to_owned),
+        message: message.into(),
+    }
+}
+
+/// Whether the data of `ai` has a predefined length, so no FNC1 separator
+/// follows it.
+pub fn is_fixed_length(ai: &str) -> bool {
+    FIXED_LENGTH_AIS.contains(&ai.get(..2).unwrap_or(ai))
+}
+
+/// Splits data in bracketed notation into its elements.
+///
+/// Only the notation is checked here: every AI is two to four digits in
+/// parentheses and followed by data. A `(` inside the data starts the next
+/// element only when a known AI and `)` follow it, so data such as
+/// `(10)AB(C)` keeps its parentheses. See [`validate`] for the AI table.
+pub fn parse(data: &str) -> ZplResult<Vec<Element>> {
+    if !data.starts_with('(') {
+        return Err(gs1_error(
+            None,
+            format!("expected an AI in parentheses, got {:?}", data),
+        ));
+    }
+    let mut elements = Vec::new();
+    let mut rest = data;
+    while let Some(after) = rest.strip_prefix('(') {
+        let (ai, tail) = after
+            .split_once(')')
+            .ok_or_else(|| gs1_error(None, format!("unterminated AI in {:?}", data)))?;
+        if !(2..=4).contains(&ai.len()) || !ai.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(gs1_error(Some(ai), "an AI is two to four digits"));
+        }
+        let end = tail
+            .match_indices('(')
+            .map(|(i, _)| i)
+            .find(|&i| starts_with_ai(&tail[i + 1..]))
+            .unwrap_or(tail.len());
+        let value = &tail[..end];
+        if value.is_empty() {
+            return Err(gs1_error(Some(ai), "no data"));
+        }
+        elements.push(Element {
+            ai: ai.to_owned(),
+            value: value.to_owned(),
+        });
+        rest = &tail[end..];
+    }
+    Ok(elements)
+}
+
+/// Whether `text` starts with a known AI followed by `)`.
+fn starts_with_ai(text: &str) -> bool {
+    text.split_once(')')
+        .is_some_and(|(ai, _)| AI_FORMATS.iter().any(|f| f.covers(ai)))
+}
+
+/// Parses data in bracketed notation and checks every element against the
+/// AI table.
+pub fn validate(data: &str) -> ZplResult<Vec<Element>> {
+    let elements = parse(data)?;
+    for element in &elements {
+        let format = AI_FORMATS
+            .iter()
+            .find(|f| f.covers(&element.ai))
+            .ok_or_else(|| gs1_error(Some(&element.ai), "unknown AI"))?;
+        format.check(element)?;
+    }
+    Ok(elements)
+}
+
+/// Turns `^FD` data into an element string with [`GS`] for FNC1.
+///
+/// Data in bracketed notation gets separators after variable-length fields;
+/// anything else is taken as an element string already, without the
+/// leading FNC1.
+pub fn element_string(data: &str) -> ZplResult<String> {
+    if !data.starts_with('(') {
+        return Ok(data.trim_start_matches(GS).to_owned());
+    }
+    let elements = parse(data)?;
+    let mut out = String::new();
+    for (i, element) in elements.iter().enumerate() {
+        out.push_str(&element.ai);
+        out.push_str(&element.value);
+        if i + 1 < elements.len() && !is_fixed_length(&element.ai) {
+            out.push(GS);
+        }
+    }
+    Ok(out)
+}
+
+// ─── AI table ───────────────────────────────────────────────────────────────
+
+/// Characters allowed in the data of an AI.
+#[derive(Debug, Clone, Copy)]
+enum Charset {
+    /// Digits only.
+    Numeric,
+    /// GS1 AI encodable character set 82.
+    Cset82,
+    /// GS1 AI encodable character set 39.
+    Cset39,
+}
+
+impl Charset {
+    fn contains(self, c: char) -> bool {
+        match self {
+            Charset::Numeric => c.is_ascii_digit(),
+            Charset::Cset82 => c.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(c),
+            Charset::Cset39 => c.is_ascii_digit() || c.is_ascii_uppercase() || "#-/".contains(c),
+        }
+    }
+}
+
+/// Additional rule on the data of an AI.
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    None,
+    /// Characters `start..end` are digits ending in a GS1 Mod 10 check digit.
+    CheckDigit(usize, usize),
+    /// The data starts with a `YYMMDD` date; `true` allows day `00` (the
+    /// last day of the month).
+    Date(bool),
+    /// The data is a `YYMMDDHH` date and hour, optionally followed by
+    /// minutes and seconds.
+    DateTime,
+}
+
+/// Data format of a range of AIs.
+#[derive(Debug, Clone, Copy)]
+struct AiFormat {
+    /// First and last AI of the range, of equal length.
+    first: &'static str,
+    last: &'static str,
+    /// Leading characters that must be digits, ahead of `charset` data.
+    digits: usize,
+    charset: Charset,
+    /// Minimum and maximum data length.
+    min: usize,
+    max: usize,
+    rule: Rule,
+}
+
+const fn ai(
+    first: &'static str,
+    last: &'static str,
+    charset: Charset,
+    min: usize,
+    max: usize,
+) -> AiFormat {
+    AiFormat {
+        first,
+        last,
+        digits: 0,
+        charset,
+        min,
+        max,
+        rule: Rule::None,
+    }
+}
+
+impl AiFormat {
+    const fn digits(self, digits: usize) -> Self {
+        AiFormat { digits, ..self }
+    }
+
+    const fn rule(self, rule: Rule) -> Self {
+        AiFormat { rule, ..self }
+    }
+
+    fn covers(&self, ai: &str) -> bool {
+        ai.len() == self.first.len() && self.first <= ai && ai <= self.last
+    }
+
+    fn check(&self, element: &Element) -> ZplResult<()> {
+        let ai = Some(element.ai.as_str());
+        let value = &element.value;
+        let len = value.chars().count();
+        if len < self.min || len > self.max {
+            return Err(gs1_error(
+                ai,
+                if self.min == self.max {
+                    format!("data must be {} characters long, got {}", self.min, len)
+                } else {
+                    format!(
+                        "data must be {} to {} characters long, got {}",
+                        self.min, self.max, len
+                    )
+                },
+            ));
+        }
+        if let Some(c) = value
+            .chars()
+            .take(self.digits)
+            .find(|c| !c.is_ascii_digit())
+        {
+            return Err(gs1_error(
+                ai,
+                format!(
+                    "the first {} characters must be digits, got {:?}",
+                    self.digits, c
+                ),
+            ));
+        }
+        if let Some(c) = value
+            .chars()
+            .skip(self.digits)
+            .find(|&c| !self.charset.contains(c))
+        {
+            return Err(gs1_error(
+                ai,
+                match self.charset {
+                    Charset::Numeric => format!("data must be numeric, got {:?}", c),
+                    _ => format!("character {:?} is not allowed", c),
+                },
+            ));
+        }
+        match self.rule {
+            Rule::None => Ok(()),
+            Rule::CheckDigit(start, end) => {
+                let body = &value[start..end - 1];
+                let expected = check_digit::mod10(body);
+                let given = value[end - 1..end].chars().next().unwrap_or_default();
+                if given == expected {
+                    Ok(())
+                } else {
+                    Err(gs1_error(
+                        ai,
+                        format!("check digit is {}, expected {}", given, expected),
+                    ))
+                }
+            }
+            Rule::Date(zero_day) => {
+                if is_date(&value[..6], zero_day) {
+                    Ok(())
+                } else {
+                    Err(gs1_error(
+                        ai,
+                        format!("{} is not a YYMMDD date", &value[..6]),
+                    ))
+                }
+            }
+            Rule::DateTime => {
+                let valid = is_date(&value[..6], false)
+                    && value.as_bytes()[6..]
+                        .chunks(2)
+                        .zip([23, 59, 59])
+                        .all(|(pair, max)| {
+                            pair.len() == 2 && (pair[0] - b'0') * 10 + (pair[1] - b'0') <= max
+                        });
+                if valid {
+                    Ok(())
+                } else {
+                    Err(gs1_error(
+                        ai,
+                        format!("{} is not a YYMMDDHH[MM[SS]] date and time", value),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// Whether six digits form a valid `YYMMDD` date.
+fn is_date(digits: &str, zero_day: bool) -> bool {
+    let field = |i: usize| digits[i..i + 2].parse::<u32>().unwrap_or(99);
+    let (year, month, day) = (field(0), field(2), field(4));
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) => 29,
+        2 => 28,
+        _ => return false,
+    };
+    day <= days && (day > 0 || zero_day)
+}
+
+/// Data formats of the Application Identifiers, from the GS1 General
+/// Specifications.
+const AI_FORMATS: &[AiFormat] = &[
+    ai("00", "00", N, 18, 18).rule(CheckDigit(0, 18)),
+    ai("01", "02", N, 14, 14).rule(CheckDigit(0, 14)),
+    ai("10", "10", X, 1, 20),
+    ai("11", "13", N, 6, 6).rule(Date(true)),
+    ai("15", "17", N, 6, 6).rule(Date(true)),
+    ai("20", "20", N, 2, 2),
+    ai("21", "22", X, 1, 20),
+    ai("235", "235", X, 1, 28),
+    ai("240", "241", X, 1, 30),
+    ai("242", "242", N, 1, 6),
+    ai("243", "243", X, 1, 20),
+    ai("250", "251", X, 1, 30),
+    ai("253", "253", X, 13, 30)
+        .digits(13)
+        .rule(CheckDigit(0, 13)),
+    ai("254", "254", X, 1, 20),
+    ai("255", "255", N, 13, 25).rule(CheckDigit(0, 13)),
+    ai("30", "30", N, 1, 8),
+    ai("3100", "3169", N, 6, 6),
+    ai("3200", "3379", N, 6, 6),
+    ai("3400", "3579", N, 6, 6),
+    ai("3600", "3699", N, 6, 6),
+    ai("37", "37", N, 1, 8),
+    ai("3900", "3909", N, 1, 15),
+    ai("3910", "3919", N, 4, 18),
+    ai("3920", "3929", N, 1, 15),
+    ai("3930", "3939", N, 4, 18),
+    ai("3940", "3949", N, 4, 4),
+    ai("3950", "3959", N, 6, 6),
+    ai("400", "401", X, 1, 30),
+    ai("402", "402", N, 17, 17).rule(CheckDigit(0, 17)),
+    ai("403", "403", X, 1, 30),
+    ai("410", "417", N, 13, 13).rule(CheckDigit(0, 13)),
+    ai("420", "420", X, 1, 20),
+    ai("421", "421", X, 4, 12).digits(3),
+    ai("422", "422", N, 3, 3),
+    ai("423", "423", N, 3, 15),
+    ai("424", "424", N, 3, 3),
+    ai("425", "425", N, 3, 15),
+    ai("426", "426", N, 3, 3),
+    ai("427", "427", X, 1, 3),
+    ai("4300", "4301", X, 1, 35),
+    ai("4302", "4306", X, 1, 70),
+    ai("4307", "4307", X, 2, 2),
+    ai("4308", "4308", X, 1, 30),
+    ai("4309", "4309", N, 20, 20),
+    ai("4310", "4311", X, 1, 35),
+    ai("4312", "4316", X, 1, 70),
+    ai("4317", "4317", X, 2, 2),
+    ai("4318", "4318", X, 1, 20),
+    ai("4319", "4319", X, 1, 30),
+    ai("4320", "4320", X, 1, 35),
+    ai("4321", "4323", N, 1, 1),
+    ai("4324", "4325", N, 10, 10).rule(DateTime),
+    ai("4326", "4326", N, 6, 6).rule(Date(false)),
+    ai("7001", "7001", N, 13, 13),
+    ai("7002", "7002", X, 1, 30),
+    ai("7003", "7003", N, 10, 10).rule(DateTime),
+    ai("7004", "7004", N, 1, 4),
+    ai("7005", "7005", X, 1, 12),
+    ai("7006", "7006", N, 6, 6).rule(Date(false)),
+    ai("7007", "7007", N, 6, 12).rule(Date(false)),
+    ai("7008", "7008", X, 1, 3),
+    ai("7009", "7009", X, 1, 10),
+    ai("7010", "7010", X, 1, 2),
+    ai("7020", "7022", X, 1, 20),
+    ai("7023", "7023", X, 1, 30),
+    ai("7030", "7039", X, 4, 30).digits(3),
+    ai("710", "716", X, 1, 20),
+    ai("7230", "7239", X, 2, 30),
+    ai("7240", "7240", X, 1, 20),
+    ai("8001", "8001", N, 14, 14),
+    ai("8002", "8002", X, 1, 20),
+    ai("8003", "8003", X, 14, 30)
+        .digits(14)
+        .rule(CheckDigit(1, 14)),
+    ai("8004", "8004", X, 1, 30),
+    ai("8005", "8005", N, 6, 6),
+    ai("8006", "8006", N, 18, 18).rule(CheckDigit(0, 14)),
+    ai("8007", "8007", X, 1, 34),
+    ai("8008", "8008", N, 8, 12).rule(DateTime),
+    ai("8009", "8009", X, 1, 50),
+    ai("8010", "8010", C39, 1, 30),
+    ai("8011", "8011", N, 1, 12),
+    ai("8012", "8012", X, 1, 20),
+    ai("8013", "8013", X, 1, 25),
+    ai("8017", "8018", N, 18, 18).rule(CheckDigit(0, 18)),
+    ai("8019", "8019", N, 1, 10),
+    ai("8020", "8020", X, 1, 25),
+    ai("8026", "8026", N, 18, 18).rule(CheckDigit(0, 14)),
+    ai("8110", "8110", X, 1, 70),
+    ai("8111", "8111", N, 4, 4),
+    ai("8112", "8112", X, 1, 70),
+    ai("8200", "8200", X, 1, 70),
+    ai("90", "90", X, 1, 30),
+    ai("91", "99", X, 1, 90),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_of(result: ZplResult<Vec<Element>>) -> (Option<String>, String) {
+        match result {
+            Err(ZplError::InvalidGs1Data { ai, message }) => (ai, message),
+            other => panic!("expected a GS1 error, got {:?}", other),
+        }
+    }
+
+    fn pairs(elements: &[Element]) -> Vec<(&str, &str)> {
+        elements
+            .iter()
+            .map(|e| (e.ai.as_str(), e.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn fixed_and_variable_length_ais() {
+        assert!(is_fixed_length("00"));
+        assert!(is_fixed_length("01"));
+        assert!(is_fixed_length("17"));
+        assert!(is_fixed_length("3103"));
+        assert!(!is_fixed_length("10"));
+        assert!(!is_fixed_length("21"));
+        assert!(!is_fixed_length("400"));
+
+        let elements = validate("(01)09501101530003(17)261231(10)ABC123(21)S/N-1").unwrap();
+        assert_eq!(
+            pairs(&elements),
+            [
+                ("01", "09501101530003"),
+                ("17", "261231"),
+                ("10", "ABC123"),
+                ("21", "S/N-1")
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_length_data_must_have_its_length() {
+        let (ai, message) = error_of(validate("(01)0950110153000"));
+        assert_eq!(ai.as_deref(), Some("01"));
+        assert_eq!(message, "data must be 14 characters long, got 13");
+    }
+
+    #[test]
+    fn parentheses_in_data_only_split_at_known_ais() {
+        let elements = parse("(10)AB(C)(21)X(1)").unwrap();
+        assert_eq!(pairs(&elements), [("10", "AB(C)"), ("21", "X(1)")]);
+    }
+
+    #[test]
+    fn fnc1_follows_variable_length_fields_only() {
+        assert_eq!(
+            element_string("(01)09501101530003(10)ABC(17)261231(21)XYZ").unwrap(),
+            "0109501101530003\
+             10ABC\u{1d}\
+             17261231\
+             21XYZ"
+        );
+        // No separator after the last field, fixed or variable.
+        assert_eq!(element_string("(10)ABC").unwrap(), "10ABC");
+        assert_eq!(
+            element_string("(10)ABC(01)09501101530003").unwrap(),
+            "10ABC\u{1d}0109501101530003"
+        );
+        // Element strings pass through without their leading FNC1.
+        assert_eq!(
+            element_string("\u{1d}0109501101530003").unwrap(),
+            "0109501101530003"
+        );
+    }
+
+    #[test]
+    fn gtin_and_sscc_check_digits() {
+        assert!(validate("(01)09501101530003").is_ok());
+        let (ai, message) = error_of(validate("(01)09501101530004"));
+        assert_eq!(ai.as_deref(), Some("01"));
+        assert_eq!(message, "check digit is 4, expected 3");
+
+        assert!(validate("(00)106141412345678908").is_ok());
+        let (ai, message) = error_of(validate("(00)106141412345678907"));
+        assert_eq!(ai.as_deref(), Some("00"));
+        assert_eq!(message, "check digit is 7, expected 8");
+    }
+
+    #[test]
+    fn missing_data_is_an_error() {
+        let (ai, message) = error_of(parse("(10)"));
+        assert_eq!((ai.as_deref(), message.as_str()), (Some("10"), "no data"));
+        let (ai, message) = error_of(parse("(10)(01)09501101530003"));
+        assert_eq!((ai.as_deref(), message.as_str()), (Some("10"), "no data"));
+    }
+
+    #[test]
+    fn unknown_ais_are_rejected() {
+        let elements = parse("(05)123").unwrap();
+        assert_eq!(pairs(&elements), [("05", "123")]);
+        let (ai, message) = error_of(validate("(05)123"));
+        assert_eq!(
+            (ai.as_deref(), message.as_str()),
+            (Some("05"), "unknown AI")
+        );
+    }
+
+    #[test]
+    fn malformed_ais_are_rejected() {
+        let (_, message) = error_of(parse("0109501101530003"));
+        assert!(message.starts_with("expected an AI in parentheses"));
+        let (ai, message) = error_of(parse("(1)ABC"));
+        assert_eq!(ai.as_deref(), Some("1"));
+        assert_eq!(message, "an AI is two to four digits");
+        let (_, message) = error_of(parse("(01"));
+        assert!(message.starts_with("unterminated AI"));
+    }
+}